@@ -72,6 +72,7 @@ pub async fn run(config: Arc<Config>) -> anyhow::Result<Bound> {
 		.map(|ca| agent_hbone::pool::WorkloadHBONEPool::new(config.hbone.clone(), ca));
 	// Build metrics and then the upstream client with metrics wired in
 	let sub_registry = metrics::sub_registry(&mut registry);
+	mcp::security::wasm::register_metrics(sub_registry);
 	let tracer = trc::Tracer::new(&config.tracing)?.map(Arc::new);
 	let metrics_handle = Arc::new(crate::metrics::Metrics::new(
 		sub_registry,
@@ -83,6 +84,8 @@ pub async fn run(config: Arc<Config>) -> anyhow::Result<Bound> {
 		config.backend.clone(),
 		Some(metrics_handle.clone()),
 	);
+	#[cfg(feature = "wasm-guards")]
+	mcp::security::wasm::set_http_fetcher(Arc::new(client.clone()));
 
 	let (xds_tx, xds_rx) = tokio::sync::watch::channel(());
 	let state_mgr =
@@ -108,15 +111,8 @@ pub async fn run(config: Arc<Config>) -> anyhow::Result<Bound> {
 	)
 	.await
 	.context("admin server starts")?;
-	#[cfg(feature = "ui")]
-	admin_server.set_admin_handler(Arc::new(crate::ui::UiHandler::new(
-		config.clone(),
-		stores.guard_registry.clone(),
-	)));
-	#[cfg(feature = "ui")]
-	info!("serving UI at http://{}/ui", config.admin_addr);
 
-	let pi = ProxyInputs {
+	let pi = Arc::new(ProxyInputs {
 		cfg: config.clone(),
 		stores: stores.clone(),
 		tracer: tracer.clone(),
@@ -125,9 +121,18 @@ pub async fn run(config: Arc<Config>) -> anyhow::Result<Bound> {
 		ca,
 
 		mcp_state: mcp::App::new(stores.clone(), config.session_encoder.clone()),
-	};
+	});
+
+	#[cfg(feature = "ui")]
+	admin_server.set_admin_handler(Arc::new(crate::ui::UiHandler::new(
+		config.clone(),
+		stores.guard_registry.clone(),
+		pi.clone(),
+	)));
+	#[cfg(feature = "ui")]
+	info!("serving UI at http://{}/ui", config.admin_addr);
 
-	let gw = proxy::Gateway::new(Arc::new(pi), drain_rx.clone());
+	let gw = proxy::Gateway::new(pi, drain_rx.clone());
 
 	// Run the agentgateway in the data plane worker pool.
 	let mut xds_rx_for_proxy = xds_rx.clone();
@@ -243,6 +243,36 @@ pub fn parse_config(contents: String, filename: Option<PathBuf>) -> anyhow::Resu
 		termination_min_deadline,
 		threading_mode,
 		backend: raw.backend,
+		mcp_guard_concurrency: {
+			let default = crate::mcp::security::limiter::ConcurrencyLimit::default();
+			crate::mcp::security::limiter::ConcurrencyLimit {
+				max_concurrent: parse_default("MCP_GUARD_MAX_CONCURRENCY", default.max_concurrent)?,
+				queue_timeout: parse_duration("MCP_GUARD_QUEUE_TIMEOUT")?.unwrap_or(default.queue_timeout),
+				max_concurrent_per_guard: parse_default(
+					"MCP_GUARD_MAX_CONCURRENCY_PER_GUARD",
+					default.max_concurrent_per_guard,
+				)?,
+				max_concurrent_per_backend: parse_default(
+					"MCP_GUARD_MAX_CONCURRENCY_PER_BACKEND",
+					default.max_concurrent_per_backend,
+				)?,
+			}
+		},
+		mcp_guard_limits: {
+			let default = crate::mcp::security::GuardLimits::default();
+			crate::mcp::security::GuardLimits {
+				max_guards_per_backend: parse_default(
+					"MCP_GUARD_MAX_PER_BACKEND",
+					default.max_guards_per_backend,
+				)?,
+				max_custom_patterns_per_guard: parse_default(
+					"MCP_GUARD_MAX_CUSTOM_PATTERNS",
+					default.max_custom_patterns_per_guard,
+				)?,
+			}
+		},
+		mcp_guard_alert_window: parse_duration("MCP_GUARD_ALERT_WINDOW")?
+			.unwrap_or(crate::mcp::security::alerts::DEFAULT_ALERT_WINDOW),
 		admin_runtime_handle: None,
 		termination_max_deadline: match termination_max_deadline {
 			Some(period) => period,
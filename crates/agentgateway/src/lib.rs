@@ -398,6 +398,13 @@ pub struct Config {
 	pub admin_runtime_handle: Option<tokio::runtime::Handle>,
 
 	pub backend: BackendConfig,
+	/// Global limit on concurrent heavy (WASM) MCP guard evaluations
+	pub mcp_guard_concurrency: crate::mcp::security::limiter::ConcurrencyLimit,
+	/// Caps on the size of each MCP backend's guard configuration
+	pub mcp_guard_limits: crate::mcp::security::GuardLimits,
+	/// Window over which identical MCP guard deny alerts are collapsed into one
+	#[serde(with = "serde_dur")]
+	pub mcp_guard_alert_window: Duration,
 }
 
 impl Config {
@@ -0,0 +1,98 @@
+use crate::llm::policy::pii::pattern_recognizer::PatternRecognizer;
+use crate::llm::policy::pii::recognizer::Recognizer;
+use crate::llm::policy::pii::recognizer_result::RecognizerResult;
+
+/// Number of characters before an address that are searched for context words
+const CONTEXT_WINDOW: usize = 40;
+
+/// Score for an address preceded by an address context word
+const CONTEXT_SCORE: f32 = 0.7;
+
+const CONTEXT_WORDS: &[&str] = &[
+	"address",
+	"addr",
+	"live at",
+	"lives at",
+	"living at",
+	"located at",
+	"resides at",
+	"residence",
+	"ship to",
+	"deliver to",
+	"mailing",
+	"billing",
+];
+
+const STREET_TYPE: &str = r"(?i:street|st|avenue|ave|road|rd|boulevard|blvd|drive|dr|lane|ln|way|court|ct|place|pl|terrace|ter|circle|cir|parkway|pkwy|highway|hwy|square|sq|trail|trl)";
+
+const DIRECTION: &str = r"(?i:n|s|e|w|ne|nw|se|sw|north|south|east|west)";
+
+const UNIT: &str = r"(?i:apt|apartment|suite|ste|unit|floor|fl)";
+
+pub struct AddressRecognizer {
+	recognizer: PatternRecognizer,
+}
+
+impl AddressRecognizer {
+	pub fn new() -> Self {
+		let mut recognizer = PatternRecognizer::new(
+			"ADDRESS",
+			CONTEXT_WORDS.iter().map(|w| w.to_string()).collect(),
+		);
+		// House number, one to three capitalized (or ordinal) street name words and a
+		// street type keyword, followed by an optional direction, unit and
+		// "City, ST 12345" tail. Requiring the street type and capitalized names keeps
+		// phrases like "123 ideas" or "3 ideas for the road" out; the score stays
+		// moderate and is boosted when an address context word precedes the match.
+		recognizer.add_pattern(
+			"STREET_ADDRESS (medium)",
+			&format!(
+				concat!(
+					r"\b[0-9]{{1,6}}[A-Za-z]?\s+",
+					r"(?:(?:[A-Z][A-Za-z'.-]*|[0-9]{{1,3}}(?:st|nd|rd|th))\s+){{1,3}}",
+					r"{STREET_TYPE}\b\.?",
+					r"(?:\s+{DIRECTION}\b\.?)?",
+					r"(?:,?\s+(?:{UNIT}\.?\s*#?|#)\s*[A-Za-z0-9-]+)?",
+					r"(?:,\s*[A-Z][A-Za-z .'-]{{1,30}}?,?\s+[A-Z]{{2}}\s+[0-9]{{5}}(?:-[0-9]{{4}})?)?",
+				),
+				STREET_TYPE = STREET_TYPE,
+				DIRECTION = DIRECTION,
+				UNIT = UNIT,
+			),
+			0.45,
+		);
+
+		Self { recognizer }
+	}
+
+	/// Whether an address context word appears shortly before `start`
+	fn has_context(text: &str, start: usize) -> bool {
+		let prefix = &text[..start];
+		let window_start = prefix
+			.char_indices()
+			.rev()
+			.nth(CONTEXT_WINDOW - 1)
+			.map(|(i, _)| i)
+			.unwrap_or(0);
+		let window = prefix[window_start..].to_lowercase();
+		CONTEXT_WORDS.iter().any(|w| window.contains(w))
+	}
+}
+
+impl Recognizer for AddressRecognizer {
+	fn recognize(&self, text: &str) -> Vec<RecognizerResult> {
+		let mut results = self.recognizer.recognize(text);
+		for result in &mut results {
+			if result.score < CONTEXT_SCORE && Self::has_context(text, result.start) {
+				result.score = CONTEXT_SCORE;
+			}
+		}
+		results
+	}
+	fn name(&self) -> &str {
+		self.recognizer.name()
+	}
+	fn regexes(&self) -> Vec<&regex::Regex> {
+		self.recognizer.regexes()
+	}
+}
@@ -31,4 +31,7 @@ impl Recognizer for CaSinRecognizer {
 	fn name(&self) -> &str {
 		self.recognizer.name()
 	}
+	fn regexes(&self) -> Vec<&regex::Regex> {
+		self.recognizer.regexes()
+	}
 }
@@ -0,0 +1,149 @@
+use aws_lc_rs::digest::{SHA256, digest};
+
+use crate::llm::policy::pii::pattern_recognizer::PatternRecognizer;
+use crate::llm::policy::pii::recognizer::Recognizer;
+use crate::llm::policy::pii::recognizer_result::RecognizerResult;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+pub struct CryptoAddressRecognizer {
+	recognizer: PatternRecognizer,
+}
+
+impl CryptoAddressRecognizer {
+	pub fn new() -> Self {
+		let mut recognizer = PatternRecognizer::new(
+			"CRYPTO_ADDRESS",
+			vec![
+				"wallet".to_string(),
+				"bitcoin".to_string(),
+				"btc".to_string(),
+				"ethereum".to_string(),
+				"eth".to_string(),
+			],
+		);
+		// Bitcoin matches are only kept when their checksum validates, so score them high
+		recognizer.add_pattern("BTC_BASE58", r"\b[13][a-km-zA-HJ-NP-Z1-9]{25,34}\b", 0.9);
+		recognizer.add_pattern("BTC_BECH32", r"\b(?:bc|tb)1[02-9ac-hj-np-z]{11,71}\b", 0.9);
+		// EIP-55 checksums need Keccak-256, which is not available here; the shape alone
+		// is still distinctive enough for a medium score
+		recognizer.add_pattern("ETH", r"\b0x[0-9a-fA-F]{40}\b", 0.6);
+
+		Self { recognizer }
+	}
+}
+
+impl Recognizer for CryptoAddressRecognizer {
+	fn recognize(&self, text: &str) -> Vec<RecognizerResult> {
+		self
+			.recognizer
+			.recognize(text)
+			.into_iter()
+			.filter(|r| is_valid_address(&r.matched))
+			.collect()
+	}
+	fn name(&self) -> &str {
+		self.recognizer.name()
+	}
+	fn regexes(&self) -> Vec<&regex::Regex> {
+		self.recognizer.regexes()
+	}
+}
+
+fn is_valid_address(candidate: &str) -> bool {
+	if candidate.starts_with("0x") {
+		true
+	} else if candidate.starts_with("bc1") || candidate.starts_with("tb1") {
+		is_valid_bech32(candidate)
+	} else {
+		is_valid_base58check(candidate)
+	}
+}
+
+/// Validate a base58check P2PKH/P2SH address (version byte + 20-byte hash + 4-byte checksum)
+fn is_valid_base58check(candidate: &str) -> bool {
+	let Some(bytes) = decode_base58(candidate) else {
+		return false;
+	};
+	if bytes.len() != 25 {
+		return false;
+	}
+	let (payload, checksum) = bytes.split_at(21);
+	let hash = digest(&SHA256, digest(&SHA256, payload).as_ref());
+	&hash.as_ref()[..4] == checksum
+}
+
+fn decode_base58(input: &str) -> Option<Vec<u8>> {
+	let mut bytes: Vec<u8> = Vec::new();
+	for c in input.bytes() {
+		let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+		for b in bytes.iter_mut().rev() {
+			carry += (*b as u32) * 58;
+			*b = (carry & 0xff) as u8;
+			carry >>= 8;
+		}
+		while carry > 0 {
+			bytes.insert(0, (carry & 0xff) as u8);
+			carry >>= 8;
+		}
+	}
+	// Each leading '1' encodes a leading zero byte
+	let leading_zeros = input.bytes().take_while(|&c| c == b'1').count();
+	let mut decoded = vec![0u8; leading_zeros];
+	decoded.extend(bytes);
+	Some(decoded)
+}
+
+/// Validate a segwit address checksum (bech32 for v0, bech32m for v1+)
+fn is_valid_bech32(candidate: &str) -> bool {
+	let Some(sep) = candidate.rfind('1') else {
+		return false;
+	};
+	let (hrp, data) = (&candidate[..sep], &candidate[sep + 1..]);
+	if data.len() < 7 {
+		return false;
+	}
+	let Some(values) = data
+		.bytes()
+		.map(|c| BECH32_CHARSET.iter().position(|&a| a == c).map(|v| v as u8))
+		.collect::<Option<Vec<u8>>>()
+	else {
+		return false;
+	};
+
+	let mut expanded: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+	expanded.push(0);
+	expanded.extend(hrp.bytes().map(|c| c & 31));
+	expanded.extend(&values);
+
+	let expected = if values[0] == 0 {
+		BECH32_CONST
+	} else {
+		BECH32M_CONST
+	};
+	bech32_polymod(&expanded) == expected
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+	const GENERATOR: [u32; 5] = [
+		0x3b6a_57b2,
+		0x2650_8e6d,
+		0x1ea1_19fa,
+		0x3d42_33dd,
+		0x2a14_62b3,
+	];
+	let mut chk: u32 = 1;
+	for &v in values {
+		let top = chk >> 25;
+		chk = ((chk & 0x01ff_ffff) << 5) ^ v as u32;
+		for (i, g) in GENERATOR.iter().enumerate() {
+			if (top >> i) & 1 == 1 {
+				chk ^= g;
+			}
+		}
+	}
+	chk
+}
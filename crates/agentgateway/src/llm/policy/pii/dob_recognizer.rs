@@ -0,0 +1,115 @@
+use crate::llm::policy::pii::pattern_recognizer::PatternRecognizer;
+use crate::llm::policy::pii::recognizer::Recognizer;
+use crate::llm::policy::pii::recognizer_result::RecognizerResult;
+
+/// Number of characters before a date that are searched for context words
+const CONTEXT_WINDOW: usize = 40;
+
+/// Score for a date preceded by a birthdate context word
+const CONTEXT_SCORE: f32 = 0.85;
+
+const CONTEXT_WORDS: &[&str] = &[
+	"dob",
+	"d.o.b",
+	"born",
+	"date of birth",
+	"birth date",
+	"birthdate",
+	"birthday",
+];
+
+const MONTH: &str = r"(?:jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sep(?:t(?:ember)?)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?)";
+
+pub struct DobRecognizer {
+	recognizer: PatternRecognizer,
+}
+
+impl DobRecognizer {
+	pub fn new() -> Self {
+		let mut recognizer = PatternRecognizer::new(
+			"DATE_OF_BIRTH",
+			CONTEXT_WORDS.iter().map(|w| w.to_string()).collect(),
+		);
+		// Bare dates are very weak on their own: most dates are not birthdates.
+		// Their score is boosted when a context word precedes them.
+		recognizer.add_pattern(
+			"DATE_NUMERIC (very weak)",
+			r"\b(0?[1-9]|[12][0-9]|3[01])[/.-](0?[1-9]|[12][0-9]|3[01])[/.-](19|20)[0-9]{2}\b",
+			0.1,
+		);
+		recognizer.add_pattern(
+			"DATE_ISO (very weak)",
+			r"\b(19|20)[0-9]{2}-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])\b",
+			0.1,
+		);
+		recognizer.add_pattern(
+			"DATE_MONTH_FIRST (very weak)",
+			&format!(r"(?i)\b{MONTH}\.?\s+[0-9]{{1,2}}(?:st|nd|rd|th)?,?\s+(?:19|20)[0-9]{{2}}\b"),
+			0.1,
+		);
+		recognizer.add_pattern(
+			"DATE_DAY_FIRST (very weak)",
+			&format!(r"(?i)\b[0-9]{{1,2}}(?:st|nd|rd|th)?\s+{MONTH}\.?,?\s+(?:19|20)[0-9]{{2}}\b"),
+			0.1,
+		);
+		// Ages carry their own context in the pattern; matches inside a longer token
+		// (`max-age=300`) are dropped in `recognize`
+		recognizer.add_pattern(
+			"AGE_PREFIX (medium)",
+			r"(?i)\bage[d]?\s*[:=]?\s*[0-9]{1,3}\b",
+			0.6,
+		);
+		recognizer.add_pattern(
+			"AGE_SUFFIX (medium)",
+			r"(?i)\b[0-9]{1,3}[\s-]*(?:years?|yrs?)[\s-]*old\b",
+			0.6,
+		);
+
+		Self { recognizer }
+	}
+
+	/// Whether an age match is the tail of a longer token, such as the `max-age=300`
+	/// of a `Cache-Control` header or a `page_age` field
+	fn is_embedded_age(text: &str, result: &RecognizerResult) -> bool {
+		result
+			.matched
+			.get(..3)
+			.is_some_and(|p| p.eq_ignore_ascii_case("age"))
+			&& text[..result.start]
+				.chars()
+				.next_back()
+				.is_some_and(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+	}
+
+	/// Whether a birthdate context word appears shortly before `start`
+	fn has_context(text: &str, start: usize) -> bool {
+		let prefix = &text[..start];
+		let window_start = prefix
+			.char_indices()
+			.rev()
+			.nth(CONTEXT_WINDOW - 1)
+			.map(|(i, _)| i)
+			.unwrap_or(0);
+		let window = prefix[window_start..].to_lowercase();
+		CONTEXT_WORDS.iter().any(|w| window.contains(w))
+	}
+}
+
+impl Recognizer for DobRecognizer {
+	fn recognize(&self, text: &str) -> Vec<RecognizerResult> {
+		let mut results = self.recognizer.recognize(text);
+		results.retain(|r| !Self::is_embedded_age(text, r));
+		for result in &mut results {
+			if result.score < CONTEXT_SCORE && Self::has_context(text, result.start) {
+				result.score = CONTEXT_SCORE;
+			}
+		}
+		results
+	}
+	fn name(&self) -> &str {
+		self.recognizer.name()
+	}
+	fn regexes(&self) -> Vec<&regex::Regex> {
+		self.recognizer.regexes()
+	}
+}
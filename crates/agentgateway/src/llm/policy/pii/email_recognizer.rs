@@ -1,8 +1,85 @@
+use regex::Regex;
+
 use super::pattern_recognizer::PatternRecognizer;
 use super::recognizer::Recognizer;
+use super::recognizer_result::RecognizerResult;
+
+/// `at` / `dot` written out to slip past the email regex: bracketed (`[at]`, `(dot)`)
+/// or as bare words between spaces (`user at example dot com`)
+const OBFUSCATED_AT: &str = r"[ \t]*[\[\(\{<][ \t]*at[ \t]*[\]\)\}>][ \t]*|[ \t]+at[ \t]+";
+const OBFUSCATED_DOT: &str = r"[ \t]*[\[\(\{<][ \t]*dot[ \t]*[\]\)\}>][ \t]*|[ \t]+dot[ \t]+";
+
+/// Top-level domains accepted after a bare ` dot `. Bare words are common in prose
+/// ("look at the dot product"), so they only count when they end in a likely domain.
+const COMMON_TLDS: &[&str] = &[
+	"com", "org", "net", "edu", "gov", "io", "co", "us", "uk", "de", "fr", "ca", "au", "in", "info",
+	"biz", "me", "dev", "ai",
+];
+
+/// Bracketed obfuscation is a deliberate signal; bare words are weaker evidence
+const BRACKETED_SCORE: f32 = 0.6;
+const BARE_SCORE: f32 = 0.5;
+
+struct ObfuscatedEmail {
+	candidate: Regex,
+	at: Regex,
+	dot: Regex,
+	bracketed: Regex,
+}
+
+impl ObfuscatedEmail {
+	fn new() -> Self {
+		let candidate = format!(
+			r"(?i)\b[a-z0-9_.+-]+(?:@|{OBFUSCATED_AT})[a-z0-9-]+(?:(?:\.|{OBFUSCATED_DOT})[a-z0-9-]+)*(?:\.|{OBFUSCATED_DOT})[a-z]{{2,24}}\b"
+		);
+		Self {
+			candidate: Regex::new(&candidate).unwrap(),
+			at: Regex::new(&format!("(?i){OBFUSCATED_AT}")).unwrap(),
+			dot: Regex::new(&format!("(?i){OBFUSCATED_DOT}")).unwrap(),
+			bracketed: Regex::new(r"[\[\(\{<]").unwrap(),
+		}
+	}
+
+	fn recognize(&self, text: &str) -> Vec<RecognizerResult> {
+		self
+			.candidate
+			.find_iter(text)
+			.filter_map(|m| {
+				let email = self.normalize(m.as_str());
+				// Plain emails are left to the standard pattern
+				if email == m.as_str() {
+					return None;
+				}
+				let score = if self.bracketed.is_match(m.as_str()) {
+					BRACKETED_SCORE
+				} else {
+					let tld = email.rsplit('.').next().unwrap_or_default();
+					if !COMMON_TLDS.contains(&tld.to_ascii_lowercase().as_str()) {
+						return None;
+					}
+					BARE_SCORE
+				};
+				Some(RecognizerResult {
+					entity_type: "EMAIL_ADDRESS".to_string(),
+					matched: email,
+					start: m.start(),
+					end: m.end(),
+					score,
+				})
+			})
+			.collect()
+	}
+
+	/// The email an obfuscated candidate spells out
+	fn normalize(&self, candidate: &str) -> String {
+		let at = self.at.replace_all(candidate, "@");
+		self.dot.replace_all(&at, ".").into_owned()
+	}
+}
 
 pub struct EmailRecognizer {
 	recognizer: PatternRecognizer,
+	obfuscated: Option<ObfuscatedEmail>,
 }
 
 impl EmailRecognizer {
@@ -18,15 +95,37 @@ impl EmailRecognizer {
 		// Standard email regex (simplified, but robust for most cases)
 		let email_regex = r"[a-zA-Z0-9_.+-]+@[a-zA-Z0-9-]+\.[a-zA-Z0-9-.]+";
 		recognizer.add_pattern("Standard Email", email_regex, 0.85);
-		Self { recognizer }
+		Self {
+			recognizer,
+			obfuscated: None,
+		}
+	}
+
+	/// Also detect emails with `at` / `dot` spelled out, such as `user [at] example [dot] com`
+	/// or `user at example dot com`. Results carry the normalized address in `matched`
+	/// and a moderate score, below that of plain emails.
+	pub fn with_obfuscated(mut self) -> Self {
+		self.obfuscated = Some(ObfuscatedEmail::new());
+		self
 	}
 }
 
 impl Recognizer for EmailRecognizer {
 	fn recognize(&self, text: &str) -> Vec<super::recognizer_result::RecognizerResult> {
-		self.recognizer.recognize(text)
+		let mut results = self.recognizer.recognize(text);
+		if let Some(obfuscated) = &self.obfuscated {
+			results.extend(obfuscated.recognize(text));
+		}
+		results
 	}
 	fn name(&self) -> &str {
 		self.recognizer.name()
 	}
+	fn regexes(&self) -> Vec<&Regex> {
+		let mut regexes = self.recognizer.regexes();
+		if let Some(o) = &self.obfuscated {
+			regexes.extend([&o.candidate, &o.at, &o.dot, &o.bracketed]);
+		}
+		regexes
+	}
 }
@@ -1,18 +1,25 @@
 #![allow(dead_code)]
 
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
-use email_recognizer::EmailRecognizer;
+pub use email_recognizer::EmailRecognizer;
 use phone_recognizer::PhoneRecognizer;
 
+mod address_recognizer;
 mod ca_sin_recognizer;
 mod credit_card_recognizer;
+mod crypto_address_recognizer;
+mod dob_recognizer;
 mod email_recognizer;
 mod pattern_recognizer;
 mod phone_recognizer;
 pub mod recognizer;
 pub mod recognizer_result;
+mod uk_nino_recognizer;
 mod url_recognizer;
 mod us_ssn_recognizer;
 
@@ -21,7 +28,7 @@ pub use recognizer::Recognizer;
 pub use recognizer_result::RecognizerResult;
 
 /// PII types that can be detected using the LLM PII recognizers
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum PiiType {
@@ -37,6 +44,26 @@ pub enum PiiType {
 	CaSin,
 	/// URLs (http/https)
 	Url,
+	/// Dates of birth and ages
+	DateOfBirth,
+	/// UK National Insurance numbers
+	UkNino,
+	/// Cryptocurrency wallet addresses (Bitcoin, Ethereum)
+	CryptoAddress,
+	/// Physical street addresses
+	Address,
+}
+
+/// Sensitivity tier of a PII type, for policies that treat whole tiers alike
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PiiSensitivity {
+	/// Contact and location details: emails, phone numbers, URLs, dates of birth, addresses
+	Sensitive,
+	/// Government identifiers and financial data: SSNs, SINs, NINOs, card numbers,
+	/// crypto addresses
+	HighlySensitive,
 }
 
 impl PiiType {
@@ -49,9 +76,29 @@ impl PiiType {
 			PiiType::CreditCard,
 			PiiType::CaSin,
 			PiiType::Url,
+			PiiType::DateOfBirth,
+			PiiType::UkNino,
+			PiiType::CryptoAddress,
+			PiiType::Address,
 		]
 	}
 
+	/// Returns the sensitivity tier of this PII type
+	pub fn sensitivity(&self) -> PiiSensitivity {
+		match self {
+			PiiType::Ssn
+			| PiiType::CreditCard
+			| PiiType::CaSin
+			| PiiType::UkNino
+			| PiiType::CryptoAddress => PiiSensitivity::HighlySensitive,
+			PiiType::Email
+			| PiiType::PhoneNumber
+			| PiiType::Url
+			| PiiType::DateOfBirth
+			| PiiType::Address => PiiSensitivity::Sensitive,
+		}
+	}
+
 	/// Returns the recognizer for this PII type
 	pub fn recognizer(&self) -> &'static (dyn Recognizer + Sync + Send) {
 		match self {
@@ -61,6 +108,10 @@ impl PiiType {
 			PiiType::CreditCard => CC.as_ref(),
 			PiiType::CaSin => CA_SIN.as_ref(),
 			PiiType::Url => URL.as_ref(),
+			PiiType::DateOfBirth => DOB.as_ref(),
+			PiiType::UkNino => UK_NINO.as_ref(),
+			PiiType::CryptoAddress => CRYPTO_ADDRESS.as_ref(),
+			PiiType::Address => ADDRESS.as_ref(),
 		}
 	}
 }
@@ -83,6 +134,68 @@ pub static CA_SIN: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
 pub static URL: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
 	Lazy::new(|| Box::new(url_recognizer::UrlRecognizer::new()));
 
+pub static DOB: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
+	Lazy::new(|| Box::new(dob_recognizer::DobRecognizer::new()));
+
+pub static UK_NINO: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
+	Lazy::new(|| Box::new(uk_nino_recognizer::UkNinoRecognizer::new()));
+
+pub static CRYPTO_ADDRESS: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
+	Lazy::new(|| Box::new(crypto_address_recognizer::CryptoAddressRecognizer::new()));
+
+pub static ADDRESS: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
+	Lazy::new(|| Box::new(address_recognizer::AddressRecognizer::new()));
+
+/// A recognizer registered at runtime, shared between the registry and the guards using it
+pub type SharedRecognizer = Arc<dyn Recognizer + Sync + Send + 'static>;
+
+/// Recognizers registered by embedders, keyed by registered name
+static REGISTERED: Lazy<RwLock<HashMap<String, SharedRecognizer>>> = Lazy::new(Default::default);
+
+/// Register a recognizer under `name`, replacing any recognizer already registered under it.
+///
+/// Registered recognizers extend the built-in `PiiType`s with domain-specific formats
+/// (e.g. proprietary account numbers). PII guards opt into them by name and mask their
+/// results with the recognizer's entity type. Register before creating the guards that
+/// use them: guards resolve names when they are created.
+pub fn register_recognizer(name: impl Into<String>, recognizer: Box<dyn Recognizer + Sync + Send>) {
+	REGISTERED
+		.write()
+		.expect("recognizer registry lock poisoned")
+		.insert(name.into(), Arc::from(recognizer));
+}
+
+/// Remove a registered recognizer, returning whether it was registered.
+/// Guards created while it was registered keep using it.
+pub fn unregister_recognizer(name: &str) -> bool {
+	REGISTERED
+		.write()
+		.expect("recognizer registry lock poisoned")
+		.remove(name)
+		.is_some()
+}
+
+/// The recognizer registered under `name`, if any
+pub fn registered_recognizer(name: &str) -> Option<SharedRecognizer> {
+	REGISTERED
+		.read()
+		.expect("recognizer registry lock poisoned")
+		.get(name)
+		.cloned()
+}
+
+/// Names of all registered recognizers, sorted
+pub fn registered_recognizer_names() -> Vec<String> {
+	let mut names: Vec<String> = REGISTERED
+		.read()
+		.expect("recognizer registry lock poisoned")
+		.keys()
+		.cloned()
+		.collect();
+	names.sort();
+	names
+}
+
 #[allow(clippy::borrowed_box)]
 pub fn recognizer(
 	r: &Box<dyn Recognizer + Sync + Send + 'static>,
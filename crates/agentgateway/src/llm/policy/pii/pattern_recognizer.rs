@@ -79,6 +79,34 @@ impl Recognizer for PatternRecognizer {
 	fn name(&self) -> &str {
 		&self.entity_type
 	}
+	fn regexes(&self) -> Vec<&Regex> {
+		self.patterns.iter().map(|p| &p.regex).collect()
+	}
+}
+
+/// Delimiters used to group digits in formatted numbers (cards, SSNs, ...)
+const NUMBER_DELIMITERS: &[char] = &['-', ' ', '.'];
+
+/// Whether the number matched at `start..end` stands on its own rather than being
+/// a slice of a longer digit run.
+///
+/// `\b` is not enough for delimited formats: it matches between a digit and a `-`,
+/// so `4111-1111-1111-1111` is found inside `4111-1111-1111-1111-2222`. A match is
+/// rejected when it touches another digit, or when it touches one of the delimiters
+/// it uses internally and that delimiter continues into more digits.
+pub fn is_standalone_number(text: &str, start: usize, end: usize) -> bool {
+	let matched = &text[start..end];
+	let continues = |adjacent: Option<char>, next: Option<char>| match adjacent {
+		Some(c) if c.is_ascii_digit() => true,
+		Some(c) if NUMBER_DELIMITERS.contains(&c) && matched.contains(c) => {
+			next.is_some_and(|n| n.is_ascii_digit())
+		},
+		_ => false,
+	};
+
+	let mut before = text[..start].chars().rev();
+	let mut after = text[end..].chars();
+	!continues(before.next(), before.next()) && !continues(after.next(), after.next())
 }
 
 // Tests are now in the parent module's tests.rs file
@@ -5,6 +5,9 @@ use regex::Regex;
 use super::recognizer::Recognizer;
 use super::recognizer_result::RecognizerResult;
 
+static CANDIDATE_RE: Lazy<Regex> =
+	Lazy::new(|| Regex::new(r"(?i)(^|[^0-9])([+()]?[0-9][0-9\s().\-]{6,30})").unwrap());
+
 pub struct PhoneRecognizer {
 	regions: Vec<&'static str>,
 }
@@ -22,9 +25,6 @@ impl PhoneRecognizer {
 
 impl Recognizer for PhoneRecognizer {
 	fn recognize(&self, text: &str) -> Vec<RecognizerResult> {
-		static CANDIDATE_RE: Lazy<Regex> =
-			Lazy::new(|| Regex::new(r"(?i)(^|[^0-9])([+()]?[0-9][0-9\s().\-]{6,30})").unwrap());
-
 		// Map region strings once.
 		fn to_country(code: &str) -> Option<country::Id> {
 			match code {
@@ -95,4 +95,7 @@ impl Recognizer for PhoneRecognizer {
 	fn name(&self) -> &str {
 		"PHONE_NUMBER"
 	}
+	fn regexes(&self) -> Vec<&Regex> {
+		vec![&*CANDIDATE_RE]
+	}
 }
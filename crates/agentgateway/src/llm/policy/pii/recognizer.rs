@@ -1,6 +1,12 @@
+use regex::Regex;
+
 use super::recognizer_result::RecognizerResult;
 
 pub trait Recognizer {
 	fn recognize(&self, text: &str) -> Vec<RecognizerResult>;
 	fn name(&self) -> &str;
+	/// The compiled regexes this recognizer matches with, for reporting its footprint
+	fn regexes(&self) -> Vec<&Regex> {
+		Vec::new()
+	}
 }
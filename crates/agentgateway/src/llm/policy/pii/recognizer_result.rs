@@ -6,3 +6,49 @@ pub struct RecognizerResult {
 	pub end: usize,
 	pub score: f32,
 }
+
+impl RecognizerResult {
+	/// Resolve overlapping results, as returned by several recognizers scanning the same
+	/// text, into non-overlapping ones sorted by position.
+	///
+	/// Where results overlap the highest score wins, then the longest span, then the
+	/// earliest start and entity type, so the outcome does not depend on the order the
+	/// recognizers ran in. This keeps e.g. a URL match on "example.com" from beating an
+	/// email match on "user@example.com".
+	pub fn merge_overlapping(results: Vec<RecognizerResult>) -> Vec<RecognizerResult> {
+		let mut sorted = results;
+		sorted.sort_by(|a, b| {
+			b.score
+				.partial_cmp(&a.score)
+				.unwrap_or(std::cmp::Ordering::Equal)
+				.then_with(|| b.len().cmp(&a.len()))
+				.then_with(|| a.start.cmp(&b.start))
+				.then_with(|| a.entity_type.cmp(&b.entity_type))
+		});
+
+		// Greedily keep results that don't overlap one already kept
+		let mut merged: Vec<RecognizerResult> = Vec::new();
+		for result in sorted {
+			if !merged.iter().any(|kept| kept.overlaps(&result)) {
+				merged.push(result);
+			}
+		}
+		merged.sort_by_key(|r| r.start);
+		merged
+	}
+
+	/// Length of the matched span in bytes
+	pub fn len(&self) -> usize {
+		self.end.saturating_sub(self.start)
+	}
+
+	/// Whether the matched span is empty
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Whether the spans of the two results share any byte
+	pub fn overlaps(&self, other: &RecognizerResult) -> bool {
+		self.start < other.end && other.start < self.end
+	}
+}
@@ -44,6 +44,70 @@ fn test_email_recognizer() {
 	assert!(results[1].score > 0.0);
 }
 
+#[test]
+fn test_obfuscated_email_recognizer() {
+	let recognizer = EmailRecognizer::new().with_obfuscated();
+
+	for (text, email) in [
+		(
+			"Send it to john [at] example [dot] com today",
+			"john@example.com",
+		),
+		(
+			"reach me: jane.doe(at)mail(dot)co(dot)uk",
+			"jane.doe@mail.co.uk",
+		),
+		("ops {AT} corp {DOT} io", "ops@corp.io"),
+		("contact admin@internal [dot] net", "admin@internal.net"),
+		("write to bob at example dot com please", "bob@example.com"),
+	] {
+		let results = recognizer.recognize(text);
+		assert_eq!(results.len(), 1, "{text}: {results:?}");
+		assert_eq!(results[0].matched, email);
+		assert_eq!(results[0].entity_type, "EMAIL_ADDRESS");
+		assert!(results[0].score < 0.85);
+	}
+
+	// The span covers the obfuscated text, so masking replaces all of it
+	let text = "Send it to john [at] example [dot] com today";
+	let results = recognizer.recognize(text);
+	assert_eq!(
+		&text[results[0].start..results[0].end],
+		"john [at] example [dot] com"
+	);
+
+	// Bracketed forms are stronger evidence than bare words
+	let bracketed = recognizer.recognize("john [at] example [dot] com");
+	let bare = recognizer.recognize("john at example dot com");
+	assert!(bracketed[0].score > bare[0].score);
+
+	// Plain emails are reported once, by the standard pattern
+	let results = recognizer.recognize("Contact test@example.com");
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0].score, 0.85);
+
+	// Ordinary prose using "at" and "dot" is left alone
+	for text in [
+		"Let's meet at noon",
+		"The dot product is computed at runtime",
+		"At the end of the day, dot your i's",
+		"We arrived at home dot on time",
+		"Look at the code dot by dot",
+	] {
+		assert!(
+			recognizer.recognize(text).is_empty(),
+			"Expected '{text}' to be ignored"
+		);
+	}
+
+	// Off unless enabled
+	assert!(
+		EmailRecognizer::new()
+			.recognize("john [at] example [dot] com")
+			.is_empty()
+	);
+}
+
 #[test]
 fn test_phone_recognizer() {
 	let recognizer = PhoneRecognizer::new();
@@ -290,3 +354,300 @@ fn test_multiple_recognizers() {
 		"Expected at least 5 total matches, got {total_results}"
 	);
 }
+
+#[test]
+fn test_dob_recognizer_with_context() {
+	let recognizer = dob_recognizer::DobRecognizer::new();
+
+	for text in [
+		"DOB: 01/02/1990",
+		"Patient date of birth 1990-02-01",
+		"She was born on March 3rd, 1985",
+	] {
+		let results = recognizer.recognize(text);
+		assert_eq!(results.len(), 1, "Expected one match in '{text}'");
+		assert_eq!(results[0].entity_type, "DATE_OF_BIRTH");
+		assert!(
+			results[0].score >= 0.8,
+			"Expected high confidence for '{text}', got {}",
+			results[0].score
+		);
+	}
+
+	let results = recognizer.recognize("DOB: 01/02/1990");
+	assert_eq!(results[0].matched, "01/02/1990");
+}
+
+#[test]
+fn test_dob_recognizer_without_context() {
+	let recognizer = dob_recognizer::DobRecognizer::new();
+
+	// A random calendar date is still found, but with low confidence
+	let results = recognizer.recognize("The invoice was issued on 15/06/2021 and paid in full.");
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0].matched, "15/06/2021");
+	assert!(results[0].score < 0.3, "got {}", results[0].score);
+
+	// Context far away from the date does not boost it
+	let text = format!(
+		"DOB fields are optional.{} Meeting on 2024-01-15",
+		" ".repeat(60)
+	);
+	let results = recognizer.recognize(&text);
+	assert_eq!(results.len(), 1);
+	assert!(results[0].score < 0.3);
+}
+
+#[test]
+fn test_dob_recognizer_ages() {
+	let recognizer = dob_recognizer::DobRecognizer::new();
+
+	for text in ["Age: 42", "the patient, aged 67,", "a 35-year-old male"] {
+		let results = recognizer.recognize(text);
+		assert!(!results.is_empty(), "Expected an age match in '{text}'");
+		assert!(results[0].score >= 0.5);
+	}
+	assert!(recognizer.recognize("see page 42").is_empty());
+	for text in [
+		"Cache-Control: public, max-age=300",
+		"Strict-Transport-Security: max-age=31536000; includeSubDomains",
+		"{\"cache_age\": 120}",
+	] {
+		assert!(
+			recognizer.recognize(text).is_empty(),
+			"Unexpected age match in '{text}'"
+		);
+	}
+}
+
+#[test]
+fn test_uk_nino_recognizer() {
+	let recognizer = uk_nino_recognizer::UkNinoRecognizer::new();
+
+	// Structurally valid, compact and grouped
+	for text in [
+		"NI number: AB123456C",
+		"NINO QQ is invalid but JG 10 37 42 B is fine",
+	] {
+		let results = recognizer.recognize(text);
+		assert_eq!(results.len(), 1, "Expected one match in '{text}'");
+		assert_eq!(results[0].entity_type, "UK_NINO");
+		assert!(results[0].score > 0.0);
+	}
+	assert_eq!(
+		recognizer.recognize("NI number: AB123456C")[0].matched,
+		"AB123456C"
+	);
+
+	// Invalid prefix pairs: unallocated combinations and excluded letters
+	for text in [
+		"GB123456A",
+		"NT123456A",
+		"ZZ123456A",
+		"DA123456A",
+		"AO123456A",
+	] {
+		assert!(
+			recognizer.recognize(text).is_empty(),
+			"Expected '{text}' to be rejected"
+		);
+	}
+
+	// Invalid suffix and random alphanumeric strings
+	assert!(recognizer.recognize("AB123456E").is_empty());
+	assert!(recognizer.recognize("X7K2P9QZ4M1").is_empty());
+	assert!(recognizer.recognize("order ref AB12345C").is_empty());
+}
+
+#[test]
+fn test_crypto_address_recognizer() {
+	let recognizer = crypto_address_recognizer::CryptoAddressRecognizer::new();
+
+	for address in [
+		// Bitcoin P2PKH, P2SH, bech32 (v0) and bech32m (v1)
+		"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+		"3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy",
+		"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+		"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+		// Ethereum
+		"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+	] {
+		let text = format!("Send funds to wallet {address} today");
+		let results = recognizer.recognize(&text);
+		assert_eq!(results.len(), 1, "Expected '{address}' to be detected");
+		assert_eq!(results[0].matched, address);
+		assert_eq!(results[0].entity_type, "CRYPTO_ADDRESS");
+	}
+
+	// Near misses: one character off breaks the checksum
+	for near_miss in [
+		"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb",
+		"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+		// Too short for an Ethereum address
+		"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA",
+	] {
+		assert!(
+			recognizer.recognize(near_miss).is_empty(),
+			"Expected '{near_miss}' to be rejected"
+		);
+	}
+}
+
+#[test]
+fn test_credit_card_recognizer_boundaries() {
+	let recognizer = credit_card_recognizer::CreditCardRecognizer::new();
+
+	let cases = [
+		// Standalone cards in the usual formats
+		("4111111111111111", Some("4111111111111111")),
+		("4111 1111 1111 1111", Some("4111 1111 1111 1111")),
+		("4111-1111-1111-1111", Some("4111-1111-1111-1111")),
+		(
+			"Card: 4111 1111 1111 1111, exp 12/29",
+			Some("4111 1111 1111 1111"),
+		),
+		("(4111-1111-1111-1111)", Some("4111-1111-1111-1111")),
+		(
+			"paid in 2024 with 4111111111111111.",
+			Some("4111111111111111"),
+		),
+		// Embedded in longer digit runs
+		("41111111111111112222", None),
+		("99994111111111111111", None),
+		("4111-1111-1111-1111-2222", None),
+		("9999-4111-1111-1111-1111", None),
+		("4111 1111 1111 1111 2222", None),
+		("9999 4111 1111 1111 1111", None),
+	];
+
+	for (text, expected) in cases {
+		let results = recognizer.recognize(text);
+		let matched: Vec<&str> = results.iter().map(|r| r.matched.as_str()).collect();
+		match expected {
+			Some(card) => assert_eq!(matched, vec![card], "in '{text}'"),
+			None => assert!(matched.is_empty(), "unexpected {matched:?} in '{text}'"),
+		}
+	}
+}
+
+#[test]
+fn test_ssn_recognizer_boundaries() {
+	let recognizer = us_ssn_recognizer::UsSsnRecognizer::new();
+
+	let cases = [
+		("SSN: 123-45-6789", true),
+		("SSN 123 45 6789.", true),
+		("ssn=123.45.6789", true),
+		// Embedded in longer numbers
+		("9123-45-6789", false),
+		("123-45-67890", false),
+		("555-123-45-6789", false),
+		("123-45-6789-01", false),
+		("ref 1234567890", false),
+	];
+
+	for (text, should_match) in cases {
+		let results = recognizer.recognize(text);
+		assert_eq!(
+			!results.is_empty(),
+			should_match,
+			"'{text}' produced {:?}",
+			results.iter().map(|r| &r.matched).collect::<Vec<_>>()
+		);
+	}
+}
+
+#[test]
+fn test_address_recognizer() {
+	let recognizer = address_recognizer::AddressRecognizer::new();
+
+	let text = "Ship to 350 5th Ave, Suite 3400, New York, NY 10118 by Friday";
+	let results = recognizer.recognize(text);
+	assert_eq!(results.len(), 1);
+	assert_eq!(
+		results[0].matched,
+		"350 5th Ave, Suite 3400, New York, NY 10118"
+	);
+	assert_eq!(results[0].entity_type, "ADDRESS");
+	// "Ship to" boosts the score
+	assert!(results[0].score >= 0.7);
+
+	// Without context the score stays moderate
+	let results = recognizer.recognize("Meet at 1600 Pennsylvania Avenue NW tomorrow");
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0].matched, "1600 Pennsylvania Avenue NW");
+	assert!(results[0].score < 0.7);
+
+	// A number followed by ordinary words is not an address
+	for text in [
+		"I have 123 ideas",
+		"3 ideas for the road",
+		"We sold 12 Big Red Dogs",
+	] {
+		assert!(
+			recognizer.recognize(text).is_empty(),
+			"Expected '{text}' to be rejected"
+		);
+	}
+}
+
+fn result(entity_type: &str, start: usize, end: usize, score: f32) -> RecognizerResult {
+	RecognizerResult {
+		entity_type: entity_type.to_string(),
+		matched: String::new(),
+		start,
+		end,
+		score,
+	}
+}
+
+#[test]
+fn test_merge_overlapping_different_types() {
+	// "mail user@example.com": the email beats the URL on its domain by score
+	let merged = RecognizerResult::merge_overlapping(vec![
+		result("URL", 10, 21, 0.5),
+		result("EMAIL_ADDRESS", 5, 21, 0.85),
+		result("PHONE_NUMBER", 30, 42, 0.4),
+	]);
+	assert_eq!(
+		merged,
+		vec![
+			result("EMAIL_ADDRESS", 5, 21, 0.85),
+			result("PHONE_NUMBER", 30, 42, 0.4),
+		]
+	);
+}
+
+#[test]
+fn test_merge_overlapping_same_type() {
+	// Equal scores: the longest span wins, and adjacent spans don't overlap
+	let merged = RecognizerResult::merge_overlapping(vec![
+		result("PHONE_NUMBER", 4, 12, 0.6),
+		result("PHONE_NUMBER", 0, 12, 0.6),
+		result("PHONE_NUMBER", 12, 20, 0.6),
+	]);
+	assert_eq!(
+		merged,
+		vec![
+			result("PHONE_NUMBER", 0, 12, 0.6),
+			result("PHONE_NUMBER", 12, 20, 0.6),
+		]
+	);
+
+	// The outcome does not depend on input order
+	let forward = vec![
+		result("SSN", 0, 11, 0.7),
+		result("SSN", 0, 11, 0.7),
+		result("SSN", 3, 14, 0.9),
+	];
+	let mut backward = forward.clone();
+	backward.reverse();
+	assert_eq!(
+		RecognizerResult::merge_overlapping(forward),
+		RecognizerResult::merge_overlapping(backward)
+	);
+	assert_eq!(
+		RecognizerResult::merge_overlapping(vec![result("SSN", 0, 11, 0.7), result("SSN", 3, 14, 0.9)]),
+		vec![result("SSN", 3, 14, 0.9)]
+	);
+}
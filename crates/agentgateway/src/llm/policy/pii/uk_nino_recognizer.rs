@@ -0,0 +1,49 @@
+use crate::llm::policy::pii::pattern_recognizer::PatternRecognizer;
+use crate::llm::policy::pii::recognizer::Recognizer;
+use crate::llm::policy::pii::recognizer_result::RecognizerResult;
+
+/// Prefix pairs that are never allocated, even though both letters are individually valid
+const INVALID_PREFIXES: &[&str] = &["BG", "GB", "KN", "NK", "NT", "TN", "ZZ"];
+
+pub struct UkNinoRecognizer {
+	recognizer: PatternRecognizer,
+}
+
+impl UkNinoRecognizer {
+	pub fn new() -> Self {
+		let mut recognizer = PatternRecognizer::new(
+			"UK_NINO",
+			vec![
+				"national insurance".to_string(),
+				"nino".to_string(),
+				"ni number".to_string(),
+			],
+		);
+		// First letter excludes D, F, I, Q, U, V; second letter additionally excludes O.
+		// Six digits (optionally grouped in pairs) and a suffix letter A-D.
+		recognizer.add_pattern(
+			"UK_NINO",
+			r"\b[A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?[0-9]{2} ?[0-9]{2} ?[0-9]{2} ?[A-D]\b",
+			0.6,
+		);
+
+		Self { recognizer }
+	}
+}
+
+impl Recognizer for UkNinoRecognizer {
+	fn recognize(&self, text: &str) -> Vec<RecognizerResult> {
+		self
+			.recognizer
+			.recognize(text)
+			.into_iter()
+			.filter(|r| !INVALID_PREFIXES.contains(&&r.matched[..2]))
+			.collect()
+	}
+	fn name(&self) -> &str {
+		self.recognizer.name()
+	}
+	fn regexes(&self) -> Vec<&regex::Regex> {
+		self.recognizer.regexes()
+	}
+}
@@ -39,4 +39,7 @@ impl Recognizer for UrlRecognizer {
 	fn name(&self) -> &str {
 		self.recognizer.name()
 	}
+	fn regexes(&self) -> Vec<&regex::Regex> {
+		self.recognizer.regexes()
+	}
 }
@@ -1,4 +1,4 @@
-use crate::llm::policy::pii::pattern_recognizer::PatternRecognizer;
+use crate::llm::policy::pii::pattern_recognizer::{PatternRecognizer, is_standalone_number};
 use crate::llm::policy::pii::recognizer::Recognizer;
 
 pub struct UsSsnRecognizer {
@@ -37,9 +37,17 @@ impl UsSsnRecognizer {
 
 impl Recognizer for UsSsnRecognizer {
 	fn recognize(&self, text: &str) -> Vec<super::recognizer_result::RecognizerResult> {
-		self.recognizer.recognize(text)
+		self
+			.recognizer
+			.recognize(text)
+			.into_iter()
+			.filter(|r| is_standalone_number(text, r.start, r.end))
+			.collect()
 	}
 	fn name(&self) -> &str {
 		self.recognizer.name()
 	}
+	fn regexes(&self) -> Vec<&regex::Regex> {
+		self.recognizer.regexes()
+	}
 }
@@ -10,7 +10,7 @@ use secrecy::SecretString;
 
 use crate::http::auth::BackendAuth;
 use crate::http::authorization::{PolicySet, RuleSet};
-use crate::mcp::McpAuthorization;
+use crate::mcp::{DryRunDecision, McpAuthorization};
 use crate::test_helpers::proxymock::{
 	BIND_KEY, TestBind, basic_named_route, basic_route, setup_proxy_test, simple_bind,
 };
@@ -172,6 +172,161 @@ async fn stream_to_stream_single_tls() {
 	);
 }
 
+/// Test that a tool-invoke guard's masked arguments, not the originals, reach the upstream
+#[tokio::test]
+async fn guard_masked_arguments_forwarded_upstream() {
+	let mock = mock_streamable_http_server(true).await;
+	// Only the request is guarded, so the echoed response shows what the upstream received
+	let guards = serde_yaml::from_str(
+		r#"
+- id: pii
+  type: pii
+  runs_on: [tool_invoke]
+  detect: [email]
+  mask_arguments: true
+"#,
+	)
+	.unwrap();
+	let t = setup_proxy_test("{}")
+		.unwrap()
+		.with_mcp_backend_guards(mock.addr, true, false, guards)
+		.with_bind(simple_bind(basic_route(mock.addr)));
+	let io = t.serve_real_listener(BIND_KEY).await;
+	let client = mcp_streamable_client(io).await;
+
+	let ctr = client
+		.call_tool(rmcp::model::CallToolRequestParams {
+			meta: None,
+			task: None,
+			name: "echo".into(),
+			arguments: serde_json::json!({"to": "jane.doe@example.com", "hi": "world"})
+				.as_object()
+				.cloned(),
+		})
+		.await
+		.unwrap();
+	let received: serde_json::Value =
+		serde_json::from_str(&ctr.content[0].raw.as_text().unwrap().text).unwrap();
+	assert_eq!(
+		received,
+		serde_json::json!({"to": "<EMAIL_ADDRESS>", "hi": "world"})
+	);
+}
+
+/// Test that a PII guard in mask mode redacts tool descriptions in the listed tools
+#[tokio::test]
+async fn guard_masks_pii_in_tool_descriptions() {
+	struct GreetingRecognizer;
+	impl crate::llm::policy::pii::Recognizer for GreetingRecognizer {
+		fn recognize(&self, text: &str) -> Vec<crate::llm::policy::pii::RecognizerResult> {
+			text
+				.match_indices("hello")
+				.map(|(start, m)| crate::llm::policy::pii::RecognizerResult {
+					entity_type: "GREETING".to_string(),
+					matched: m.to_string(),
+					start,
+					end: start + m.len(),
+					score: 0.9,
+				})
+				.collect()
+		}
+		fn name(&self) -> &str {
+			"GreetingRecognizer"
+		}
+	}
+	crate::llm::policy::pii::register_recognizer(
+		"test_tool_description_greeting",
+		Box::new(GreetingRecognizer),
+	);
+
+	let mock = mock_streamable_http_server(true).await;
+	let guards = serde_yaml::from_str(
+		r#"
+- id: pii
+  type: pii
+  runs_on: [tools_list]
+  detect: []
+  custom_recognizers: [test_tool_description_greeting]
+"#,
+	)
+	.unwrap();
+	let t = setup_proxy_test("{}")
+		.unwrap()
+		.with_mcp_backend_guards(mock.addr, true, false, guards)
+		.with_bind(simple_bind(basic_route(mock.addr)));
+	let io = t.serve_real_listener(BIND_KEY).await;
+	let client = mcp_streamable_client(io).await;
+	let tools = client.list_tools(None).await.unwrap();
+	crate::llm::policy::pii::unregister_recognizer("test_tool_description_greeting");
+
+	let description = |name: &str| {
+		tools
+			.tools
+			.iter()
+			.find(|t| t.name == name)
+			.and_then(|t| t.description.as_deref())
+			.map(str::to_string)
+	};
+	assert_eq!(
+		description("say_hello").as_deref(),
+		Some("Say <GREETING> to the client")
+	);
+	assert_eq!(description("echo").as_deref(), Some("Repeat what you say"));
+}
+
+/// Test that a guard dry run reports decisions and the filtered catalog of a backend,
+/// without creating guard state for its live traffic
+#[tokio::test]
+async fn guard_dry_run_reports_catalog() {
+	let mock = mock_streamable_http_server(true).await;
+	let guards = serde_yaml::from_str(
+		r#"
+- id: poisoning
+  type: tool_poisoning
+  runs_on: [tools_list]
+  action: quarantine
+  custom_patterns:
+    - "(?i)say hello"
+"#,
+	)
+	.unwrap();
+	let t = setup_proxy_test("{}")
+		.unwrap()
+		.with_mcp_backend_guards(mock.addr, true, false, guards);
+	let pi = t.inputs();
+
+	let report = pi
+		.mcp_state
+		.dry_run(pi.clone(), &format!("/{}", mock.addr))
+		.await
+		.unwrap();
+	assert_eq!(report.servers.len(), 1);
+	let server = &report.servers[0];
+	assert_eq!(server.connection, DryRunDecision::Allow);
+	assert_eq!(
+		server.tools_list,
+		Some(DryRunDecision::Quarantine {
+			tools: vec!["say_hello".to_string()]
+		})
+	);
+	let tools = server
+		.tools
+		.iter()
+		.map(|t| t.name.to_string())
+		.collect_vec();
+	assert!(tools.contains(&"increment".to_string()), "got {tools:?}");
+	assert!(!tools.contains(&"say_hello".to_string()), "got {tools:?}");
+	assert!(server.error.is_none());
+
+	// Live traffic has no guard executor (and so no baselines) from the dry run
+	assert!(pi.stores.guard_registry.effective_configs().is_empty());
+
+	assert!(matches!(
+		pi.mcp_state.dry_run(pi.clone(), "/missing").await,
+		Err(crate::proxy::ProxyError::BackendDoesNotExist)
+	));
+}
+
 /// Test that calling a tool denied by MCP authorization policy returns proper JSON-RPC error
 /// with INVALID_PARAMS error code (-32602) and message "Unknown tool: {tool_name}"
 #[tokio::test]
@@ -24,6 +24,20 @@ impl Messages {
 	pub fn from_result<T: Into<ServerResult>>(id: RequestId, result: T) -> Self {
 		Self::from(ServerJsonRpcMessage::response(result.into(), id))
 	}
+
+	/// Keep the messages `f` returns, possibly rewritten, and drop those it doesn't.
+	/// Errors pass through.
+	pub fn filter_map_messages(
+		self,
+		mut f: impl FnMut(ServerJsonRpcMessage) -> Option<ServerJsonRpcMessage> + Send + 'static,
+	) -> Self {
+		Messages(
+			self
+				.0
+				.filter_map(move |r| futures::future::ready(r.map(&mut f).transpose()))
+				.boxed(),
+		)
+	}
 }
 
 impl Stream for Messages {
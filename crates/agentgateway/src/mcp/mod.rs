@@ -14,6 +14,10 @@ use std::sync::Arc;
 use crate::http::SendDirectResponse;
 use crate::proxy::ProxyError;
 use axum_core::BoxError;
+pub use handler::{
+	DEFAULT_TOOL_NAME_DELIMITER, DryRunDecision, GuardDryRunReport, ServerDryRun,
+	check_tool_name_delimiter,
+};
 use prometheus_client::encoding::{EncodeLabelValue, LabelValueEncoder};
 pub use rbac::{McpAuthorization, McpAuthorizationSet, ResourceId, ResourceType};
 use rmcp::model::RequestId;
@@ -60,7 +64,12 @@ pub enum Error {
 	#[error("failed to create SSE url: {0}")]
 	CreateSseUrl(String),
 	#[error("security guard rejected: {1} - {2}")]
-	SecurityGuard(RequestId, String, String),
+	SecurityGuard(
+		RequestId,
+		String,
+		String,
+		Option<Box<security::DenyExplanation>>,
+	),
 }
 
 impl From<Error> for ProxyError {
@@ -29,6 +29,13 @@ impl CelExecWrapper {
 		let dummy = ::http::Request::from_parts(parts, bytes::Bytes::new());
 		CelExecWrapper(dummy)
 	}
+
+	/// Caller identity for auditing: the JWT `sub` claim, if the request was authenticated
+	pub fn identity(&self) -> Option<String> {
+		Identity::new(self.0.extensions().get::<Claims>().cloned())
+			.get_claim("sub", ".")
+			.map(String::from)
+	}
 }
 #[derive(Clone, Debug)]
 pub struct McpAuthorizationSet(RuleSets);
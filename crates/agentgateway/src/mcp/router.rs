@@ -64,6 +64,145 @@ impl App {
 		}
 	}
 
+	/// Resolve the targets of an MCP backend into the group a `Relay` connects to
+	fn backend_group(
+		&self,
+		pi: &ProxyInputs,
+		backend_group_name: &ResourceName,
+		backend: &McpBackend,
+		backend_policies: &BackendPolicies,
+	) -> Result<McpBackendGroup, ProxyError> {
+		let binds = self.state.read_binds();
+		let nt = backend
+			.targets
+			.iter()
+			.map(|t| {
+				let be = t
+					.spec
+					.backend()
+					.map(|b| crate::proxy::resolve_simple_backend_with_policies(b, pi))
+					.transpose()?;
+				let inline_pols = be.as_ref().map(|pol| pol.inline_policies.as_slice());
+				let sub_backend_target = BackendTargetRef::Backend {
+					name: backend_group_name.name.as_ref(),
+					namespace: backend_group_name.namespace.as_ref(),
+					section: Some(t.name.as_ref()),
+				};
+				let backend_policies = backend_policies
+					.clone()
+					.merge(binds.sub_backend_policies(sub_backend_target, inline_pols));
+				Ok::<_, ProxyError>(Arc::new(McpTarget {
+					name: t.name.clone(),
+					spec: t.spec.clone(),
+					backend: be.map(|b| b.backend),
+					backend_policies,
+					always_use_prefix: backend.always_use_prefix,
+				}))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(McpBackendGroup {
+			name: format!(
+				"{}/{}",
+				backend_group_name.namespace, backend_group_name.name
+			),
+			targets: nt,
+			stateful: backend.stateful,
+			security_guards: backend.security_guards.clone(),
+			on_server_deny: backend.on_server_deny,
+			tool_dedup: backend.tool_dedup.clone(),
+			explain_denials: backend.explain_denials,
+			guard_state: backend.guard_state,
+			notification_guards: backend.notification_guards,
+			event_stream_guards: backend.event_stream_guards,
+			tool_name_delimiter: backend.tool_name_delimiter.clone(),
+		})
+	}
+
+	/// Connect to a configured MCP backend and run its connection and tools-list guards,
+	/// reporting each decision and the tools that would be exposed.
+	///
+	/// The guards run in a private registry, so baselines and other guard state of the
+	/// backend's live traffic are left untouched.
+	pub async fn dry_run(
+		&self,
+		pi: Arc<ProxyInputs>,
+		backend_key: &str,
+	) -> Result<crate::mcp::GuardDryRunReport, ProxyError> {
+		let Some(backend) = self.state.read_binds().backend(&backend_key.into()) else {
+			return Err(ProxyError::BackendDoesNotExist);
+		};
+		let crate::types::agent::Backend::MCP(name, mcp_backend) = &backend.backend else {
+			return Err(ProxyError::ProcessingString(format!(
+				"backend '{}' is not an MCP backend",
+				backend_key
+			)));
+		};
+		let backend_policies = self
+			.state
+			.read_binds()
+			.inline_backend_policies(&backend.inline_policies);
+		let group = self.backend_group(&pi, name, mcp_backend, &backend_policies)?;
+		let relay = Relay::new(
+			group,
+			McpAuthorizationSet::new(RuleSets::from(Vec::new())),
+			PolicyClient { inputs: pi },
+			crate::mcp::security::GuardExecutorRegistry::new(),
+		)
+		.map_err(|e| ProxyError::ProcessingString(e.to_string()))?;
+		Ok(
+			relay
+				.dry_run_security_guards(crate::mcp::upstream::IncomingRequestContext::empty())
+				.await,
+		)
+	}
+
+	/// Re-establish the rug pull baselines of a configured MCP backend from the tools its
+	/// servers list now, lifting any rug pull block. Returns the servers rebaselined.
+	///
+	/// Live per-session executors only forget their baselines; each establishes a new one
+	/// from the next tools/list of its session.
+	pub async fn rebaseline(
+		&self,
+		pi: Arc<ProxyInputs>,
+		backend_key: &str,
+	) -> Result<Vec<String>, ProxyError> {
+		let Some(backend) = self.state.read_binds().backend(&backend_key.into()) else {
+			return Err(ProxyError::BackendDoesNotExist);
+		};
+		let crate::types::agent::Backend::MCP(name, mcp_backend) = &backend.backend else {
+			return Err(ProxyError::ProcessingString(format!(
+				"backend '{}' is not an MCP backend",
+				backend_key
+			)));
+		};
+		let backend_policies = self
+			.state
+			.read_binds()
+			.inline_backend_policies(&backend.inline_policies);
+		let group = self.backend_group(&pi, name, mcp_backend, &backend_policies)?;
+		let servers: Vec<String> = group.targets.iter().map(|t| t.name.to_string()).collect();
+		for executor in self.state.guard_registry.backend_executors(&group.name) {
+			for server in &servers {
+				executor.rebaseline_server(server);
+			}
+		}
+		if group.guard_state == crate::mcp::security::GuardStateScope::PerSession {
+			return Ok(servers);
+		}
+		let relay = Relay::new(
+			group,
+			McpAuthorizationSet::new(RuleSets::from(Vec::new())),
+			PolicyClient { inputs: pi },
+			self.state.guard_registry.clone(),
+		)
+		.map_err(|e| ProxyError::ProcessingString(e.to_string()))?;
+		relay
+			.rebaseline_security_guards(crate::mcp::upstream::IncomingRequestContext::empty())
+			.await;
+		Ok(servers)
+	}
+
 	#[allow(clippy::too_many_arguments)]
 	pub async fn serve(
 		&self,
@@ -74,46 +213,7 @@ impl App {
 		mut req: Request,
 		log: AsyncLog<MCPInfo>,
 	) -> Result<Response, ProxyError> {
-		let backends = {
-			let binds = self.state.read_binds();
-			let nt = backend
-				.targets
-				.iter()
-				.map(|t| {
-					let be = t
-						.spec
-						.backend()
-						.map(|b| crate::proxy::resolve_simple_backend_with_policies(b, &pi))
-						.transpose()?;
-					let inline_pols = be.as_ref().map(|pol| pol.inline_policies.as_slice());
-					let sub_backend_target = BackendTargetRef::Backend {
-						name: backend_group_name.name.as_ref(),
-						namespace: backend_group_name.namespace.as_ref(),
-						section: Some(t.name.as_ref()),
-					};
-					let backend_policies = backend_policies
-						.clone()
-						.merge(binds.sub_backend_policies(sub_backend_target, inline_pols));
-					Ok::<_, ProxyError>(Arc::new(McpTarget {
-						name: t.name.clone(),
-						spec: t.spec.clone(),
-						backend: be.map(|b| b.backend),
-						backend_policies,
-						always_use_prefix: backend.always_use_prefix,
-					}))
-				})
-				.collect::<Result<Vec<_>, _>>()?;
-
-			McpBackendGroup {
-				name: format!(
-					"{}/{}",
-					backend_group_name.namespace, backend_group_name.name
-				),
-				targets: nt,
-				stateful: backend.stateful,
-				security_guards: backend.security_guards.clone(),
-			}
-		};
+		let backends = self.backend_group(&pi, &backend_group_name, &backend, &backend_policies)?;
 		let guard_registry = self.state.guard_registry.clone();
 		let sm = self.session.clone();
 		let client = PolicyClient { inputs: pi.clone() };
@@ -260,6 +360,13 @@ pub struct McpBackendGroup {
 	pub targets: Vec<Arc<McpTarget>>,
 	pub stateful: bool,
 	pub security_guards: Vec<crate::mcp::security::McpSecurityGuard>,
+	pub on_server_deny: crate::mcp::security::OnServerDeny,
+	pub tool_dedup: Option<crate::mcp::security::ToolDedupConfig>,
+	pub explain_denials: bool,
+	pub guard_state: crate::mcp::security::GuardStateScope,
+	pub notification_guards: crate::mcp::security::NotificationGuarding,
+	pub event_stream_guards: crate::mcp::security::EventStreamGuarding,
+	pub tool_name_delimiter: String,
 }
 
 #[derive(Debug)]
@@ -271,6 +378,40 @@ pub struct McpTarget {
 	pub always_use_prefix: bool,
 }
 
+impl McpTarget {
+	/// URL the gateway connects to for this target, as seen by connection guards.
+	/// The scheme reflects whether a backend TLS policy applies; stdio targets have none.
+	pub fn server_url(&self) -> Option<String> {
+		use crate::types::agent::McpTargetSpec;
+		let path = match &self.spec {
+			McpTargetSpec::Sse(sse) if sse.path.is_empty() => "/sse",
+			McpTargetSpec::Sse(sse) => sse.path.as_str(),
+			McpTargetSpec::Mcp(mcp) if mcp.path.is_empty() => "/mcp",
+			McpTargetSpec::Mcp(mcp) => mcp.path.as_str(),
+			McpTargetSpec::OpenAPI(_) => "",
+			McpTargetSpec::Stdio { .. } => return None,
+		};
+		let scheme = if self.backend_policies.backend_tls.is_some() {
+			"https"
+		} else {
+			"http"
+		};
+		let backend = self.backend.as_ref()?;
+		Some(format!("{}://{}{}", scheme, backend.hostport(), path))
+	}
+
+	/// Static request headers the gateway adds to every request to this target, as seen
+	/// by connection guards
+	pub fn server_headers(&self) -> Vec<(Strng, Strng)> {
+		self
+			.backend_policies
+			.request_header_modifier
+			.as_ref()
+			.map(|m| m.add.iter().chain(m.set.iter()).cloned().collect())
+			.unwrap_or_default()
+	}
+}
+
 impl App {
 	fn create_auth_required_response(
 		inner: ProxyError,
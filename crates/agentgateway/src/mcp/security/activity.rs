@@ -0,0 +1,108 @@
+// Per-guard activity tracking
+//
+// Guards accumulate over time and it is easy to lose track of which ones still do
+// anything. Each executor counts, per guard id, how often a guard was evaluated and
+// how often it acted (deny, modify or quarantine), with the time of its last action.
+// Counters are keyed by guard id so they survive hot-reloads; the admin UI exposes
+// them (`GET /api/v1/guards/activity`) to flag guards that have not acted in a while.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use super::{GuardDecision, GuardResult};
+
+/// Runtime activity of one guard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardActivity {
+	pub guard_id: String,
+	/// Times the guard ran, whatever the outcome
+	pub evaluations: u64,
+	/// Times the guard denied, modified or quarantined
+	pub actions: u64,
+	/// Times the guard failed to run
+	pub errors: u64,
+	/// When tracking started for this guard (seconds since the Unix epoch)
+	pub tracked_since: u64,
+	/// When the guard last acted (seconds since the Unix epoch)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub last_acted: Option<u64>,
+}
+
+impl GuardActivity {
+	fn new(guard_id: &str, now: u64) -> Self {
+		Self {
+			guard_id: guard_id.to_string(),
+			evaluations: 0,
+			actions: 0,
+			errors: 0,
+			tracked_since: now,
+			last_acted: None,
+		}
+	}
+
+	/// Whether the guard has been tracked for at least `period` without acting in it
+	pub fn idle_for(&self, period: Duration, now: SystemTime) -> bool {
+		let cutoff = unix_secs(now).saturating_sub(period.as_secs());
+		self.tracked_since <= cutoff && self.last_acted.is_none_or(|t| t < cutoff)
+	}
+}
+
+/// Activity counters for the guards of one executor
+#[derive(Debug, Default)]
+pub struct ActivityTracker {
+	guards: Mutex<HashMap<String, GuardActivity>>,
+}
+
+impl ActivityTracker {
+	/// Start tracking guards that are not tracked yet; existing counters are kept
+	pub fn track<'a>(&self, guard_ids: impl IntoIterator<Item = &'a str>) {
+		let now = unix_secs(SystemTime::now());
+		let mut guards = self.guards.lock().unwrap_or_else(PoisonError::into_inner);
+		for id in guard_ids {
+			guards
+				.entry(id.to_string())
+				.or_insert_with(|| GuardActivity::new(id, now));
+		}
+	}
+
+	/// Record the outcome of one evaluation
+	pub fn record(&self, guard_id: &str, result: &GuardResult) {
+		let now = unix_secs(SystemTime::now());
+		let mut guards = self.guards.lock().unwrap_or_else(PoisonError::into_inner);
+		let activity = guards
+			.entry(guard_id.to_string())
+			.or_insert_with(|| GuardActivity::new(guard_id, now));
+		activity.evaluations += 1;
+		match result {
+			Ok(GuardDecision::Allow) => {},
+			Ok(_) => {
+				activity.actions += 1;
+				activity.last_acted = Some(now);
+			},
+			Err(_) => activity.errors += 1,
+		}
+	}
+
+	/// Activity of the given guards, in the given order
+	pub fn snapshot<'a>(&self, guard_ids: impl IntoIterator<Item = &'a str>) -> Vec<GuardActivity> {
+		let now = unix_secs(SystemTime::now());
+		let guards = self.guards.lock().unwrap_or_else(PoisonError::into_inner);
+		guard_ids
+			.into_iter()
+			.map(|id| {
+				guards
+					.get(id)
+					.cloned()
+					.unwrap_or_else(|| GuardActivity::new(id, now))
+			})
+			.collect()
+	}
+}
+
+fn unix_secs(t: SystemTime) -> u64 {
+	t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
@@ -0,0 +1,201 @@
+// Deny alert throttling
+//
+// A client stuck in a retry loop against a poisoned server produces the same denial
+// over and over. Every request is still denied, but the alert stream (the
+// `mcp_guard_alert` log and the live event feed) collapses identical denials: the
+// first denial for a `(guard_id, code, server)` key is alerted immediately, repeats
+// within the window are only counted, and once the window has passed they are
+// reported as a single throttled alert carrying the total count.
+//
+// Windows are closed on the next denial seen by the executor and, for executors created
+// by the registry on a runtime, by a task that flushes once per window. A throttled alert
+// may therefore arrive up to a window after its own window ends. Without a runtime,
+// windows are only closed by later denials.
+//
+// A guard may also configure quiet hours: daily windows in which its below-critical
+// denials are written to the audit log instead of alerted. Enforcement is unaffected.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::events::GuardEvent;
+use super::{DenyReason, GuardError, Severity};
+
+/// Tracing target for guard deny alerts
+pub const ALERT_LOG_TARGET: &str = "mcp_guard_alert";
+
+/// Default window over which identical denials are collapsed into one alert
+pub const DEFAULT_ALERT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Identical denials share a guard, a reason code and a server
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct AlertKey {
+	guard_id: String,
+	code: String,
+	server: String,
+}
+
+impl AlertKey {
+	fn of(event: &GuardEvent) -> Self {
+		Self {
+			guard_id: event.guard_id.clone(),
+			code: event.code.clone().unwrap_or_default(),
+			server: event.server.clone(),
+		}
+	}
+}
+
+#[derive(Debug)]
+struct OpenWindow {
+	opened: Instant,
+	/// Denials in this window, including the one that was alerted
+	count: u64,
+	/// Most recent denial, reported as the throttled alert
+	last: GuardEvent,
+}
+
+impl OpenWindow {
+	/// The throttled alert for this window, if any denial was suppressed
+	fn into_alert(self) -> Option<GuardEvent> {
+		(self.count > 1).then(|| GuardEvent {
+			count: Some(self.count),
+			..self.last
+		})
+	}
+}
+
+/// Collapses identical deny alerts within a time window
+#[derive(Debug)]
+pub struct DenyAlertThrottle {
+	window: Duration,
+	open: Mutex<HashMap<AlertKey, OpenWindow>>,
+}
+
+impl Default for DenyAlertThrottle {
+	fn default() -> Self {
+		Self::new(DEFAULT_ALERT_WINDOW)
+	}
+}
+
+impl DenyAlertThrottle {
+	/// A zero window disables throttling: every denial is alerted
+	pub fn new(window: Duration) -> Self {
+		Self {
+			window,
+			open: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Record a denial seen at `now`. Returns whether the denial should be alerted on its
+	/// own, along with the throttled alerts of any windows that have closed since.
+	pub fn record(&self, event: &GuardEvent, now: Instant) -> (bool, Vec<GuardEvent>) {
+		if self.window.is_zero() {
+			return (true, Vec::new());
+		}
+		let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+		let closed = self.close_expired(&mut open, now);
+		let mut last = event.clone();
+		last.input = None;
+		match open.get_mut(&AlertKey::of(event)) {
+			Some(window) => {
+				window.count += 1;
+				window.last = last;
+				(false, closed)
+			},
+			None => {
+				open.insert(
+					AlertKey::of(event),
+					OpenWindow {
+						opened: now,
+						count: 1,
+						last,
+					},
+				);
+				(true, closed)
+			},
+		}
+	}
+
+	/// Close every window that has expired at `now`, returning their throttled alerts
+	pub fn flush(&self, now: Instant) -> Vec<GuardEvent> {
+		let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+		self.close_expired(&mut open, now)
+	}
+
+	fn close_expired(
+		&self,
+		open: &mut HashMap<AlertKey, OpenWindow>,
+		now: Instant,
+	) -> Vec<GuardEvent> {
+		let expired: Vec<AlertKey> = open
+			.iter()
+			.filter(|(_, w)| now.saturating_duration_since(w.opened) >= self.window)
+			.map(|(key, _)| key.clone())
+			.collect();
+		expired
+			.into_iter()
+			.filter_map(|key| open.remove(&key).and_then(OpenWindow::into_alert))
+			.collect()
+	}
+}
+
+/// Daily windows in which a guard's below-critical denials are recorded in the audit log
+/// instead of alerted. Critical denials, and denials that carry no severity, are always
+/// alerted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct QuietHours {
+	/// Windows as `HH:MM-HH:MM` in UTC, e.g. `22:00-06:00`. A window that ends before it
+	/// starts runs past midnight.
+	pub windows: Vec<String>,
+}
+
+impl QuietHours {
+	/// Reject windows that are not `HH:MM-HH:MM`
+	pub fn validate(&self) -> Result<(), GuardError> {
+		for (i, window) in self.windows.iter().enumerate() {
+			if parse_window(window).is_none() {
+				return Err(GuardError::InvalidField {
+					field: format!("quiet_hours.windows[{}]", i),
+					reason: format!("'{}' is not a HH:MM-HH:MM time window", window),
+				});
+			}
+		}
+		Ok(())
+	}
+
+	/// Whether `now` falls in one of the windows
+	pub fn contains(&self, now: DateTime<Utc>) -> bool {
+		let time = now.time();
+		self
+			.windows
+			.iter()
+			.filter_map(|w| parse_window(w))
+			.any(|(start, end)| {
+				if start <= end {
+					start <= time && time < end
+				} else {
+					start <= time || time < end
+				}
+			})
+	}
+
+	/// Whether the alert for `reason`, denied at `now`, is held back
+	pub fn silences(&self, reason: &DenyReason, now: DateTime<Utc>) -> bool {
+		reason
+			.severity()
+			.is_some_and(|severity| severity < Severity::Critical)
+			&& self.contains(now)
+	}
+}
+
+fn parse_window(window: &str) -> Option<(NaiveTime, NaiveTime)> {
+	let (start, end) = window.split_once('-')?;
+	let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok();
+	Some((parse(start)?, parse(end)?))
+}
@@ -0,0 +1,164 @@
+// Decision caching for external guards
+//
+// External guards (WASM guards, which may call out to analysis services through
+// `http-get`) are expensive to evaluate. A guard configured with `decision_cache`
+// reuses its earlier decision for an identical input instead of evaluating again:
+// allows for `allow_ttl_ms` and denies for `deny_ttl_ms`. Caching denies matters
+// during an attack, when the same malicious input is replayed in a loop and would
+// otherwise hit the analysis service every time.
+//
+// Entries are keyed by guard id and a SHA-256 digest of the phase, server, identity,
+// metadata and guard input. Modifications, quarantines and errors are never cached. The cache
+// is emptied whenever the executor's guards are reloaded, since a new configuration
+// or module may decide differently.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use aws_lc_rs::digest;
+use serde::{Deserialize, Serialize};
+
+use super::{GuardContext, GuardDecision, GuardPhase, GuardResult};
+
+/// Per-guard decision cache settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct DecisionCacheConfig {
+	/// How long an allow decision is reused, in milliseconds (default: 60000; 0 disables)
+	#[serde(default = "default_allow_ttl_ms")]
+	pub allow_ttl_ms: u64,
+
+	/// How long a deny decision is reused, in milliseconds (default: 5000; 0 disables)
+	#[serde(default = "default_deny_ttl_ms")]
+	pub deny_ttl_ms: u64,
+
+	/// Most decisions kept for the guard (default: 1024)
+	#[serde(default = "default_max_entries")]
+	pub max_entries: usize,
+}
+
+fn default_allow_ttl_ms() -> u64 {
+	60_000
+}
+
+fn default_deny_ttl_ms() -> u64 {
+	5_000
+}
+
+fn default_max_entries() -> usize {
+	1024
+}
+
+impl Default for DecisionCacheConfig {
+	fn default() -> Self {
+		Self {
+			allow_ttl_ms: default_allow_ttl_ms(),
+			deny_ttl_ms: default_deny_ttl_ms(),
+			max_entries: default_max_entries(),
+		}
+	}
+}
+
+impl DecisionCacheConfig {
+	/// How long `decision` may be reused, if it may be cached at all
+	fn ttl(&self, decision: &GuardDecision) -> Option<Duration> {
+		let ms = match decision {
+			GuardDecision::Allow => self.allow_ttl_ms,
+			GuardDecision::Deny(_) => self.deny_ttl_ms,
+			GuardDecision::Modify(_) | GuardDecision::Quarantine(_) => 0,
+		};
+		(ms > 0).then(|| Duration::from_millis(ms))
+	}
+}
+
+#[derive(Debug)]
+struct CachedDecision {
+	expires: Instant,
+	decision: GuardDecision,
+}
+
+/// Identifies one evaluation: the SHA-256 of everything that can change the decision.
+/// A collision-resistant digest, so a different input can never be served another
+/// input's decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecisionKey([u8; 32]);
+
+/// Cached decisions of an executor's guards, by guard id and input digest
+#[derive(Debug, Default)]
+pub struct DecisionCache {
+	guards: Mutex<HashMap<String, HashMap<DecisionKey, CachedDecision>>>,
+}
+
+impl DecisionCache {
+	/// Cache key for one evaluation
+	pub fn key(phase: GuardPhase, context: &GuardContext, input: &serde_json::Value) -> DecisionKey {
+		let mut ctx = digest::Context::new(&digest::SHA256);
+		let fields = [
+			format!("{:?}", phase),
+			context.server_name.clone(),
+			serde_json::to_string(&context.identity).unwrap_or_default(),
+			context.metadata.to_string(),
+			input.to_string(),
+		];
+		// Length-prefixed, so field boundaries cannot shift between inputs
+		for field in &fields {
+			ctx.update(&(field.len() as u64).to_be_bytes());
+			ctx.update(field.as_bytes());
+		}
+		let mut key = [0u8; 32];
+		key.copy_from_slice(ctx.finish().as_ref());
+		DecisionKey(key)
+	}
+
+	/// The decision `guard_id` made for `key`, if still fresh
+	pub fn get(&self, guard_id: &str, key: DecisionKey) -> Option<GuardDecision> {
+		let guards = self.guards.lock().unwrap_or_else(PoisonError::into_inner);
+		guards
+			.get(guard_id)?
+			.get(&key)
+			.filter(|cached| cached.expires > Instant::now())
+			.map(|cached| cached.decision.clone())
+	}
+
+	/// Remember `result` for `key` when `config` allows caching it
+	pub fn insert(
+		&self,
+		guard_id: &str,
+		key: DecisionKey,
+		config: &DecisionCacheConfig,
+		result: &GuardResult,
+	) {
+		let Ok(decision) = result else {
+			return;
+		};
+		let Some(ttl) = config.ttl(decision) else {
+			return;
+		};
+		let mut guards = self.guards.lock().unwrap_or_else(PoisonError::into_inner);
+		let entries = guards.entry(guard_id.to_string()).or_default();
+		if entries.len() >= config.max_entries {
+			let now = Instant::now();
+			entries.retain(|_, cached| cached.expires > now);
+		}
+		if entries.len() < config.max_entries {
+			entries.insert(
+				key,
+				CachedDecision {
+					expires: Instant::now() + ttl,
+					decision: decision.clone(),
+				},
+			);
+		}
+	}
+
+	/// Forget every cached decision
+	pub fn clear(&self) {
+		self
+			.guards
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.clear();
+	}
+}
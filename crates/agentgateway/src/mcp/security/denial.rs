@@ -0,0 +1,165 @@
+// Client-facing errors for guard denials
+//
+// Every denial used to be reported as JSON-RPC error -32001, so clients could not
+// tell a PII block (strip the data and retry) from a rug pull (stop and tell the
+// user) without parsing messages. Deny codes are grouped into categories, each with
+// its own code in the implementation-defined server error range (-32000..-32099),
+// and the category is repeated in the error's `data` for clients that prefer names.
+//
+// Backends with `explainDenials` also name the guard that decided and the guards that
+// passed before it under `data.decidedBy`. This is off by default: it tells a client
+// exactly which policies it is up against.
+
+use rmcp::ErrorData;
+use rmcp::model::ErrorCode;
+use serde::Serialize;
+
+use super::{DenyExplanation, DenyReason};
+
+/// Category of a guard denial, as reported to MCP clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenialCategory {
+	/// Any guard code without a dedicated category (including custom WASM codes)
+	Generic,
+	/// Personal data in a request or response
+	Pii,
+	/// Malicious instructions hidden in tool metadata
+	ToolPoisoning,
+	/// A server's tools changed after the baseline was established
+	RugPull,
+	/// Prompt injection in tool output
+	PromptInjection,
+	/// A tool violates the configured capability or annotation policy
+	ToolPolicy,
+	/// The tool was quarantined by a guard
+	Quarantined,
+}
+
+impl DenialCategory {
+	pub fn from_code(code: &str) -> Self {
+		match code {
+			"pii_detected" | "pii_in_tool_description" => DenialCategory::Pii,
+			"tool_poisoning_detected" | "pattern_blocked" => DenialCategory::ToolPoisoning,
+			"rug_pull_detected"
+			| "rug_pull_server_blocked"
+			| "excessive_change_velocity"
+			| "tool_definition_mismatch" => DenialCategory::RugPull,
+			"prompt_injection_in_result" => DenialCategory::PromptInjection,
+			"dangerous_tool_annotations"
+			| "unknown_tool_annotations"
+			| "sensitive_capability_requested" => DenialCategory::ToolPolicy,
+			"tool_quarantined" => DenialCategory::Quarantined,
+			_ => DenialCategory::Generic,
+		}
+	}
+
+	/// JSON-RPC error code for this category
+	pub fn error_code(&self) -> ErrorCode {
+		ErrorCode(match self {
+			DenialCategory::Generic => -32001,
+			DenialCategory::Pii => -32002,
+			DenialCategory::ToolPoisoning => -32003,
+			DenialCategory::RugPull => -32004,
+			DenialCategory::PromptInjection => -32005,
+			DenialCategory::ToolPolicy => -32006,
+			DenialCategory::Quarantined => -32007,
+		})
+	}
+
+	/// Whether the same operation may succeed if the client changes its input.
+	/// Only PII denials qualify; everything else concerns the server or tool itself.
+	pub fn retryable(&self) -> bool {
+		matches!(self, DenialCategory::Pii)
+	}
+}
+
+/// Build the JSON-RPC error sent to a client for a guard denial, explaining which guard
+/// decided when `explanation` is given
+pub fn denial_error_data(
+	code: &str,
+	message: &str,
+	explanation: Option<&DenyExplanation>,
+) -> ErrorData {
+	let category = DenialCategory::from_code(code);
+	let mut data = serde_json::json!({
+		"guardCode": code,
+		"category": category,
+		"retryable": category.retryable(),
+	});
+	if let Some(explanation) = explanation {
+		data["decidedBy"] = serde_json::json!(explanation);
+	}
+	ErrorData::new(
+		category.error_code(),
+		format!("Security guard denied: {}", message),
+		Some(data),
+	)
+}
+
+impl DenyReason {
+	/// JSON-RPC error for this denial. `details` are left out: they can quote the
+	/// sensitive content that caused the denial.
+	pub fn to_error_data(&self, explanation: Option<&DenyExplanation>) -> ErrorData {
+		denial_error_data(&self.code, &self.message, explanation)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn reason(code: &str) -> DenyReason {
+		DenyReason {
+			code: code.to_string(),
+			message: "denied".to_string(),
+			details: Some(serde_json::json!({"matched": "alice@example.com"})),
+		}
+	}
+
+	#[test]
+	fn test_pii_and_poisoning_codes_differ() {
+		let pii = reason("pii_detected").to_error_data(None);
+		let poisoning = reason("tool_poisoning_detected").to_error_data(None);
+
+		assert_ne!(pii.code, poisoning.code);
+		assert_eq!(pii.code, ErrorCode(-32002));
+		assert_eq!(poisoning.code, ErrorCode(-32003));
+
+		let data = pii.data.unwrap();
+		assert_eq!(data["guardCode"], "pii_detected");
+		assert_eq!(data["category"], "pii");
+		assert_eq!(data["retryable"], true);
+		assert!(!data.to_string().contains("alice@example.com"));
+		assert_eq!(poisoning.data.unwrap()["retryable"], false);
+	}
+
+	#[test]
+	fn test_explanation_only_when_given() {
+		let explanation = DenyExplanation {
+			guard_id: "pii-block".to_string(),
+			category: "PII blocking",
+			passed: vec!["poisoning".to_string()],
+		};
+		let data = reason("pii_detected")
+			.to_error_data(Some(&explanation))
+			.data
+			.unwrap();
+		assert_eq!(data["decidedBy"]["guardId"], "pii-block");
+		assert_eq!(data["decidedBy"]["category"], "PII blocking");
+		assert_eq!(
+			data["decidedBy"]["passed"],
+			serde_json::json!(["poisoning"])
+		);
+
+		let data = reason("pii_detected").to_error_data(None).data.unwrap();
+		assert!(data.get("decidedBy").is_none());
+	}
+
+	#[test]
+	fn test_unknown_code_uses_default() {
+		let err = reason("wasm_denied").to_error_data(None);
+		assert_eq!(err.code, ErrorCode(-32001));
+		assert_eq!(err.data.unwrap()["category"], "generic");
+	}
+}
@@ -0,0 +1,201 @@
+// Live guard decision feed
+//
+// Every guard evaluation is published as a `GuardEvent` on a bounded broadcast
+// channel shared by all executors in a GuardExecutorRegistry. The admin UI exposes
+// it as an SSE stream (`GET /api/v1/guards/events`) for SOC tooling.
+//
+// Guards with an `audit_sample_rate` additionally attach the full (PII-hashed) input
+// to a fraction of their events, which are also written to the `mcp_guard_audit` log.
+//
+// Repeated identical denials are collapsed into throttled alerts before they reach
+// the channel (see `alerts`).
+//
+// Every merged tools/list also writes a `ToolVisibilityAudit` to the audit log, recording
+// which tools the caller was shown and which were filtered out by RBAC or by guards, or
+// collapsed as duplicates of another server's tool.
+//
+// The channel never applies backpressure to the data path: publishing is a
+// non-blocking send, and a subscriber that falls more than the channel capacity
+// behind is disconnected by the SSE endpoint rather than slowing guards down.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use super::{GuardContext, GuardDecision, GuardPhase, GuardResult};
+
+/// Events buffered per subscriber before it is considered too slow and dropped
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Tracing target for sampled guard inputs (see `McpSecurityGuard::audit_sample_rate`)
+pub const AUDIT_LOG_TARGET: &str = "mcp_guard_audit";
+
+/// Decide whether one evaluation is sampled into the audit log
+pub fn should_sample(rate: f64) -> bool {
+	rate > 0.0 && rand::random::<f64>() < rate
+}
+
+fn now_ms() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_millis() as u64)
+		.unwrap_or_default()
+}
+
+/// Outcome of a single guard evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardEventDecision {
+	Allow,
+	Deny,
+	Modify,
+	Quarantine,
+	Error,
+}
+
+/// A single guard evaluation, as published to monitoring subscribers
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardEvent {
+	/// Milliseconds since the Unix epoch
+	pub timestamp_ms: u64,
+	pub guard_id: String,
+	pub phase: GuardPhase,
+	pub server: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub identity: Option<String>,
+	pub decision: GuardEventDecision,
+	/// Deny reason code, or the `GuardError::code` of a failed evaluation
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<String>,
+	/// Deny reason or error message
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub message: Option<String>,
+	/// Full guard input with PII hashed, for evaluations picked by `audit_sample_rate`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub input: Option<serde_json::Value>,
+	/// Identical denials this event stands for, on throttled deny alerts
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub count: Option<u64>,
+	/// A denial during the guard's quiet hours, recorded without alerting
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub quiet: bool,
+}
+
+impl GuardEvent {
+	pub fn new(
+		guard_id: &str,
+		phase: GuardPhase,
+		context: &GuardContext,
+		result: &GuardResult,
+	) -> Self {
+		let (decision, code, message) = match result {
+			Ok(GuardDecision::Allow) => (GuardEventDecision::Allow, None, None),
+			Ok(GuardDecision::Deny(reason)) => (
+				GuardEventDecision::Deny,
+				Some(reason.code.clone()),
+				Some(reason.message.clone()),
+			),
+			Ok(GuardDecision::Modify(_)) => (GuardEventDecision::Modify, None, None),
+			Ok(GuardDecision::Quarantine(quarantined)) => (
+				GuardEventDecision::Quarantine,
+				None,
+				Some(
+					quarantined
+						.iter()
+						.map(|q| format!("{}: {}", q.tool, q.code))
+						.collect::<Vec<_>>()
+						.join(", "),
+				),
+			),
+			Err(e) => (
+				GuardEventDecision::Error,
+				Some(e.code().to_string()),
+				Some(e.to_string()),
+			),
+		};
+		Self {
+			timestamp_ms: now_ms(),
+			guard_id: guard_id.to_string(),
+			phase,
+			server: context.server_name.clone(),
+			identity: context.identity.clone(),
+			decision,
+			code,
+			message,
+			input: None,
+			count: None,
+			quiet: false,
+		}
+	}
+}
+
+/// Broadcast channel for guard events
+#[derive(Debug, Clone)]
+pub struct GuardEventBus {
+	tx: broadcast::Sender<GuardEvent>,
+}
+
+impl Default for GuardEventBus {
+	fn default() -> Self {
+		let (tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+		Self { tx }
+	}
+}
+
+impl GuardEventBus {
+	/// Whether anyone is listening; lets publishers skip building events
+	pub fn has_subscribers(&self) -> bool {
+		self.tx.receiver_count() > 0
+	}
+
+	/// Publish an event. Never blocks; events are discarded when nobody is subscribed.
+	pub fn publish(&self, event: GuardEvent) {
+		let _ = self.tx.send(event);
+	}
+
+	pub fn subscribe(&self) -> broadcast::Receiver<GuardEvent> {
+		self.tx.subscribe()
+	}
+}
+
+/// The tools one caller was shown by a merged tools/list, for "who could access what"
+/// audits. Tool names are as presented to the client (namespaced when multiplexing).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolVisibilityAudit {
+	/// Milliseconds since the Unix epoch
+	pub timestamp_ms: u64,
+	/// JWT subject of the caller, if authenticated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub identity: Option<String>,
+	/// Tools returned to the caller
+	pub allowed: Vec<String>,
+	/// Tools hidden by RBAC policies
+	pub rbac_filtered: Vec<String>,
+	/// Tools removed by security guards (quarantined, or dropped with a denied server)
+	pub guard_filtered: Vec<String>,
+	/// Tools dropped as duplicates of an equivalent tool from a preferred server
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub deduplicated: Vec<String>,
+}
+
+impl ToolVisibilityAudit {
+	pub fn new(identity: Option<String>) -> Self {
+		Self {
+			timestamp_ms: now_ms(),
+			identity,
+			..Default::default()
+		}
+	}
+
+	/// Write the record to the audit log
+	pub fn log(&self) {
+		tracing::info!(
+			target: AUDIT_LOG_TARGET,
+			audit = %serde_json::to_string(self).unwrap_or_default(),
+			"Tool visibility"
+		);
+	}
+}
@@ -0,0 +1,423 @@
+// Global concurrency limit for heavy guard evaluations
+//
+// WASM (and future external) guards are far more expensive than the native regex
+// guards and, once evaluated off the async runtime, each one occupies a blocking
+// thread. Under load an unbounded number of them can exhaust the blocking pool.
+// The limiter is shared by every executor in a GuardExecutorRegistry and bounds
+// how many heavy evaluations run at once; excess evaluations queue for up to
+// `queue_timeout` and then fail with `GuardError::Timeout`, which is handled by the
+// guard's failure mode (fail closed unless configured otherwise).
+//
+// All limits are off by default (0), so heavy guards run unbounded as they did
+// before the limiter existed; operators opt in with the `MCP_GUARD_MAX_CONCURRENCY*`
+// settings.
+//
+// Evaluations are called from async code, so a queued evaluation must not simply park
+// its thread: on a multi-threaded runtime it waits
+// inside `block_in_place`, which hands the worker's other tasks to another thread. A
+// current-thread runtime (e.g. thread-per-core mode) has no other worker to hand them
+// to, and plain threads have no timer at all; both wait on a small process-wide queue
+// runtime instead, for the same `queue_timeout`. The slots they wait for are held by
+// synchronous evaluations on other threads, so those keep running and free them.
+//
+// Guard evaluations are synchronous, so one slow guard holds its slot for as long as
+// it runs. To keep a single slow guard (or a backend full of them) from taking every
+// shared slot, each executor also isolates its heavy guards: an evaluation first takes
+// a slot of its own guard, then of its backend, and only then a shared one. A slow
+// guard's backlog queues (and times out) on its own slots while other guards and
+// backends keep using the rest of the shared pool.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::Duration;
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::GuardError;
+use crate::serdes::serde_dur;
+
+/// Limits for concurrent heavy guard evaluations. A limit of 0 disables it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyLimit {
+	/// Maximum number of heavy guard evaluations running at once (0: no limit)
+	pub max_concurrent: usize,
+	/// How long an evaluation may wait for a free slot before timing out
+	#[serde(with = "serde_dur")]
+	pub queue_timeout: Duration,
+	/// Maximum number of evaluations of one guard running at once (0: no separate limit)
+	pub max_concurrent_per_guard: usize,
+	/// Maximum number of heavy evaluations of one backend running at once (0: no separate limit)
+	pub max_concurrent_per_backend: usize,
+}
+
+impl Default for ConcurrencyLimit {
+	fn default() -> Self {
+		Self {
+			max_concurrent: 0,
+			queue_timeout: Duration::from_secs(1),
+			max_concurrent_per_guard: 0,
+			max_concurrent_per_backend: 0,
+		}
+	}
+}
+
+/// Timer runtime for evaluations queueing outside a multi-threaded runtime, started on
+/// first use and shared by every limiter
+static QUEUE_RUNTIME: LazyLock<std::io::Result<tokio::runtime::Runtime>> = LazyLock::new(|| {
+	tokio::runtime::Builder::new_multi_thread()
+		.worker_threads(1)
+		.thread_name("mcp-guard-queue")
+		.enable_time()
+		.build()
+});
+
+/// Counting semaphore bounding concurrent heavy guard evaluations
+#[derive(Debug)]
+pub struct EvaluationLimiter {
+	limit: ConcurrencyLimit,
+	/// What the limiter bounds, for logging ("shared", "backend", "guard")
+	scope: &'static str,
+	slots: Arc<Semaphore>,
+}
+
+/// Slot held for the duration of one heavy evaluation; released on drop
+pub struct EvaluationPermit {
+	_slot: Option<OwnedSemaphorePermit>,
+}
+
+impl EvaluationLimiter {
+	pub fn new(limit: ConcurrencyLimit) -> Self {
+		Self::scoped(limit, "shared")
+	}
+
+	/// A limiter allowing `limit.max_concurrent` evaluations within `scope`
+	fn scoped(limit: ConcurrencyLimit, scope: &'static str) -> Self {
+		Self {
+			limit,
+			scope,
+			slots: Arc::new(Semaphore::new(limit.max_concurrent)),
+		}
+	}
+
+	pub fn limit(&self) -> ConcurrencyLimit {
+		self.limit
+	}
+
+	/// Number of heavy evaluations currently running (always 0 without a limit)
+	pub fn in_flight(&self) -> usize {
+		self.limit.max_concurrent - self.slots.available_permits()
+	}
+
+	/// Take a free slot, queueing for up to the configured queue timeout
+	pub fn acquire(&self) -> Result<EvaluationPermit, GuardError> {
+		if self.limit.max_concurrent == 0 {
+			return Ok(EvaluationPermit { _slot: None });
+		}
+		let slot = match self.slots.clone().try_acquire_owned() {
+			Ok(slot) => slot,
+			Err(_) => self.queue()?,
+		};
+		Ok(EvaluationPermit { _slot: Some(slot) })
+	}
+
+	/// Wait for a slot without parking an async runtime worker
+	fn queue(&self) -> Result<OwnedSemaphorePermit, GuardError> {
+		let timeout = self.limit.queue_timeout;
+		let slots = self.slots.clone();
+		let wait = async move { tokio::time::timeout(timeout, slots.acquire_owned()).await };
+		let acquired = match Handle::try_current() {
+			Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+				tokio::task::block_in_place(|| handle.block_on(wait))
+			},
+			// The only thread of a current-thread runtime cannot hand its tasks on, and plain
+			// threads have no timer: wait on the shared queue runtime
+			_ => {
+				let queue = QUEUE_RUNTIME
+					.as_ref()
+					.map_err(|e| GuardError::ExecutionError(format!("Failed to start queue timer: {}", e)))?;
+				let (tx, rx) = std::sync::mpsc::sync_channel(1);
+				queue.spawn(async move {
+					let _ = tx.send(wait.await);
+				});
+				rx.recv()
+					.map_err(|_| GuardError::ExecutionError("Guard evaluation queue stopped".to_string()))?
+			},
+		};
+		match acquired {
+			Ok(Ok(slot)) => Ok(slot),
+			// Timed out; the semaphore is never closed
+			_ => Err(self.timed_out()),
+		}
+	}
+
+	fn timed_out(&self) -> GuardError {
+		tracing::warn!(
+			scope = self.scope,
+			max_concurrent = self.limit.max_concurrent,
+			"Guard evaluation queue timeout, concurrency limit reached"
+		);
+		GuardError::Timeout(self.limit.queue_timeout)
+	}
+}
+
+impl Default for EvaluationLimiter {
+	fn default() -> Self {
+		Self::new(ConcurrencyLimit::default())
+	}
+}
+
+/// Per-guard and per-backend slots for the heavy guards of one executor (backend)
+#[derive(Debug)]
+pub struct GuardIsolation {
+	limit: ConcurrencyLimit,
+	backend: Option<EvaluationLimiter>,
+	/// Guard id -> its slots, created on first evaluation; kept across hot-reloads for the
+	/// guards that remain configured
+	guards: Mutex<HashMap<String, Arc<EvaluationLimiter>>>,
+}
+
+/// Guard and backend slots held for one heavy evaluation; released on drop
+pub struct IsolationPermit {
+	_guard: Option<EvaluationPermit>,
+	_backend: Option<EvaluationPermit>,
+}
+
+impl GuardIsolation {
+	pub fn new(limit: ConcurrencyLimit) -> Self {
+		let backend = (limit.max_concurrent_per_backend > 0).then(|| {
+			EvaluationLimiter::scoped(
+				ConcurrencyLimit {
+					max_concurrent: limit.max_concurrent_per_backend,
+					..limit
+				},
+				"backend",
+			)
+		});
+		Self {
+			limit,
+			backend,
+			guards: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Wait for a slot of guard `guard_id`, then of the backend. Take the shared slot only
+	/// once this succeeds, so waiting here never holds one.
+	pub fn acquire(&self, guard_id: &str) -> Result<IsolationPermit, GuardError> {
+		let guard = match self.guard_limiter(guard_id) {
+			Some(limiter) => Some(limiter.acquire()?),
+			None => None,
+		};
+		let backend = match &self.backend {
+			Some(limiter) => Some(limiter.acquire()?),
+			None => None,
+		};
+		Ok(IsolationPermit {
+			_guard: guard,
+			_backend: backend,
+		})
+	}
+
+	/// Drop the slots of guards no longer configured. Evaluations still holding one keep
+	/// it until they finish.
+	pub fn retain<'a>(&self, guard_ids: impl IntoIterator<Item = &'a str>) {
+		let current: HashSet<&str> = guard_ids.into_iter().collect();
+		let mut guards = self.guards.lock().unwrap_or_else(PoisonError::into_inner);
+		guards.retain(|id, _| current.contains(id.as_str()));
+	}
+
+	fn guard_limiter(&self, guard_id: &str) -> Option<Arc<EvaluationLimiter>> {
+		if self.limit.max_concurrent_per_guard == 0 {
+			return None;
+		}
+		let mut guards = self.guards.lock().unwrap_or_else(PoisonError::into_inner);
+		let limiter = guards.entry(guard_id.to_string()).or_insert_with(|| {
+			Arc::new(EvaluationLimiter::scoped(
+				ConcurrencyLimit {
+					max_concurrent: self.limit.max_concurrent_per_guard,
+					..self.limit
+				},
+				"guard",
+			))
+		});
+		Some(limiter.clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	#[test]
+	fn test_limit_respected_under_concurrency() {
+		let limiter = Arc::new(EvaluationLimiter::new(ConcurrencyLimit {
+			max_concurrent: 3,
+			queue_timeout: Duration::from_secs(10),
+			..Default::default()
+		}));
+		let running = Arc::new(AtomicUsize::new(0));
+		let peak = Arc::new(AtomicUsize::new(0));
+
+		let handles: Vec<_> = (0..32)
+			.map(|_| {
+				let (limiter, running, peak) = (limiter.clone(), running.clone(), peak.clone());
+				std::thread::spawn(move || {
+					let _permit = limiter.acquire().unwrap();
+					let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+					peak.fetch_max(now, Ordering::SeqCst);
+					std::thread::sleep(Duration::from_millis(5));
+					running.fetch_sub(1, Ordering::SeqCst);
+				})
+			})
+			.collect();
+		for handle in handles {
+			handle.join().unwrap();
+		}
+
+		assert!(peak.load(Ordering::SeqCst) <= 3);
+		assert_eq!(limiter.in_flight(), 0);
+	}
+
+	#[test]
+	fn test_queue_timeout() {
+		let limiter = EvaluationLimiter::new(ConcurrencyLimit {
+			max_concurrent: 1,
+			queue_timeout: Duration::from_millis(20),
+			..Default::default()
+		});
+
+		let permit = limiter.acquire().unwrap();
+		assert!(matches!(limiter.acquire(), Err(GuardError::Timeout(_))));
+
+		// Releasing the slot lets the next evaluation through
+		drop(permit);
+		assert!(limiter.acquire().is_ok());
+	}
+
+	#[test]
+	fn test_isolation_caps_each_guard() {
+		let isolation = GuardIsolation::new(ConcurrencyLimit {
+			max_concurrent: 8,
+			queue_timeout: Duration::from_millis(20),
+			max_concurrent_per_guard: 1,
+			max_concurrent_per_backend: 2,
+		});
+
+		// A busy guard only blocks itself
+		let slow = isolation.acquire("slow").unwrap();
+		assert!(matches!(
+			isolation.acquire("slow"),
+			Err(GuardError::Timeout(_))
+		));
+		let other = isolation.acquire("other").unwrap();
+
+		// The backend as a whole is capped too
+		assert!(matches!(
+			isolation.acquire("third"),
+			Err(GuardError::Timeout(_))
+		));
+		drop(slow);
+		drop(other);
+		assert!(isolation.acquire("third").is_ok());
+	}
+
+	#[test]
+	fn test_isolation_retains_configured_guards() {
+		let isolation = GuardIsolation::new(ConcurrencyLimit {
+			max_concurrent: 8,
+			queue_timeout: Duration::from_millis(20),
+			max_concurrent_per_guard: 1,
+			..Default::default()
+		});
+		for id in ["kept", "removed"] {
+			drop(isolation.acquire(id).unwrap());
+		}
+
+		// A reload keeps the slots of remaining guards and forgets removed ones
+		let held = isolation.acquire("kept").unwrap();
+		isolation.retain(["kept"]);
+		let guards = isolation.guards.lock().unwrap();
+		assert_eq!(guards.keys().collect::<Vec<_>>(), vec!["kept"]);
+		drop(guards);
+		assert!(matches!(
+			isolation.acquire("kept"),
+			Err(GuardError::Timeout(_))
+		));
+		drop(held);
+		assert!(isolation.acquire("kept").is_ok());
+	}
+
+	#[tokio::test(flavor = "current_thread")]
+	async fn test_isolation_on_current_thread_runtime() {
+		let isolation = Arc::new(GuardIsolation::new(ConcurrencyLimit {
+			max_concurrent: 8,
+			queue_timeout: Duration::from_secs(5),
+			max_concurrent_per_guard: 1,
+			max_concurrent_per_backend: 1,
+		}));
+		let held = isolation.acquire("slow").unwrap();
+		let holder = std::thread::spawn(move || {
+			std::thread::sleep(Duration::from_millis(20));
+			drop(held);
+		});
+
+		// Waiting for the guard's (and backend's) slot works without a second worker
+		assert!(isolation.acquire("slow").is_ok());
+		holder.join().unwrap();
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+	async fn test_queue_keeps_runtime_running() {
+		let limiter = EvaluationLimiter::new(ConcurrencyLimit {
+			max_concurrent: 1,
+			queue_timeout: Duration::from_secs(5),
+			..Default::default()
+		});
+		let held = limiter.acquire().unwrap();
+		tokio::spawn(async move {
+			tokio::time::sleep(Duration::from_millis(20)).await;
+			drop(held);
+		});
+
+		// Queueing hands the only worker's tasks on, so the task freeing the slot still runs
+		assert!(limiter.acquire().is_ok());
+	}
+
+	#[tokio::test(flavor = "current_thread")]
+	async fn test_queue_on_current_thread_runtime() {
+		let limiter = Arc::new(EvaluationLimiter::new(ConcurrencyLimit {
+			max_concurrent: 1,
+			queue_timeout: Duration::from_secs(5),
+			..Default::default()
+		}));
+		let held = limiter.acquire().unwrap();
+		let holder = std::thread::spawn(move || {
+			std::thread::sleep(Duration::from_millis(20));
+			drop(held);
+		});
+
+		// The queued evaluation waits for the slot instead of timing out immediately
+		assert!(limiter.acquire().is_ok());
+		holder.join().unwrap();
+
+		// ...and still times out once the queue timeout passes
+		let limiter = EvaluationLimiter::new(ConcurrencyLimit {
+			max_concurrent: 1,
+			queue_timeout: Duration::from_millis(20),
+			..Default::default()
+		});
+		let _held = limiter.acquire().unwrap();
+		assert!(matches!(limiter.acquire(), Err(GuardError::Timeout(_))));
+	}
+
+	#[test]
+	fn test_unlimited_by_default() {
+		let limiter = EvaluationLimiter::default();
+		let permits: Vec<_> = (0..1000).map(|_| limiter.acquire().unwrap()).collect();
+		assert_eq!(permits.len(), 1000);
+		assert_eq!(limiter.in_flight(), 0);
+	}
+}
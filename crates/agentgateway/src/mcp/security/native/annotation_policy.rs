@@ -0,0 +1,525 @@
+// Annotation Policy Enforcement
+//
+// Uses the behavioural hints that MCP servers attach to tools (`annotations`) to
+// block tools that declare themselves dangerous unless an operator has explicitly
+// allowed them, e.g. "no destructive tools without explicit approval".
+//
+// Annotation hints considered:
+// - destructiveHint: the tool may perform destructive updates
+// - openWorldHint: the tool interacts with external entities
+// - idempotentHint: repeated calls have no additional effect
+// - readOnlyHint: the tool does not modify its environment
+//
+// Hints are self-reported by the server, so this guard is a cheap policy gate
+// rather than a defence against malicious servers (see rug_pull/tool_poisoning).
+//
+// Invocations are judged by the annotations the tool was listed with. A tool this
+// guard has not seen listed (called before the first tools/list, after a reset or
+// reload, or by a guessed name) is denied unless explicitly allowed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult};
+
+/// Configuration for Annotation Policy Enforcement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct AnnotationPolicyConfig {
+	/// Annotation flags that mark a tool as dangerous
+	#[serde(default = "default_blocked_flags")]
+	pub blocked_flags: Vec<AnnotationFlag>,
+
+	/// Tools explicitly approved despite carrying a blocked flag
+	#[serde(default)]
+	pub allowed_tools: Vec<String>,
+
+	/// Apply the MCP spec defaults for missing hints (destructive and open-world
+	/// unless stated otherwise). When false, only explicitly set hints are considered.
+	#[serde(default)]
+	pub assume_spec_defaults: bool,
+
+	/// Deny the whole tools/list response when it contains a dangerous tool.
+	/// When false, dangerous tools are still listed but their invocation is denied.
+	#[serde(default = "default_deny_on_list")]
+	pub deny_on_list: bool,
+}
+
+fn default_blocked_flags() -> Vec<AnnotationFlag> {
+	vec![AnnotationFlag::Destructive, AnnotationFlag::OpenWorld]
+}
+
+fn default_deny_on_list() -> bool {
+	true
+}
+
+impl Default for AnnotationPolicyConfig {
+	fn default() -> Self {
+		Self {
+			blocked_flags: default_blocked_flags(),
+			allowed_tools: Vec::new(),
+			assume_spec_defaults: false,
+			deny_on_list: default_deny_on_list(),
+		}
+	}
+}
+
+/// Tool annotation flags that can be treated as dangerous
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationFlag {
+	/// `destructiveHint` is true (and the tool is not read-only)
+	Destructive,
+	/// `openWorldHint` is true
+	OpenWorld,
+	/// `idempotentHint` is false (and the tool is not read-only)
+	NonIdempotent,
+	/// `readOnlyHint` is false
+	NotReadOnly,
+}
+
+impl AnnotationFlag {
+	fn as_str(&self) -> &'static str {
+		match self {
+			AnnotationFlag::Destructive => "destructive",
+			AnnotationFlag::OpenWorld => "open_world",
+			AnnotationFlag::NonIdempotent => "non_idempotent",
+			AnnotationFlag::NotReadOnly => "not_read_only",
+		}
+	}
+}
+
+/// Annotation Policy Guard implementation
+pub struct AnnotationPolicyGuard {
+	config: AnnotationPolicyConfig,
+	/// Tools seen in tools/list, per server: tool name -> matched dangerous flags (empty
+	/// for safe tools)
+	listed_tools: RwLock<HashMap<String, HashMap<String, Vec<AnnotationFlag>>>>,
+}
+
+impl AnnotationPolicyGuard {
+	pub fn new(config: AnnotationPolicyConfig) -> Self {
+		Self {
+			config,
+			listed_tools: RwLock::new(HashMap::new()),
+		}
+	}
+
+	/// Return the configured dangerous flags carried by a tool
+	fn dangerous_flags(&self, tool: &rmcp::model::Tool) -> Vec<AnnotationFlag> {
+		let defaults = self.config.assume_spec_defaults;
+		let hint = |value: Option<bool>, spec_default: bool| value.unwrap_or(defaults && spec_default);
+
+		let annotations = tool.annotations.as_ref();
+		let read_only = hint(annotations.and_then(|a| a.read_only_hint), false);
+		let destructive = hint(annotations.and_then(|a| a.destructive_hint), true);
+		let idempotent = annotations
+			.and_then(|a| a.idempotent_hint)
+			.unwrap_or(!defaults);
+		let open_world = hint(annotations.and_then(|a| a.open_world_hint), true);
+		// readOnlyHint is only meaningful when the server actually set it, or when
+		// spec defaults are assumed
+		let not_read_only = annotations
+			.and_then(|a| a.read_only_hint)
+			.map(|v| !v)
+			.unwrap_or(defaults);
+
+		self
+			.config
+			.blocked_flags
+			.iter()
+			.copied()
+			.filter(|flag| match flag {
+				AnnotationFlag::Destructive => destructive && !read_only,
+				AnnotationFlag::OpenWorld => open_world,
+				AnnotationFlag::NonIdempotent => !idempotent && !read_only,
+				AnnotationFlag::NotReadOnly => not_read_only,
+			})
+			.collect()
+	}
+
+	fn is_allowed(&self, tool_name: &str) -> bool {
+		self.config.allowed_tools.iter().any(|t| t == tool_name)
+	}
+
+	fn flags_json(flags: &[AnnotationFlag]) -> serde_json::Value {
+		serde_json::json!(flags.iter().map(|f| f.as_str()).collect::<Vec<_>>())
+	}
+}
+
+impl NativeGuard for AnnotationPolicyGuard {
+	fn evaluate_tools_list(
+		&self,
+		tools: &[rmcp::model::Tool],
+		context: &GuardContext,
+	) -> GuardResult {
+		let listed: HashMap<String, Vec<AnnotationFlag>> = tools
+			.iter()
+			.filter(|tool| !self.is_allowed(&tool.name))
+			.map(|tool| (tool.name.to_string(), self.dangerous_flags(tool)))
+			.collect();
+
+		let mut violations: Vec<_> = listed
+			.iter()
+			.filter(|(_, flags)| !flags.is_empty())
+			.map(|(name, flags)| {
+				serde_json::json!({
+					"tool": name,
+					"flags": Self::flags_json(flags),
+				})
+			})
+			.collect();
+		violations.sort_by(|a, b| a["tool"].as_str().cmp(&b["tool"].as_str()));
+
+		// Remember the listed tools so invocations can be checked even when listing is allowed
+		self
+			.listed_tools
+			.write()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(context.server_name.clone(), listed);
+
+		if violations.is_empty() || !self.config.deny_on_list {
+			return Ok(GuardDecision::Allow);
+		}
+
+		tracing::warn!(
+			server = %context.server_name,
+			violation_count = violations.len(),
+			"Tools with dangerous annotations detected"
+		);
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "dangerous_tool_annotations".to_string(),
+			message: format!(
+				"Server exposes {} tool(s) annotated as dangerous without explicit approval",
+				violations.len()
+			),
+			details: Some(serde_json::json!({
+				"violations": violations,
+			})),
+		}))
+	}
+
+	fn evaluate_tool_invoke(
+		&self,
+		tool_name: &str,
+		_arguments: &serde_json::Value,
+		context: &GuardContext,
+	) -> GuardResult {
+		if self.is_allowed(tool_name) {
+			return Ok(GuardDecision::Allow);
+		}
+
+		let listed_tools = self
+			.listed_tools
+			.read()
+			.unwrap_or_else(PoisonError::into_inner);
+		let Some(flags) = listed_tools
+			.get(&context.server_name)
+			.and_then(|tools| tools.get(tool_name))
+		else {
+			// Fail closed: without the listed annotations there is nothing to judge by
+			tracing::warn!(
+				server = %context.server_name,
+				tool = %tool_name,
+				"Blocked invocation of tool whose annotations were never listed"
+			);
+			return Ok(GuardDecision::Deny(DenyReason {
+				code: "unknown_tool_annotations".to_string(),
+				message: format!(
+					"Tool '{}' has not been listed by server '{}', so its annotations cannot be checked",
+					tool_name, context.server_name
+				),
+				details: Some(serde_json::json!({ "tool": tool_name })),
+			}));
+		};
+		if flags.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+
+		tracing::warn!(
+			server = %context.server_name,
+			tool = %tool_name,
+			"Blocked invocation of tool with dangerous annotations"
+		);
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "dangerous_tool_annotations".to_string(),
+			message: format!(
+				"Tool '{}' is annotated as dangerous and has not been explicitly allowed",
+				tool_name
+			),
+			details: Some(serde_json::json!({
+				"tool": tool_name,
+				"flags": Self::flags_json(flags),
+			})),
+		}))
+	}
+
+	fn reset_server(&self, server_name: &str) {
+		self
+			.listed_tools
+			.write()
+			.unwrap_or_else(PoisonError::into_inner)
+			.remove(server_name);
+	}
+
+	fn fresh_state(&self) -> Option<Arc<dyn NativeGuard>> {
+		Some(Arc::new(Self::new(self.config.clone())))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rmcp::model::Tool;
+	use std::borrow::Cow;
+	use std::sync::Arc;
+
+	fn create_test_tool(name: &str, annotations: Option<serde_json::Value>) -> Tool {
+		Tool {
+			name: Cow::Owned(name.to_string()),
+			description: Some(Cow::Owned("A tool".to_string())),
+			icons: None,
+			title: None,
+			meta: None,
+			input_schema: Arc::new(
+				serde_json::from_value(serde_json::json!({"type": "object"})).unwrap(),
+			),
+			annotations: annotations.map(|a| serde_json::from_value(a).unwrap()),
+			output_schema: None,
+		}
+	}
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	#[test]
+	fn test_destructive_tool_blocked() {
+		let guard = AnnotationPolicyGuard::new(AnnotationPolicyConfig::default());
+		let tools = vec![
+			create_test_tool(
+				"list_files",
+				Some(serde_json::json!({"readOnlyHint": true})),
+			),
+			create_test_tool(
+				"delete_repo",
+				Some(serde_json::json!({"destructiveHint": true})),
+			),
+		];
+
+		match guard.evaluate_tools_list(&tools, &create_test_context()) {
+			Ok(GuardDecision::Deny(reason)) => {
+				assert_eq!(reason.code, "dangerous_tool_annotations");
+				let details = reason.details.unwrap();
+				assert_eq!(details["violations"][0]["tool"], "delete_repo");
+				assert_eq!(details["violations"][0]["flags"][0], "destructive");
+				assert_eq!(details["violations"].as_array().unwrap().len(), 1);
+			},
+			other => panic!("Expected Deny, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn test_read_only_tool_allowed() {
+		let guard = AnnotationPolicyGuard::new(AnnotationPolicyConfig::default());
+		let tools = vec![create_test_tool(
+			"list_files",
+			Some(serde_json::json!({"readOnlyHint": true, "openWorldHint": false})),
+		)];
+		let context = create_test_context();
+
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &context),
+			Ok(GuardDecision::Allow)
+		));
+		assert!(matches!(
+			guard.evaluate_tool_invoke("list_files", &serde_json::json!({}), &context),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_read_only_overrides_destructive_hint() {
+		let guard = AnnotationPolicyGuard::new(AnnotationPolicyConfig::default());
+		let tools = vec![create_test_tool(
+			"get_status",
+			Some(serde_json::json!({"readOnlyHint": true, "destructiveHint": true})),
+		)];
+
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &create_test_context()),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_destructive_invocation_blocked_when_listing_allowed() {
+		let config = AnnotationPolicyConfig {
+			deny_on_list: false,
+			..Default::default()
+		};
+		let guard = AnnotationPolicyGuard::new(config);
+		let tools = vec![
+			create_test_tool(
+				"list_files",
+				Some(serde_json::json!({"readOnlyHint": true})),
+			),
+			create_test_tool(
+				"delete_repo",
+				Some(serde_json::json!({"destructiveHint": true})),
+			),
+		];
+		let context = create_test_context();
+		let args = serde_json::json!({});
+
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &context),
+			Ok(GuardDecision::Allow)
+		));
+		assert!(matches!(
+			guard.evaluate_tool_invoke("delete_repo", &args, &context),
+			Ok(GuardDecision::Deny(_))
+		));
+		assert!(matches!(
+			guard.evaluate_tool_invoke("list_files", &args, &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		// Resetting the server forgets what was listed, so nothing is allowed until the
+		// next tools/list
+		guard.reset_server("test-server");
+		for tool in ["delete_repo", "list_files"] {
+			match guard.evaluate_tool_invoke(tool, &args, &context) {
+				Ok(GuardDecision::Deny(reason)) => assert_eq!(reason.code, "unknown_tool_annotations"),
+				other => panic!("Expected Deny, got {:?}", other),
+			}
+		}
+	}
+
+	#[test]
+	fn test_invoke_before_list_denied() {
+		let guard = AnnotationPolicyGuard::new(AnnotationPolicyConfig {
+			allowed_tools: vec!["approved".to_string()],
+			..Default::default()
+		});
+		let context = create_test_context();
+		let args = serde_json::json!({});
+
+		match guard.evaluate_tool_invoke("delete_repo", &args, &context) {
+			Ok(GuardDecision::Deny(reason)) => {
+				assert_eq!(reason.code, "unknown_tool_annotations");
+				assert_eq!(reason.details.unwrap()["tool"], "delete_repo");
+			},
+			other => panic!("Expected Deny, got {:?}", other),
+		}
+		// Explicit approval does not depend on the listing
+		assert!(matches!(
+			guard.evaluate_tool_invoke("approved", &args, &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		// A tool guessed by name is unknown even once the server has listed its tools
+		let tools = vec![create_test_tool(
+			"list_files",
+			Some(serde_json::json!({"readOnlyHint": true})),
+		)];
+		guard.evaluate_tools_list(&tools, &context).unwrap();
+		assert!(matches!(
+			guard.evaluate_tool_invoke("list_files", &args, &context),
+			Ok(GuardDecision::Allow)
+		));
+		assert!(matches!(
+			guard.evaluate_tool_invoke("hidden_admin", &args, &context),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+
+	#[test]
+	fn test_explicitly_allowed_tool() {
+		let config = AnnotationPolicyConfig {
+			allowed_tools: vec!["delete_repo".to_string()],
+			..Default::default()
+		};
+		let guard = AnnotationPolicyGuard::new(config);
+		let tools = vec![create_test_tool(
+			"delete_repo",
+			Some(serde_json::json!({"destructiveHint": true, "openWorldHint": true})),
+		)];
+		let context = create_test_context();
+
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &context),
+			Ok(GuardDecision::Allow)
+		));
+		assert!(matches!(
+			guard.evaluate_tool_invoke("delete_repo", &serde_json::json!({}), &context),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_spec_defaults_for_unannotated_tools() {
+		let tools = vec![create_test_tool("mystery", None)];
+		let context = create_test_context();
+
+		// Without spec defaults, tools without hints are not flagged
+		let guard = AnnotationPolicyGuard::new(AnnotationPolicyConfig::default());
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		// With spec defaults, a tool without hints is destructive and open-world
+		let guard = AnnotationPolicyGuard::new(AnnotationPolicyConfig {
+			assume_spec_defaults: true,
+			..Default::default()
+		});
+		match guard.evaluate_tools_list(&tools, &context) {
+			Ok(GuardDecision::Deny(reason)) => {
+				let details = reason.details.unwrap();
+				assert_eq!(
+					details["violations"][0]["flags"],
+					serde_json::json!(["destructive", "open_world"])
+				);
+			},
+			other => panic!("Expected Deny, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn test_configurable_blocked_flags() {
+		let guard = AnnotationPolicyGuard::new(AnnotationPolicyConfig {
+			blocked_flags: vec![AnnotationFlag::NonIdempotent],
+			..Default::default()
+		});
+		let tools = vec![
+			create_test_tool(
+				"send_email",
+				Some(serde_json::json!({"idempotentHint": false})),
+			),
+			create_test_tool(
+				"delete_repo",
+				Some(serde_json::json!({"destructiveHint": true, "idempotentHint": true})),
+			),
+		];
+
+		match guard.evaluate_tools_list(&tools, &create_test_context()) {
+			Ok(GuardDecision::Deny(reason)) => {
+				let details = reason.details.unwrap();
+				assert_eq!(details["violations"].as_array().unwrap().len(), 1);
+				assert_eq!(details["violations"][0]["tool"], "send_email");
+			},
+			other => panic!("Expected Deny, got {:?}", other),
+		}
+	}
+}
@@ -0,0 +1,163 @@
+// Tool Argument Size Limit
+//
+// Denies tool invocations whose serialized arguments exceed a byte limit. Oversized
+// arguments are a denial-of-service and smuggling vector, and every scanning guard
+// after this one would otherwise pay for walking the whole payload. Give this guard a
+// low `priority` so it runs before the scanning guards.
+//
+// Arguments are measured as compact JSON, and measuring stops as soon as the limit is
+// passed, so checking a huge payload costs no more than checking one at the limit.
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult};
+
+/// Configuration for the Tool Argument Size Limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ArgumentSizeConfig {
+	/// Maximum size of a tool call's arguments, serialized as compact JSON (default: 1 MiB)
+	#[serde(default = "default_max_bytes")]
+	pub max_bytes: usize,
+}
+
+fn default_max_bytes() -> usize {
+	1024 * 1024
+}
+
+impl Default for ArgumentSizeConfig {
+	fn default() -> Self {
+		Self {
+			max_bytes: default_max_bytes(),
+		}
+	}
+}
+
+/// Counts serialized bytes, failing once more than `limit` have been written
+struct LimitedCounter {
+	written: usize,
+	limit: usize,
+}
+
+impl io::Write for LimitedCounter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.written += buf.len();
+		if self.written > self.limit {
+			return Err(io::Error::other("size limit exceeded"));
+		}
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Tool Argument Size Guard implementation
+pub struct ArgumentSizeGuard {
+	config: ArgumentSizeConfig,
+}
+
+impl ArgumentSizeGuard {
+	pub fn new(config: ArgumentSizeConfig) -> Self {
+		Self { config }
+	}
+
+	/// Whether `arguments` serialize to more than `max_bytes`
+	fn exceeds_limit(&self, arguments: &serde_json::Value) -> bool {
+		let mut counter = LimitedCounter {
+			written: 0,
+			limit: self.config.max_bytes,
+		};
+		serde_json::to_writer(&mut counter, arguments).is_err()
+	}
+}
+
+impl NativeGuard for ArgumentSizeGuard {
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		Ok(GuardDecision::Allow)
+	}
+
+	fn evaluate_tool_invoke(
+		&self,
+		tool_name: &str,
+		arguments: &serde_json::Value,
+		_context: &GuardContext,
+	) -> GuardResult {
+		if !self.exceeds_limit(arguments) {
+			return Ok(GuardDecision::Allow);
+		}
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "arguments_too_large".to_string(),
+			message: format!(
+				"Arguments of tool '{}' exceed the maximum size of {} bytes",
+				tool_name, self.config.max_bytes
+			),
+			details: Some(serde_json::json!({
+				"tool": tool_name,
+				"max_bytes": self.config.max_bytes,
+			})),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	#[test]
+	fn test_arguments_at_and_above_limit() {
+		let arguments = serde_json::json!({"text": "x".repeat(100)});
+		let size = serde_json::to_vec(&arguments).unwrap().len();
+		let context = create_test_context();
+
+		let guard = ArgumentSizeGuard::new(ArgumentSizeConfig { max_bytes: size });
+		assert!(matches!(
+			guard.evaluate_tool_invoke("echo", &arguments, &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		let guard = ArgumentSizeGuard::new(ArgumentSizeConfig {
+			max_bytes: size - 1,
+		});
+		let result = guard.evaluate_tool_invoke("echo", &arguments, &context);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "arguments_too_large");
+		let details = reason.details.unwrap();
+		assert_eq!(details["tool"], "echo");
+		assert_eq!(details["max_bytes"], size - 1);
+	}
+
+	#[test]
+	fn test_default_limit() {
+		let guard = ArgumentSizeGuard::new(ArgumentSizeConfig::default());
+		let context = create_test_context();
+		assert!(matches!(
+			guard.evaluate_tool_invoke("echo", &serde_json::json!({"q": "hello"}), &context),
+			Ok(GuardDecision::Allow)
+		));
+		let huge = serde_json::json!({"blob": "a".repeat(2 * 1024 * 1024)});
+		assert!(matches!(
+			guard.evaluate_tool_invoke("echo", &huge, &context),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+}
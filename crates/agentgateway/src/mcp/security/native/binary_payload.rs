@@ -0,0 +1,495 @@
+// Binary Payload Detection in Tool Results
+//
+// A tool result carrying a large base64 blob is rarely something the model needs to
+// read: it can be data being exfiltrated through the agent or a payload (often an
+// executable) being delivered to it. This guard scans `tools/call` results on the
+// Response phase for base64 runs at least `min_payload_chars` long, sniffs the content
+// type from the magic bytes of a bounded decode of each run, and, per config:
+// - flag: lets the result through with a guard warning
+// - strip: replaces each payload with a short placeholder
+// - deny: replaces the whole result with an error
+//
+// Scanned content:
+// - `result.content[*].text` for `text` content items
+// - `result.content[*].resource.text` and `result.content[*].resource.blob` for
+//   embedded resources
+// Image and audio content carry base64 by design and are not scanned.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult, ModifyAction};
+
+/// Base64 characters decoded to sniff a payload's content type (decodes to 48 bytes)
+const SNIFF_CHARS: usize = 64;
+
+/// Shortest line of a wrapped base64 payload (PEM wraps at 64, MIME at 76)
+const MIN_WRAP_WIDTH: usize = 60;
+
+/// Action to take when a binary payload is found in a tool result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryPayloadAction {
+	/// Allow the result and attach a warning describing the payloads
+	Flag,
+	/// Replace each payload with a placeholder
+	#[default]
+	Strip,
+	/// Reject the tool result entirely
+	Deny,
+}
+
+/// Configuration for binary payload detection in tool results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct BinaryPayloadConfig {
+	/// Action to take when a payload is detected
+	#[serde(default)]
+	pub action: BinaryPayloadAction,
+
+	/// Shortest base64 run (in characters) reported as a payload
+	#[serde(default = "default_min_payload_chars")]
+	pub min_payload_chars: usize,
+
+	/// Custom rejection message (deny action)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rejection_message: Option<String>,
+}
+
+fn default_min_payload_chars() -> usize {
+	16 * 1024
+}
+
+impl Default for BinaryPayloadConfig {
+	fn default() -> Self {
+		Self {
+			action: BinaryPayloadAction::default(),
+			min_payload_chars: default_min_payload_chars(),
+			rejection_message: None,
+		}
+	}
+}
+
+/// Binary payload guard implementation
+pub struct BinaryPayloadGuard {
+	config: BinaryPayloadConfig,
+}
+
+/// A base64 payload found in one field of a tool result
+#[derive(Debug)]
+struct PayloadDetection {
+	/// JSON pointer to the scanned string
+	pointer: String,
+	/// Byte range of the payload within the string
+	span: (usize, usize),
+	/// Approximate decoded size in bytes
+	decoded_bytes: usize,
+	content_type: &'static str,
+	executable: bool,
+}
+
+impl PayloadDetection {
+	fn to_json(&self) -> serde_json::Value {
+		serde_json::json!({
+			"pointer": self.pointer,
+			"decoded_bytes": self.decoded_bytes,
+			"content_type": self.content_type,
+			"executable": self.executable,
+		})
+	}
+
+	fn placeholder(&self) -> String {
+		format!(
+			"[removed: {}-byte {} payload]",
+			self.decoded_bytes, self.content_type
+		)
+	}
+}
+
+impl BinaryPayloadGuard {
+	pub fn new(config: BinaryPayloadConfig) -> Self {
+		Self { config }
+	}
+
+	/// Pointers (relative to the response root) of the scanned fields in a tools/call result
+	fn scanned_pointers(response: &serde_json::Value) -> Vec<String> {
+		let Some(content) = response
+			.pointer("/result/content")
+			.and_then(|c| c.as_array())
+		else {
+			return Vec::new();
+		};
+
+		let mut pointers = Vec::new();
+		for (i, item) in content.iter().enumerate() {
+			match item.get("type").and_then(|t| t.as_str()) {
+				Some("text") => pointers.push(format!("/result/content/{}/text", i)),
+				Some("resource") => {
+					for field in ["text", "blob"] {
+						if item.pointer(&format!("/resource/{}", field)).is_some() {
+							pointers.push(format!("/result/content/{}/resource/{}", i, field));
+						}
+					}
+				},
+				_ => {},
+			}
+		}
+		pointers
+	}
+
+	fn collect_detections(&self, response: &serde_json::Value) -> Vec<PayloadDetection> {
+		let min_len = self.config.min_payload_chars.max(SNIFF_CHARS);
+		Self::scanned_pointers(response)
+			.into_iter()
+			.flat_map(|pointer| {
+				let text = response
+					.pointer(&pointer)
+					.and_then(|v| v.as_str())
+					.unwrap_or_default();
+				base64_runs(text, min_len)
+					.into_iter()
+					.filter_map(|span| {
+						let run = &text[span.0..span.1];
+						if !looks_like_base64(run) {
+							return None;
+						}
+						let bytes = sniff_decode(run)?;
+						let (content_type, executable) = content_type(&bytes);
+						Some(PayloadDetection {
+							pointer: pointer.clone(),
+							span,
+							decoded_bytes: decoded_len(run),
+							content_type,
+							executable,
+						})
+					})
+					.collect::<Vec<_>>()
+			})
+			.collect()
+	}
+
+	/// Replace each payload with its placeholder
+	fn strip(response: &serde_json::Value, detections: &[PayloadDetection]) -> serde_json::Value {
+		let mut modified = response.clone();
+		// Detections are grouped by pointer with spans in order; rewrite each field once
+		for group in detections.chunk_by(|a, b| a.pointer == b.pointer) {
+			let Some(field) = modified.pointer_mut(&group[0].pointer) else {
+				continue;
+			};
+			let Some(text) = field.as_str() else {
+				continue;
+			};
+			let mut out = String::with_capacity(text.len());
+			let mut last = 0;
+			for detection in group {
+				out.push_str(&text[last..detection.span.0]);
+				out.push_str(&detection.placeholder());
+				last = detection.span.1;
+			}
+			out.push_str(&text[last..]);
+			*field = serde_json::Value::String(out);
+		}
+		modified
+	}
+}
+
+fn is_base64_char(b: u8) -> bool {
+	b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'-' | b'_' | b'=')
+}
+
+/// Byte ranges of base64 runs of at least `min_len` characters. Line breaks inside a run
+/// (MIME-style wrapping) are part of it and count towards its length.
+fn base64_runs(text: &str, min_len: usize) -> Vec<(usize, usize)> {
+	let bytes = text.as_bytes();
+	let mut runs = Vec::new();
+	let mut start = None;
+	for i in 0..=bytes.len() {
+		let in_run = bytes
+			.get(i)
+			.is_some_and(|&b| is_base64_char(b) || (start.is_some() && matches!(b, b'\r' | b'\n')));
+		match (in_run, start) {
+			(true, None) => start = Some(i),
+			(false, Some(s)) => {
+				let mut end = i;
+				while end > s && matches!(bytes[end - 1], b'\r' | b'\n') {
+					end -= 1;
+				}
+				if end - s >= min_len {
+					runs.push((s, end));
+				}
+				start = None;
+			},
+			_ => {},
+		}
+	}
+	runs
+}
+
+/// Rule out runs that are made of base64 characters but are not an encoded payload:
+/// hex strings, and line-separated lists (of ids, hashes, ...) rather than a payload
+/// wrapped at a fixed width
+fn looks_like_base64(run: &str) -> bool {
+	if run
+		.bytes()
+		.all(|b| b.is_ascii_hexdigit() || matches!(b, b'\r' | b'\n'))
+	{
+		return false;
+	}
+	let lines: Vec<&str> = run.split('\n').map(|l| l.trim_end_matches('\r')).collect();
+	match lines.split_last() {
+		Some((last, [first, rest @ ..])) => {
+			first.len() >= MIN_WRAP_WIDTH
+				&& rest.iter().all(|l| l.len() == first.len())
+				&& last.len() <= first.len()
+		},
+		_ => true,
+	}
+}
+
+/// Decode the first `SNIFF_CHARS` characters of a run; `None` if they are not base64
+fn sniff_decode(run: &str) -> Option<Vec<u8>> {
+	let prefix: String = run
+		.chars()
+		.filter(|c| !matches!(c, '\r' | '\n' | '='))
+		.take(SNIFF_CHARS)
+		.collect();
+	let prefix = &prefix[..prefix.len() - prefix.len() % 4];
+	if prefix.contains(['-', '_']) {
+		URL_SAFE_NO_PAD.decode(prefix).ok()
+	} else {
+		STANDARD_NO_PAD.decode(prefix).ok()
+	}
+}
+
+fn decoded_len(run: &str) -> usize {
+	let chars = run
+		.bytes()
+		.filter(|b| !matches!(b, b'\r' | b'\n' | b'='))
+		.count();
+	chars * 3 / 4
+}
+
+/// Content type and whether it is executable, from the leading magic bytes
+fn content_type(bytes: &[u8]) -> (&'static str, bool) {
+	const SIGNATURES: &[(&[u8], &str, bool)] = &[
+		(b"MZ", "application/x-msdownload", true),
+		(b"\x7fELF", "application/x-elf", true),
+		(b"\xfe\xed\xfa\xce", "application/x-mach-binary", true),
+		(b"\xfe\xed\xfa\xcf", "application/x-mach-binary", true),
+		(b"\xce\xfa\xed\xfe", "application/x-mach-binary", true),
+		(b"\xcf\xfa\xed\xfe", "application/x-mach-binary", true),
+		(b"\xca\xfe\xba\xbe", "application/java-vm", true),
+		(b"\0asm", "application/wasm", true),
+		(b"#!", "text/x-shellscript", true),
+		(b"PK\x03\x04", "application/zip", false),
+		(b"\x1f\x8b", "application/gzip", false),
+		(b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed", false),
+		(b"Rar!", "application/vnd.rar", false),
+		(b"%PDF", "application/pdf", false),
+		(b"\x89PNG", "image/png", false),
+		(b"\xff\xd8\xff", "image/jpeg", false),
+		(b"GIF8", "image/gif", false),
+	];
+	SIGNATURES
+		.iter()
+		.find(|(magic, _, _)| bytes.starts_with(magic))
+		.map(|&(_, content_type, executable)| (content_type, executable))
+		.unwrap_or(("application/octet-stream", false))
+}
+
+impl NativeGuard for BinaryPayloadGuard {
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		// Only tool results are scanned
+		Ok(GuardDecision::Allow)
+	}
+
+	fn evaluate_response(&self, response: &serde_json::Value, context: &GuardContext) -> GuardResult {
+		let detections = self.collect_detections(response);
+		if detections.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+
+		tracing::warn!(
+			server = %context.server_name,
+			payloads = ?detections.iter().map(|d| (&d.pointer, d.content_type, d.decoded_bytes)).collect::<Vec<_>>(),
+			action = ?self.config.action,
+			"Binary payload detected in tool result"
+		);
+
+		match self.config.action {
+			BinaryPayloadAction::Deny => {
+				let message = self.config.rejection_message.clone().unwrap_or_else(|| {
+					format!(
+						"Tool result rejected: {} binary payload(s) detected",
+						detections.len()
+					)
+				});
+				Ok(GuardDecision::Deny(DenyReason {
+					code: "binary_payload_in_result".to_string(),
+					message,
+					details: Some(serde_json::json!({
+						"payloads": detections.iter().map(PayloadDetection::to_json).collect::<Vec<_>>(),
+					})),
+				}))
+			},
+			BinaryPayloadAction::Strip => Ok(GuardDecision::Modify(ModifyAction::Transform(
+				Self::strip(response, &detections),
+			))),
+			BinaryPayloadAction::Flag => {
+				let summary = detections
+					.iter()
+					.map(|d| format!("{} ({} bytes)", d.content_type, d.decoded_bytes))
+					.collect::<Vec<_>>()
+					.join(", ");
+				Ok(GuardDecision::Modify(ModifyAction::AddWarning(format!(
+					"{} binary payload(s) in tool result: {}",
+					detections.len(),
+					summary
+				))))
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use base64::Engine as _;
+
+	use super::*;
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	fn tool_result(text: &str) -> serde_json::Value {
+		serde_json::json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"result": {
+				"content": [{"type": "text", "text": text}],
+				"isError": false
+			}
+		})
+	}
+
+	/// Base64 of a fake ELF binary of `size` bytes
+	fn elf_payload(size: usize) -> String {
+		let mut bytes = b"\x7fELF\x02\x01\x01".to_vec();
+		bytes.resize(size, 0x90);
+		base64::engine::general_purpose::STANDARD.encode(bytes)
+	}
+
+	#[test]
+	fn test_oversized_payload_flagged() {
+		let guard = BinaryPayloadGuard::new(BinaryPayloadConfig {
+			action: BinaryPayloadAction::Flag,
+			..Default::default()
+		});
+		let text = format!("Here is the installer: {} - run it", elf_payload(20_000));
+		let result = guard.evaluate_response(&tool_result(&text), &create_test_context());
+
+		let Ok(GuardDecision::Modify(ModifyAction::AddWarning(warning))) = result else {
+			panic!("Expected a warning, got {:?}", result);
+		};
+		assert_eq!(
+			warning,
+			"1 binary payload(s) in tool result: application/x-elf (20000 bytes)"
+		);
+
+		// Below the threshold nothing is reported
+		let text = format!("Small file: {}", elf_payload(1_000));
+		assert!(matches!(
+			guard.evaluate_response(&tool_result(&text), &create_test_context()),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_payload_stripped_and_denied() {
+		let text = format!("before {} after", elf_payload(20_000));
+
+		let guard = BinaryPayloadGuard::new(BinaryPayloadConfig::default());
+		let result = guard.evaluate_response(&tool_result(&text), &create_test_context());
+		let Ok(GuardDecision::Modify(ModifyAction::Transform(modified))) = result else {
+			panic!("Expected Transform, got {:?}", result);
+		};
+		assert_eq!(
+			modified["result"]["content"][0]["text"],
+			"before [removed: 20000-byte application/x-elf payload] after"
+		);
+
+		let guard = BinaryPayloadGuard::new(BinaryPayloadConfig {
+			action: BinaryPayloadAction::Deny,
+			..Default::default()
+		});
+		let result = guard.evaluate_response(&tool_result(&text), &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "binary_payload_in_result");
+		let payload = &reason.details.unwrap()["payloads"][0];
+		assert_eq!(payload["content_type"], "application/x-elf");
+		assert_eq!(payload["executable"], true);
+		assert_eq!(payload["decoded_bytes"], 20_000);
+	}
+
+	#[test]
+	fn test_wrapped_blob_and_plain_text() {
+		let guard = BinaryPayloadGuard::new(BinaryPayloadConfig {
+			action: BinaryPayloadAction::Deny,
+			min_payload_chars: 1024,
+			..Default::default()
+		});
+
+		// MIME-wrapped zip in an embedded resource blob
+		let mut zip = b"PK\x03\x04".to_vec();
+		zip.resize(4_000, 7);
+		let encoded = base64::engine::general_purpose::STANDARD.encode(zip);
+		let wrapped = encoded
+			.as_bytes()
+			.chunks(76)
+			.map(|c| std::str::from_utf8(c).unwrap())
+			.collect::<Vec<_>>()
+			.join("\r\n");
+		let response = serde_json::json!({
+			"result": {
+				"content": [{
+					"type": "resource",
+					"resource": {"uri": "file:///tmp/a.zip", "blob": wrapped}
+				}]
+			}
+		});
+		let result = guard.evaluate_response(&response, &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(
+			reason.details.unwrap()["payloads"][0]["content_type"],
+			"application/zip"
+		);
+
+		// Long prose, hex and lists of ids are not base64 payloads
+		for text in [
+			"All work and no play makes Jack a dull boy. ".repeat(100),
+			"0123456789abcdef".repeat(100),
+			"123e4567-e89b-12d3-a456-426614174000\n".repeat(100),
+		] {
+			assert!(matches!(
+				guard.evaluate_response(&tool_result(&text), &create_test_context()),
+				Ok(GuardDecision::Allow)
+			));
+		}
+	}
+}
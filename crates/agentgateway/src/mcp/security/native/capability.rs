@@ -0,0 +1,380 @@
+// Sensitive Capability Detection
+//
+// Flags tools whose input schema asks the model for sensitive inputs, a common
+// trait of poisoned or over-privileged tools: a "weather" tool that wants a
+// `password` or `private_key` argument is trying to get the model to hand over
+// secrets it has seen in its context.
+//
+// Checked:
+// - property names anywhere in the input schema (nested objects, array items, $defs)
+// - property descriptions (optional)
+// - the total number of properties (optional), for suspiciously broad schemas
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{NativeGuard, build_regex_set};
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardError, GuardResult};
+
+/// Action taken when a tool requests a sensitive capability
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilityAction {
+	/// Reject the tools/list response
+	#[default]
+	Deny,
+	/// Log the offending tools and allow the response
+	Flag,
+}
+
+/// Configuration for Sensitive Capability Detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityConfig {
+	/// Regex patterns for sensitive property names (replaces the built-in list when set)
+	#[serde(default = "default_sensitive_patterns")]
+	pub sensitive_patterns: Vec<String>,
+
+	/// Also match the patterns against property descriptions
+	#[serde(default = "default_scan_descriptions")]
+	pub scan_descriptions: bool,
+
+	/// Flag tools whose schema declares more properties than this
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_properties: Option<usize>,
+
+	/// Tools exempt from this check
+	#[serde(default)]
+	pub allowed_tools: Vec<String>,
+
+	/// Action taken when a tool is flagged
+	#[serde(default)]
+	pub action: CapabilityAction,
+}
+
+fn default_sensitive_patterns() -> Vec<String> {
+	[
+		r"(?i)passw(or)?d|passphrase",
+		r"(?i)secret",
+		r"(?i)private[\s_-]*key",
+		r"(?i)ssh[\s_-]*key",
+		r"(?i)credential",
+		r"(?i)api[\s_-]*key",
+		r"(?i)(access|refresh|auth)[\s_-]*token",
+		r"(?i)seed[\s_-]*phrase|mnemonic",
+	]
+	.iter()
+	.map(|s| s.to_string())
+	.collect()
+}
+
+fn default_scan_descriptions() -> bool {
+	true
+}
+
+impl Default for CapabilityConfig {
+	fn default() -> Self {
+		Self {
+			sensitive_patterns: default_sensitive_patterns(),
+			scan_descriptions: default_scan_descriptions(),
+			max_properties: None,
+			allowed_tools: Vec::new(),
+			action: CapabilityAction::default(),
+		}
+	}
+}
+
+/// A tool flagged for requesting sensitive capabilities
+#[derive(Debug)]
+struct FlaggedTool {
+	tool: String,
+	/// JSON pointers of the offending properties
+	properties: Vec<String>,
+	/// Set when the schema exceeds `max_properties`
+	property_count: Option<usize>,
+}
+
+/// Sensitive Capability Guard implementation
+pub struct CapabilityGuard {
+	config: CapabilityConfig,
+	patterns: Vec<Regex>,
+}
+
+impl CapabilityGuard {
+	pub fn new(config: CapabilityConfig) -> Result<Self, GuardError> {
+		let patterns = build_regex_set("sensitive_patterns", &config.sensitive_patterns)?;
+		Ok(Self { config, patterns })
+	}
+
+	fn is_sensitive(&self, text: &str) -> bool {
+		self.patterns.iter().any(|p| p.is_match(text))
+	}
+
+	fn check_tool(&self, tool: &rmcp::model::Tool) -> Option<FlaggedTool> {
+		if self
+			.config
+			.allowed_tools
+			.iter()
+			.any(|t| t == tool.name.as_ref())
+		{
+			return None;
+		}
+
+		let schema = serde_json::Value::Object(tool.input_schema.as_ref().clone());
+		let mut properties = Vec::new();
+		let mut count = 0;
+		self.walk_schema(&schema, "", &mut properties, &mut count);
+
+		let property_count = self
+			.config
+			.max_properties
+			.filter(|max| count > *max)
+			.map(|_| count);
+		if properties.is_empty() && property_count.is_none() {
+			return None;
+		}
+		Some(FlaggedTool {
+			tool: tool.name.to_string(),
+			properties,
+			property_count,
+		})
+	}
+
+	/// Visit every property declared in a schema, recording sensitive ones
+	fn walk_schema(
+		&self,
+		node: &serde_json::Value,
+		pointer: &str,
+		flagged: &mut Vec<String>,
+		count: &mut usize,
+	) {
+		match node {
+			serde_json::Value::Object(map) => {
+				for (key, value) in map {
+					let child = format!("{}/{}", pointer, escape_pointer_token(key));
+					match (key.as_str(), value) {
+						("properties", serde_json::Value::Object(props)) => {
+							for (name, prop) in props {
+								let prop_pointer = format!("{}/{}", child, escape_pointer_token(name));
+								*count += 1;
+								let description = prop.get("description").and_then(|d| d.as_str());
+								if self.is_sensitive(name)
+									|| (self.config.scan_descriptions
+										&& description.is_some_and(|d| self.is_sensitive(d)))
+								{
+									flagged.push(prop_pointer.clone());
+								}
+								self.walk_schema(prop, &prop_pointer, flagged, count);
+							}
+						},
+						// Data values, not schemas
+						("default" | "const" | "enum" | "examples", _) => {},
+						_ => self.walk_schema(value, &child, flagged, count),
+					}
+				}
+			},
+			serde_json::Value::Array(items) => {
+				for (i, item) in items.iter().enumerate() {
+					self.walk_schema(item, &format!("{}/{}", pointer, i), flagged, count);
+				}
+			},
+			_ => {},
+		}
+	}
+}
+
+/// Escape a key for use as a JSON pointer reference token (RFC 6901)
+fn escape_pointer_token(key: &str) -> String {
+	key.replace('~', "~0").replace('/', "~1")
+}
+
+impl NativeGuard for CapabilityGuard {
+	fn evaluate_tools_list(
+		&self,
+		tools: &[rmcp::model::Tool],
+		context: &GuardContext,
+	) -> GuardResult {
+		let flagged: Vec<FlaggedTool> = tools.iter().filter_map(|t| self.check_tool(t)).collect();
+		if flagged.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+
+		let details = flagged
+			.iter()
+			.map(|f| {
+				let mut detail = serde_json::json!({
+					"tool": f.tool,
+					"properties": f.properties,
+				});
+				if let Some(count) = f.property_count {
+					detail["property_count"] = serde_json::json!(count);
+				}
+				detail
+			})
+			.collect::<Vec<_>>();
+
+		match self.config.action {
+			CapabilityAction::Flag => {
+				tracing::warn!(
+					server = %context.server_name,
+					flagged = %serde_json::Value::Array(details),
+					"Tools request sensitive capabilities (flag mode - allowing)"
+				);
+				Ok(GuardDecision::Allow)
+			},
+			CapabilityAction::Deny => Ok(GuardDecision::Deny(DenyReason {
+				code: "sensitive_capability_requested".to_string(),
+				message: format!(
+					"{} tool(s) request sensitive or excessive capabilities: {}",
+					flagged.len(),
+					flagged
+						.iter()
+						.map(|f| f.tool.as_str())
+						.collect::<Vec<_>>()
+						.join(", ")
+				),
+				details: Some(serde_json::json!({ "tools": details })),
+			})),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rmcp::model::Tool;
+	use std::borrow::Cow;
+	use std::sync::Arc;
+
+	fn create_test_tool(name: &str, schema: serde_json::Value) -> Tool {
+		Tool {
+			name: Cow::Owned(name.to_string()),
+			description: Some(Cow::Owned("A tool".to_string())),
+			icons: None,
+			title: None,
+			meta: None,
+			input_schema: Arc::new(serde_json::from_value(schema).unwrap()),
+			annotations: None,
+			output_schema: None,
+		}
+	}
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	#[test]
+	fn test_private_key_property_flagged() {
+		let guard = CapabilityGuard::new(CapabilityConfig::default()).unwrap();
+		let tool = create_test_tool(
+			"sign_message",
+			serde_json::json!({
+				"type": "object",
+				"properties": {
+					"message": {"type": "string"},
+					"signer": {
+						"type": "object",
+						"properties": {"private_key": {"type": "string"}}
+					}
+				}
+			}),
+		);
+
+		let result = guard.evaluate_tools_list(&[tool], &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "sensitive_capability_requested");
+		let details = reason.details.unwrap();
+		assert_eq!(details["tools"][0]["tool"], "sign_message");
+		assert_eq!(
+			details["tools"][0]["properties"],
+			serde_json::json!(["/properties/signer/properties/private_key"])
+		);
+	}
+
+	#[test]
+	fn test_benign_tool_passes() {
+		let guard = CapabilityGuard::new(CapabilityConfig::default()).unwrap();
+		let tool = create_test_tool(
+			"get_weather",
+			serde_json::json!({
+				"type": "object",
+				"properties": {
+					"city": {"type": "string", "description": "City name"},
+					"units": {"type": "string", "enum": ["metric", "password"]}
+				}
+			}),
+		);
+
+		let result = guard.evaluate_tools_list(&[tool], &create_test_context());
+		assert!(matches!(result, Ok(GuardDecision::Allow)));
+	}
+
+	#[test]
+	fn test_sensitive_description_flagged() {
+		let guard = CapabilityGuard::new(CapabilityConfig::default()).unwrap();
+		let tool = create_test_tool(
+			"sync",
+			serde_json::json!({
+				"type": "object",
+				"properties": {
+					"token": {"type": "string", "description": "Your GitHub access token"}
+				}
+			}),
+		);
+		let result = guard.evaluate_tools_list(&[tool], &create_test_context());
+		assert!(matches!(result, Ok(GuardDecision::Deny(_))));
+	}
+
+	#[test]
+	fn test_max_properties_and_allowlist() {
+		let guard = CapabilityGuard::new(CapabilityConfig {
+			max_properties: Some(2),
+			allowed_tools: vec!["login".to_string()],
+			..Default::default()
+		})
+		.unwrap();
+		let broad = create_test_tool(
+			"broad",
+			serde_json::json!({
+				"type": "object",
+				"properties": {"a": {}, "b": {}, "c": {}}
+			}),
+		);
+		let login = create_test_tool(
+			"login",
+			serde_json::json!({"type": "object", "properties": {"password": {}}}),
+		);
+
+		let result = guard.evaluate_tools_list(&[login.clone()], &create_test_context());
+		assert!(matches!(result, Ok(GuardDecision::Allow)));
+
+		let result = guard.evaluate_tools_list(&[broad, login], &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.details.unwrap()["tools"][0]["property_count"], 3);
+	}
+
+	#[test]
+	fn test_flag_mode_allows() {
+		let guard = CapabilityGuard::new(CapabilityConfig {
+			action: CapabilityAction::Flag,
+			..Default::default()
+		})
+		.unwrap();
+		let tool = create_test_tool(
+			"t",
+			serde_json::json!({"type": "object", "properties": {"ssh_key": {}}}),
+		);
+		let result = guard.evaluate_tools_list(&[tool], &create_test_context());
+		assert!(matches!(result, Ok(GuardDecision::Allow)));
+	}
+}
@@ -0,0 +1,188 @@
+// Server Capability Policy
+//
+// Initialize-phase guard restricting which capabilities upstream servers may advertise
+// to clients (e.g. `logging`, `experimental`). Capabilities in `deny` reject the
+// server's initialize outright; capabilities in `strip` are removed from its result, so
+// clients never negotiate them through the gateway.
+//
+// Entries name a top-level capability (`tools`, `logging`, `experimental`, ...) or a
+// single experimental capability as `experimental.<name>`.
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{
+	DenyReason, GuardContext, GuardDecision, GuardError, GuardResult, ModifyAction,
+};
+
+/// Configuration for the Server Capability Policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityPolicyConfig {
+	/// Capabilities that reject the server when advertised
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub deny: Vec<String>,
+
+	/// Capabilities removed from the server's initialize result
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub strip: Vec<String>,
+}
+
+/// Capability Policy Guard implementation
+pub struct CapabilityPolicyGuard {
+	config: CapabilityPolicyConfig,
+}
+
+impl CapabilityPolicyGuard {
+	pub fn new(config: CapabilityPolicyConfig) -> Self {
+		Self { config }
+	}
+}
+
+/// JSON pointer to the capability named by a config entry
+fn capability_pointer(entry: &str) -> String {
+	match entry.split_once('.') {
+		Some((parent, name)) => format!("/capabilities/{}/{}", parent, name),
+		None => format!("/capabilities/{}", entry),
+	}
+}
+
+/// The `entries` advertised in `info`
+fn advertised<'a>(info: &serde_json::Value, entries: &'a [String]) -> Vec<&'a str> {
+	entries
+		.iter()
+		.filter(|e| {
+			info
+				.pointer(&capability_pointer(e))
+				.is_some_and(|v| !v.is_null())
+		})
+		.map(String::as_str)
+		.collect()
+}
+
+impl NativeGuard for CapabilityPolicyGuard {
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		Ok(GuardDecision::Allow)
+	}
+
+	fn evaluate_initialize(
+		&self,
+		info: &rmcp::model::ServerInfo,
+		context: &GuardContext,
+	) -> GuardResult {
+		let mut value = serde_json::to_value(info)
+			.map_err(|e| GuardError::ExecutionError(format!("Failed to serialize server info: {}", e)))?;
+
+		let denied = advertised(&value, &self.config.deny);
+		if !denied.is_empty() {
+			return Ok(GuardDecision::Deny(DenyReason {
+				code: "forbidden_server_capability".to_string(),
+				message: format!(
+					"Server '{}' advertises forbidden capabilities: {}",
+					context.server_name,
+					denied.join(", ")
+				),
+				details: Some(serde_json::json!({ "capabilities": denied })),
+			}));
+		}
+
+		let stripped = advertised(&value, &self.config.strip);
+		if stripped.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+		for entry in &stripped {
+			let pointer = capability_pointer(entry);
+			let (parent, name) = pointer.rsplit_once('/').unwrap_or_default();
+			if let Some(parent) = value.pointer_mut(parent).and_then(|p| p.as_object_mut()) {
+				parent.remove(name);
+			}
+		}
+		tracing::warn!(
+			server = %context.server_name,
+			capabilities = ?stripped,
+			"Stripped capabilities from server initialize result"
+		);
+		Ok(GuardDecision::Modify(ModifyAction::Transform(value)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_server_info(capabilities: serde_json::Value) -> rmcp::model::ServerInfo {
+		serde_json::from_value(serde_json::json!({
+			"protocolVersion": "2025-06-18",
+			"capabilities": capabilities,
+			"serverInfo": {"name": "server", "version": "1.0.0"}
+		}))
+		.unwrap()
+	}
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	#[test]
+	fn test_experimental_capability_stripped() {
+		let guard = CapabilityPolicyGuard::new(CapabilityPolicyConfig {
+			strip: vec!["experimental.beta".to_string(), "logging".to_string()],
+			..Default::default()
+		});
+		let info = create_server_info(serde_json::json!({
+			"tools": {},
+			"experimental": {"beta": {}, "stable": {}}
+		}));
+
+		let result = guard.evaluate_initialize(&info, &create_test_context());
+		let Ok(GuardDecision::Modify(ModifyAction::Transform(value))) = result else {
+			panic!("Expected Transform, got {:?}", result);
+		};
+		let modified: rmcp::model::ServerInfo = serde_json::from_value(value).unwrap();
+		let experimental = modified.capabilities.experimental.unwrap();
+		assert!(!experimental.contains_key("beta"));
+		assert!(experimental.contains_key("stable"));
+		assert!(modified.capabilities.tools.is_some());
+
+		// Nothing to strip
+		assert!(matches!(
+			guard.evaluate_initialize(
+				&create_server_info(serde_json::json!({"tools": {}})),
+				&create_test_context()
+			),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_forbidden_capability_denied() {
+		let guard = CapabilityPolicyGuard::new(CapabilityPolicyConfig {
+			deny: vec!["logging".to_string()],
+			strip: vec!["experimental".to_string()],
+		});
+		let info = create_server_info(serde_json::json!({
+			"tools": {},
+			"logging": {},
+			"experimental": {"beta": {}}
+		}));
+
+		let result = guard.evaluate_initialize(&info, &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "forbidden_server_capability");
+		assert_eq!(
+			reason.details.unwrap()["capabilities"],
+			serde_json::json!(["logging"])
+		);
+	}
+}
@@ -0,0 +1,197 @@
+// Compressed Content Scanning
+//
+// Some tools return compressed content as base64 (a gzipped report, a zlib-wrapped
+// export), which text scanners only see as an opaque blob. Guards that opt in decode
+// such strings, scan the decompressed text, and re-compress it after masking.
+//
+// Detection is by magic bytes after base64 decoding: gzip (`1f 8b`) or zlib-wrapped
+// deflate (`78 xx` with a valid header checksum). Decompression stops as soon as the
+// output exceeds `max_decompressed_bytes`, so a decompression bomb costs at most that
+// much memory and is reported rather than expanded.
+
+use async_compression::tokio::bufread::{GzipDecoder, GzipEncoder, ZlibDecoder, ZlibEncoder};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Configuration for scanning base64-encoded compressed content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct CompressedContentConfig {
+	/// Largest decompressed size scanned; larger content is rejected as a possible
+	/// decompression bomb
+	#[serde(default = "default_max_decompressed_bytes")]
+	pub max_decompressed_bytes: usize,
+}
+
+fn default_max_decompressed_bytes() -> usize {
+	1024 * 1024
+}
+
+impl Default for CompressedContentConfig {
+	fn default() -> Self {
+		Self {
+			max_decompressed_bytes: default_max_decompressed_bytes(),
+		}
+	}
+}
+
+/// Shortest string considered as base64 compressed content; anything shorter can't hold
+/// a compression header plus a meaningful payload
+const MIN_ENCODED_LEN: usize = 24;
+
+/// Compression format of a decoded payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Compression {
+	Gzip,
+	Deflate,
+}
+
+impl Compression {
+	/// Marker reported as the `source` of detections inside compressed content
+	pub(crate) fn source(&self) -> &'static str {
+		match self {
+			Compression::Gzip => "gzip",
+			Compression::Deflate => "deflate",
+		}
+	}
+
+	fn detect(bytes: &[u8]) -> Option<Self> {
+		match bytes {
+			[0x1f, 0x8b, ..] => Some(Compression::Gzip),
+			[cmf, flg, ..] if cmf & 0x0f == 8 && ((u16::from(*cmf) << 8) | u16::from(*flg)) % 31 == 0 => {
+				Some(Compression::Deflate)
+			},
+			_ => None,
+		}
+	}
+}
+
+/// Text recovered from a base64 compressed string
+#[derive(Debug)]
+pub(crate) struct Decompressed {
+	pub compression: Compression,
+	pub text: String,
+}
+
+/// Compressed content that can't be scanned
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum DecompressError {
+	/// Decompressed output exceeded `max_decompressed_bytes`
+	TooLarge {
+		compression: Compression,
+		limit: usize,
+	},
+}
+
+impl std::fmt::Display for DecompressError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DecompressError::TooLarge { compression, limit } => write!(
+				f,
+				"{} content decompresses to more than {} bytes",
+				compression.source(),
+				limit
+			),
+		}
+	}
+}
+
+/// Decompress `text` if it is base64-encoded gzip or deflate content.
+/// Returns `Ok(None)` for anything else, including corrupt streams and payloads that
+/// aren't UTF-8 text, which are left to the regular scan.
+pub(crate) fn decompress(
+	text: &str,
+	config: &CompressedContentConfig,
+) -> Result<Option<Decompressed>, DecompressError> {
+	let text = text.trim();
+	if text.len() < MIN_ENCODED_LEN {
+		return Ok(None);
+	}
+	let Ok(bytes) = STANDARD.decode(text) else {
+		return Ok(None);
+	};
+	let Some(compression) = Compression::detect(&bytes) else {
+		return Ok(None);
+	};
+
+	let limit = config.max_decompressed_bytes;
+	let decoder: Box<dyn AsyncRead + Unpin + Send> = match compression {
+		Compression::Gzip => Box::new(GzipDecoder::new(bytes.as_slice())),
+		Compression::Deflate => Box::new(ZlibDecoder::new(bytes.as_slice())),
+	};
+	// Read at most one byte past the limit, so a bomb is never expanded further
+	let mut out = Vec::new();
+	// The reader is in memory, so this never waits on I/O
+	let read = futures::executor::block_on(decoder.take(limit as u64 + 1).read_to_end(&mut out));
+	if read.is_err() {
+		return Ok(None);
+	}
+	if out.len() > limit {
+		return Err(DecompressError::TooLarge { compression, limit });
+	}
+	Ok(
+		String::from_utf8(out)
+			.ok()
+			.map(|text| Decompressed { compression, text }),
+	)
+}
+
+/// Compress `text` and base64 encode it, the inverse of `decompress`
+pub(crate) fn compress(text: &str, compression: Compression) -> String {
+	let mut encoder: Box<dyn AsyncRead + Unpin + Send> = match compression {
+		Compression::Gzip => Box::new(GzipEncoder::new(text.as_bytes())),
+		Compression::Deflate => Box::new(ZlibEncoder::new(text.as_bytes())),
+	};
+	let mut out = Vec::new();
+	// Encoding from memory into memory can't fail
+	let _ = futures::executor::block_on(encoder.read_to_end(&mut out));
+	STANDARD.encode(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_round_trip() {
+		let config = CompressedContentConfig::default();
+		for compression in [Compression::Gzip, Compression::Deflate] {
+			let encoded = compress("contact me at jane@example.com", compression);
+			let decompressed = decompress(&encoded, &config).unwrap().unwrap();
+			assert_eq!(decompressed.compression, compression);
+			assert_eq!(decompressed.text, "contact me at jane@example.com");
+		}
+	}
+
+	#[test]
+	fn test_plain_and_non_compressed_base64_ignored() {
+		let config = CompressedContentConfig::default();
+		assert!(
+			decompress("just some ordinary text content", &config)
+				.unwrap()
+				.is_none()
+		);
+		let encoded = STANDARD.encode("not compressed, only base64 encoded");
+		assert!(decompress(&encoded, &config).unwrap().is_none());
+	}
+
+	#[test]
+	fn test_decompression_bomb_rejected() {
+		let config = CompressedContentConfig {
+			max_decompressed_bytes: 64 * 1024,
+		};
+		// 16 MiB of zeros compresses to a few KiB
+		let bomb = compress(&"\0".repeat(16 * 1024 * 1024), Compression::Gzip);
+		assert!(bomb.len() < 64 * 1024);
+		assert_eq!(
+			decompress(&bomb, &config).unwrap_err(),
+			DecompressError::TooLarge {
+				compression: Compression::Gzip,
+				limit: 64 * 1024
+			}
+		);
+	}
+}
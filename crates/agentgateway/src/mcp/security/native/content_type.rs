@@ -0,0 +1,303 @@
+// Tool Result Content Type Enforcement
+//
+// A tool whose results should be plain text (a weather lookup, a calculator) has no
+// business returning HTML with embedded scripts, images or embedded resources. This
+// guard checks each `result.content[*]` block of a `tools/call` result on the Response
+// phase against the content types allowed for the called tool (taken from
+// `metadata.tool` in the guard context), falling back to those allowed for the server
+// and then to `default_allowed`. Tools with no applicable allowlist are not checked.
+//
+// Allowlist entries are either content block types (`text`, `image`, `audio`,
+// `resource`, `resource_link`), which admit any block of that type, or media types
+// (`text/plain`, `image/*`), which admit blocks whose media type matches. Image and
+// audio blocks carry their media type in `mimeType`, resources in `resource.mimeType`;
+// text blocks are `text/html` when their text looks like an HTML document or contains
+// a script tag, and `text/plain` otherwise.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult};
+
+/// Leading characters of a text block inspected for an HTML document
+const HTML_SNIFF_CHARS: usize = 512;
+
+/// Configuration for tool result content type enforcement
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContentTypeConfig {
+	/// Allowed content types per tool name; takes precedence over `servers`
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub tools: BTreeMap<String, Vec<String>>,
+
+	/// Allowed content types per server name
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub servers: BTreeMap<String, Vec<String>>,
+
+	/// Allowed content types for tools matched by neither `tools` nor `servers`.
+	/// Unset leaves those tools unchecked.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub default_allowed: Option<Vec<String>>,
+
+	/// Custom rejection message
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rejection_message: Option<String>,
+}
+
+/// Content type enforcement guard implementation
+pub struct ContentTypeGuard {
+	config: ContentTypeConfig,
+}
+
+impl ContentTypeGuard {
+	pub fn new(config: ContentTypeConfig) -> Self {
+		Self { config }
+	}
+
+	fn allowed(&self, server: &str, tool: &str) -> Option<&[String]> {
+		self
+			.config
+			.tools
+			.get(tool)
+			.or_else(|| self.config.servers.get(server))
+			.or(self.config.default_allowed.as_ref())
+			.map(Vec::as_slice)
+	}
+}
+
+/// Block type and media type (if known) of a content block
+fn classify(block: &Value) -> (&str, Option<String>) {
+	let kind = block
+		.get("type")
+		.and_then(Value::as_str)
+		.unwrap_or_default();
+	let mime_type = match kind {
+		"text" => {
+			let text = block
+				.get("text")
+				.and_then(Value::as_str)
+				.unwrap_or_default();
+			Some(
+				if looks_like_html(text) {
+					"text/html"
+				} else {
+					"text/plain"
+				}
+				.to_string(),
+			)
+		},
+		"resource" => block
+			.pointer("/resource/mimeType")
+			.and_then(Value::as_str)
+			.map(str::to_string),
+		_ => block
+			.get("mimeType")
+			.and_then(Value::as_str)
+			.map(str::to_string),
+	};
+	(kind, mime_type.map(|m| essence(&m)))
+}
+
+/// Media type without parameters, lowercased
+fn essence(mime_type: &str) -> String {
+	mime_type
+		.split(';')
+		.next()
+		.unwrap_or_default()
+		.trim()
+		.to_ascii_lowercase()
+}
+
+fn looks_like_html(text: &str) -> bool {
+	let head = text
+		.trim_start()
+		.chars()
+		.take(HTML_SNIFF_CHARS)
+		.collect::<String>()
+		.to_ascii_lowercase();
+	head.starts_with("<!doctype html")
+		|| head.starts_with("<html")
+		|| text.to_ascii_lowercase().contains("<script")
+}
+
+/// Whether an allowlist entry admits a block
+fn admits(entry: &str, kind: &str, mime_type: Option<&str>) -> bool {
+	let entry = entry.trim().to_ascii_lowercase();
+	if !entry.contains('/') {
+		return entry == kind;
+	}
+	let Some(mime_type) = mime_type else {
+		return false;
+	};
+	match entry.strip_suffix("/*") {
+		Some(top_level) => mime_type
+			.split_once('/')
+			.is_some_and(|(t, _)| t == top_level),
+		None => entry == mime_type,
+	}
+}
+
+impl NativeGuard for ContentTypeGuard {
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		// Enforced on tool results
+		Ok(GuardDecision::Allow)
+	}
+
+	fn evaluate_response(&self, response: &Value, context: &GuardContext) -> GuardResult {
+		let Some(tool) = context.metadata.get("tool").and_then(Value::as_str) else {
+			return Ok(GuardDecision::Allow);
+		};
+		let Some(allowed) = self.allowed(&context.server_name, tool) else {
+			return Ok(GuardDecision::Allow);
+		};
+		let Some(blocks) = response
+			.pointer("/result/content")
+			.and_then(Value::as_array)
+		else {
+			return Ok(GuardDecision::Allow);
+		};
+
+		for (index, block) in blocks.iter().enumerate() {
+			let (kind, mime_type) = classify(block);
+			if allowed
+				.iter()
+				.any(|entry| admits(entry, kind, mime_type.as_deref()))
+			{
+				continue;
+			}
+			let content_type = mime_type.unwrap_or_else(|| kind.to_string());
+			tracing::warn!(
+				server = %context.server_name,
+				tool = %tool,
+				content_type = %content_type,
+				"Tool result carries a content type not allowed for the tool"
+			);
+			return Ok(GuardDecision::Deny(DenyReason {
+				code: "unexpected_content_type".to_string(),
+				message: self.config.rejection_message.clone().unwrap_or_else(|| {
+					format!(
+						"Result of tool '{}' contains {} content, which is not allowed for it",
+						tool, content_type
+					)
+				}),
+				details: Some(serde_json::json!({
+					"tool": tool,
+					"index": index,
+					"block_type": kind,
+					"content_type": content_type,
+					"allowed": allowed,
+				})),
+			}));
+		}
+		Ok(GuardDecision::Allow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_test_context(tool: &str) -> GuardContext {
+		GuardContext {
+			server_name: "weather".to_string(),
+			identity: None,
+			metadata: serde_json::json!({ "tool": tool }),
+		}
+	}
+
+	fn tool_result(content: Value) -> Value {
+		serde_json::json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"result": { "content": content }
+		})
+	}
+
+	fn guard() -> ContentTypeGuard {
+		ContentTypeGuard::new(ContentTypeConfig {
+			tools: BTreeMap::from([(
+				"render_map".to_string(),
+				vec!["text".to_string(), "image/png".to_string()],
+			)]),
+			servers: BTreeMap::from([("weather".to_string(), vec!["text/plain".to_string()])]),
+			..Default::default()
+		})
+	}
+
+	#[test]
+	fn test_unexpected_content_type_denied() {
+		let guard = guard();
+		for (content, content_type) in [
+			(
+				serde_json::json!([
+					{"type": "text", "text": "Sunny, 21C"},
+					{"type": "text", "text": "<div>Sunny</div><script>fetch('https://x.example')</script>"}
+				]),
+				"text/html",
+			),
+			(
+				serde_json::json!([{"type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png"}]),
+				"image/png",
+			),
+		] {
+			let result = guard.evaluate_response(&tool_result(content), &create_test_context("forecast"));
+			let Ok(GuardDecision::Deny(reason)) = result else {
+				panic!("Expected Deny for {content_type}, got {:?}", result);
+			};
+			assert_eq!(reason.code, "unexpected_content_type");
+			let details = reason.details.unwrap();
+			assert_eq!(details["content_type"], content_type);
+			assert_eq!(details["tool"], "forecast");
+		}
+	}
+
+	#[test]
+	fn test_allowed_content_types() {
+		let guard = guard();
+		let text = serde_json::json!([{"type": "text", "text": "Sunny, 21C"}]);
+		assert!(matches!(
+			guard.evaluate_response(&tool_result(text), &create_test_context("forecast")),
+			Ok(GuardDecision::Allow)
+		));
+
+		// The per-tool allowlist overrides the server's
+		let map = serde_json::json!([
+			{"type": "text", "text": "<!DOCTYPE html><html></html>"},
+			{"type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png; q=1"}
+		]);
+		assert!(matches!(
+			guard.evaluate_response(
+				&tool_result(map.clone()),
+				&create_test_context("render_map")
+			),
+			Ok(GuardDecision::Allow)
+		));
+
+		// Servers and tools without an allowlist are not checked
+		let context = GuardContext {
+			server_name: "maps".to_string(),
+			..create_test_context("tiles")
+		};
+		assert!(matches!(
+			guard.evaluate_response(&tool_result(map), &context),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_media_type_wildcards() {
+		assert!(admits("image/*", "image", Some("image/webp")));
+		assert!(!admits("image/*", "audio", Some("audio/wav")));
+		assert!(admits("resource", "resource", None));
+		assert!(!admits("application/json", "resource", None));
+		assert!(admits("Text/Plain", "text", Some("text/plain")));
+	}
+}
@@ -0,0 +1,137 @@
+// Data URI Content Scanning
+//
+// Tool results sometimes embed text as a base64 `data:` URI (RFC 2397), e.g.
+// `data:text/plain;base64,SGVsbG8=`, which text scanners only see as an opaque URL.
+// Guards that opt in decode such strings, scan the embedded text, and re-encode it
+// after masking, keeping the URI's media type and parameters as they were.
+//
+// Only textual media types are decoded: `text/*`, or none (which RFC 2397 defines as
+// `text/plain`). URIs whose payload would decode to more than `max_decoded_bytes` are
+// left as they are and scanned as plain strings.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+/// Configuration for scanning base64 `data:` URIs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct DataUriConfig {
+	/// Largest decoded payload scanned; larger URIs are scanned as plain strings
+	#[serde(default = "default_max_decoded_bytes")]
+	pub max_decoded_bytes: usize,
+}
+
+fn default_max_decoded_bytes() -> usize {
+	1024 * 1024
+}
+
+impl Default for DataUriConfig {
+	fn default() -> Self {
+		Self {
+			max_decoded_bytes: default_max_decoded_bytes(),
+		}
+	}
+}
+
+/// Marker reported as the `source` of detections inside data URIs
+pub(crate) const SOURCE: &str = "data_uri";
+
+/// Text recovered from a base64 `data:` URI
+#[derive(Debug)]
+pub(crate) struct DataUri {
+	/// Everything before the comma, e.g. `data:text/plain;charset=utf-8;base64`
+	header: String,
+	pub text: String,
+}
+
+impl DataUri {
+	/// The URI with its payload replaced by `text`, the inverse of `decode`
+	pub(crate) fn encode(&self, text: &str) -> String {
+		format!("{},{}", self.header, STANDARD.encode(text))
+	}
+}
+
+/// Decode `value` if it is a base64 `data:` URI of textual content within the size limit.
+/// Returns `None` for anything else, including invalid base64 and payloads that aren't
+/// UTF-8 text, which are left to the regular scan.
+pub(crate) fn decode(value: &str, config: &DataUriConfig) -> Option<DataUri> {
+	let (header, payload) = value.split_once(',')?;
+	let scheme = header.get(..5)?;
+	if !scheme.eq_ignore_ascii_case("data:") {
+		return None;
+	}
+	let mut params = header[5..].split(';');
+	let media_type = params.next().unwrap_or_default().trim();
+	if !(media_type.is_empty() || starts_with_ignore_case(media_type, "text/")) {
+		return None;
+	}
+	// `base64` must be the last parameter
+	if !params
+		.next_back()
+		.is_some_and(|p| p.trim().eq_ignore_ascii_case("base64"))
+	{
+		return None;
+	}
+	// Checked before decoding, so oversized payloads are never materialized
+	if payload.len() / 4 * 3 > config.max_decoded_bytes {
+		return None;
+	}
+	let bytes = STANDARD.decode(payload.trim()).ok()?;
+	let text = String::from_utf8(bytes).ok()?;
+	Some(DataUri {
+		header: header.to_string(),
+		text,
+	})
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+	text
+		.get(..prefix.len())
+		.is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_round_trip() {
+		let config = DataUriConfig::default();
+		let uri = format!(
+			"data:text/plain;charset=utf-8;base64,{}",
+			STANDARD.encode("contact me at jane@example.com")
+		);
+		let decoded = decode(&uri, &config).unwrap();
+		assert_eq!(decoded.text, "contact me at jane@example.com");
+		assert_eq!(decoded.encode(&decoded.text), uri);
+
+		// No media type means text/plain
+		let uri = format!("data:;base64,{}", STANDARD.encode("hello"));
+		assert_eq!(decode(&uri, &config).unwrap().text, "hello");
+	}
+
+	#[test]
+	fn test_non_text_and_non_base64_ignored() {
+		let config = DataUriConfig::default();
+		let payload = STANDARD.encode("jane@example.com");
+		for uri in [
+			format!("data:image/png;base64,{payload}"),
+			"data:text/plain,jane@example.com".to_string(),
+			format!("https://example.com/{payload}"),
+			"data:text/plain;base64,not base64!".to_string(),
+		] {
+			assert!(decode(&uri, &config).is_none(), "{uri}");
+		}
+	}
+
+	#[test]
+	fn test_oversized_payload_ignored() {
+		let config = DataUriConfig {
+			max_decoded_bytes: 16,
+		};
+		let uri = format!("data:text/plain;base64,{}", STANDARD.encode("a".repeat(64)));
+		assert!(decode(&uri, &config).is_none());
+	}
+}
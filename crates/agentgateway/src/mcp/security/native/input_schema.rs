@@ -0,0 +1,452 @@
+// Tool Input Schema Well-formedness
+//
+// Checks that every tool's `input_schema` is well-formed JSON Schema and within
+// complexity bounds before the tool list reaches clients. A malformed or deliberately
+// pathological schema (`$ref` cycles, absurd nesting, thousands of properties, refs to
+// remote documents) can crash or stall clients and the gateway's own schema handling.
+//
+// Checked:
+// - every subschema is an object or boolean, and core keywords have the right shape
+//   (`type`, `properties`, `required`, `enum`, `allOf`, `items`, ...)
+// - `$ref`s are local (`#...`), resolve within the schema, and never lead back to a
+//   schema that is already being expanded (recursive schemas are rejected too)
+// - nesting depth, counting `$ref` expansions, stays within `max_depth`
+// - the schema declares at most `max_properties` properties in total
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult};
+
+/// Configuration for Tool Input Schema Well-formedness
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct InputSchemaConfig {
+	/// Deepest nesting of subschemas allowed, counting `$ref` expansions
+	#[serde(default = "default_max_depth")]
+	pub max_depth: usize,
+
+	/// Most properties a schema may declare, summed over all of its subschemas
+	#[serde(default = "default_max_properties")]
+	pub max_properties: usize,
+
+	/// Tools exempt from this check (names as reported by the upstream server)
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub allowed_tools: Vec<String>,
+}
+
+fn default_max_depth() -> usize {
+	32
+}
+
+fn default_max_properties() -> usize {
+	1000
+}
+
+impl Default for InputSchemaConfig {
+	fn default() -> Self {
+		Self {
+			max_depth: default_max_depth(),
+			max_properties: default_max_properties(),
+			allowed_tools: Vec::new(),
+		}
+	}
+}
+
+/// Keywords whose value is a single subschema
+const SCHEMA_KEYWORDS: &[&str] = &[
+	"additionalProperties",
+	"additionalItems",
+	"unevaluatedProperties",
+	"unevaluatedItems",
+	"propertyNames",
+	"contains",
+	"not",
+	"if",
+	"then",
+	"else",
+];
+
+/// Keywords whose value is an array of subschemas
+const SCHEMA_ARRAY_KEYWORDS: &[&str] = &["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// Keywords whose value is an object of subschemas
+const SCHEMA_MAP_KEYWORDS: &[&str] = &[
+	"properties",
+	"patternProperties",
+	"dependentSchemas",
+	"$defs",
+	"definitions",
+];
+
+const TYPE_NAMES: &[&str] = &[
+	"null", "boolean", "object", "array", "number", "string", "integer",
+];
+
+/// Walks one tool's schema, stopping at the first problem
+struct SchemaWalker<'a> {
+	config: &'a InputSchemaConfig,
+	root: &'a Value,
+	/// `$ref`s currently being expanded, outermost first
+	ref_stack: Vec<&'a str>,
+	/// `$ref`s whose targets were already checked
+	checked_refs: HashSet<&'a str>,
+	properties: usize,
+}
+
+impl<'a> SchemaWalker<'a> {
+	fn check(config: &'a InputSchemaConfig, root: &'a Value) -> Result<(), String> {
+		SchemaWalker {
+			config,
+			root,
+			ref_stack: Vec::new(),
+			checked_refs: HashSet::new(),
+			properties: 0,
+		}
+		.walk(root, "", 0)
+	}
+
+	fn walk(&mut self, schema: &'a Value, path: &str, depth: usize) -> Result<(), String> {
+		if depth > self.config.max_depth {
+			return Err(format!(
+				"schema nesting exceeds the maximum depth of {} at '{}'",
+				self.config.max_depth, path
+			));
+		}
+		let schema = match schema {
+			Value::Bool(_) => return Ok(()),
+			Value::Object(schema) => schema,
+			other => {
+				return Err(format!(
+					"subschema at '{}' is {}, not an object or boolean",
+					path,
+					kind(other)
+				));
+			},
+		};
+
+		if let Some(ty) = schema.get("type") {
+			let valid = match ty {
+				Value::String(t) => TYPE_NAMES.contains(&t.as_str()),
+				Value::Array(types) => {
+					!types.is_empty()
+						&& types
+							.iter()
+							.all(|t| t.as_str().is_some_and(|t| TYPE_NAMES.contains(&t)))
+				},
+				_ => false,
+			};
+			if !valid {
+				return Err(format!("invalid 'type' at '{}': {}", path, ty));
+			}
+		}
+		if let Some(required) = schema.get("required")
+			&& !required
+				.as_array()
+				.is_some_and(|r| r.iter().all(Value::is_string))
+		{
+			return Err(format!(
+				"'required' at '{}' is not an array of strings",
+				path
+			));
+		}
+		if let Some(values) = schema.get("enum")
+			&& !values.is_array()
+		{
+			return Err(format!("'enum' at '{}' is not an array", path));
+		}
+
+		for keyword in SCHEMA_MAP_KEYWORDS {
+			let Some(value) = schema.get(*keyword) else {
+				continue;
+			};
+			let Some(map) = value.as_object() else {
+				return Err(format!("'{}' at '{}' is not an object", keyword, path));
+			};
+			if *keyword == "properties" {
+				self.properties += map.len();
+				if self.properties > self.config.max_properties {
+					return Err(format!(
+						"schema declares more than the maximum of {} properties",
+						self.config.max_properties
+					));
+				}
+			}
+			for (name, sub) in map {
+				self.walk(sub, &format!("{}/{}/{}", path, keyword, name), depth + 1)?;
+			}
+		}
+		for keyword in SCHEMA_ARRAY_KEYWORDS {
+			let Some(value) = schema.get(*keyword) else {
+				continue;
+			};
+			let Some(items) = value.as_array() else {
+				return Err(format!("'{}' at '{}' is not an array", keyword, path));
+			};
+			for (i, sub) in items.iter().enumerate() {
+				self.walk(sub, &format!("{}/{}/{}", path, keyword, i), depth + 1)?;
+			}
+		}
+		for keyword in SCHEMA_KEYWORDS {
+			if let Some(sub) = schema.get(*keyword) {
+				self.walk(sub, &format!("{}/{}", path, keyword), depth + 1)?;
+			}
+		}
+		// `items` is a schema, or (before draft 2020-12) an array of schemas
+		match schema.get("items") {
+			Some(Value::Array(items)) => {
+				for (i, sub) in items.iter().enumerate() {
+					self.walk(sub, &format!("{}/items/{}", path, i), depth + 1)?;
+				}
+			},
+			Some(sub) => self.walk(sub, &format!("{}/items", path), depth + 1)?,
+			None => {},
+		}
+
+		if let Some(reference) = schema.get("$ref") {
+			let Some(reference) = reference.as_str() else {
+				return Err(format!("'$ref' at '{}' is not a string", path));
+			};
+			self.follow_ref(reference, path, depth)?;
+		}
+		Ok(())
+	}
+
+	fn follow_ref(&mut self, reference: &'a str, path: &str, depth: usize) -> Result<(), String> {
+		let Some(pointer) = reference.strip_prefix('#') else {
+			return Err(format!(
+				"'$ref' at '{}' points outside the schema: '{}'",
+				path, reference
+			));
+		};
+		if self.ref_stack.contains(&reference) {
+			return Err(format!(
+				"cyclic '$ref' at '{}': {} -> {}",
+				path,
+				self.ref_stack.join(" -> "),
+				reference
+			));
+		}
+		if self.checked_refs.contains(reference) {
+			return Ok(());
+		}
+		let target = self
+			.root
+			.pointer(pointer)
+			.ok_or_else(|| format!("'$ref' at '{}' does not resolve: '{}'", path, reference))?;
+
+		self.ref_stack.push(reference);
+		self.walk(target, pointer, depth + 1)?;
+		self.ref_stack.pop();
+		self.checked_refs.insert(reference);
+		Ok(())
+	}
+}
+
+fn kind(value: &Value) -> &'static str {
+	match value {
+		Value::Null => "null",
+		Value::Bool(_) => "a boolean",
+		Value::Number(_) => "a number",
+		Value::String(_) => "a string",
+		Value::Array(_) => "an array",
+		Value::Object(_) => "an object",
+	}
+}
+
+/// Input Schema Guard implementation
+pub struct InputSchemaGuard {
+	config: InputSchemaConfig,
+}
+
+impl InputSchemaGuard {
+	pub fn new(config: InputSchemaConfig) -> Self {
+		Self { config }
+	}
+}
+
+impl NativeGuard for InputSchemaGuard {
+	fn evaluate_tools_list(
+		&self,
+		tools: &[rmcp::model::Tool],
+		context: &GuardContext,
+	) -> GuardResult {
+		let malformed: Vec<Value> = tools
+			.iter()
+			.filter(|t| {
+				!self
+					.config
+					.allowed_tools
+					.iter()
+					.any(|a| a == t.name.as_ref())
+			})
+			.filter_map(|t| {
+				let schema = Value::Object(t.input_schema.as_ref().clone());
+				SchemaWalker::check(&self.config, &schema)
+					.err()
+					.map(|problem| serde_json::json!({ "tool": t.name, "problem": problem }))
+			})
+			.collect();
+
+		if malformed.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "malformed_tool_schema".to_string(),
+			message: format!(
+				"Server '{}' exposes {} tool(s) with malformed or overly complex input schemas",
+				context.server_name,
+				malformed.len()
+			),
+			details: Some(serde_json::json!({ "tools": malformed })),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rmcp::model::Tool;
+
+	fn create_tool(name: &str, input_schema: Value) -> Tool {
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"description": "test tool",
+			"inputSchema": input_schema,
+		}))
+		.unwrap()
+	}
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	fn problem(guard: &InputSchemaGuard, schema: Value) -> String {
+		let result = guard.evaluate_tools_list(&[create_tool("tool", schema)], &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "malformed_tool_schema");
+		reason.details.unwrap()["tools"][0]["problem"]
+			.as_str()
+			.unwrap()
+			.to_string()
+	}
+
+	#[test]
+	fn test_well_formed_schema_allowed() {
+		let guard = InputSchemaGuard::new(InputSchemaConfig::default());
+		let tool = create_tool(
+			"search",
+			serde_json::json!({
+				"type": "object",
+				"properties": {
+					"query": {"type": "string"},
+					"filters": {"type": "array", "items": {"$ref": "#/$defs/filter"}},
+					"limit": {"type": ["integer", "null"]},
+				},
+				"required": ["query"],
+				"$defs": {
+					"filter": {
+						"type": "object",
+						"properties": {"field": {"type": "string"}, "value": true},
+						"additionalProperties": false,
+					}
+				},
+			}),
+		);
+		assert!(matches!(
+			guard.evaluate_tools_list(&[tool], &create_test_context()),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_cyclic_ref_rejected() {
+		let guard = InputSchemaGuard::new(InputSchemaConfig::default());
+		let problem = problem(
+			&guard,
+			serde_json::json!({
+				"type": "object",
+				"properties": {"node": {"$ref": "#/$defs/a"}},
+				"$defs": {
+					"a": {"$ref": "#/$defs/b"},
+					"b": {"type": "object", "properties": {"next": {"$ref": "#/$defs/a"}}},
+				},
+			}),
+		);
+		assert!(problem.contains("cyclic '$ref'"), "{problem}");
+		assert!(
+			problem.contains("#/$defs/a -> #/$defs/b -> #/$defs/a"),
+			"{problem}"
+		);
+	}
+
+	#[test]
+	fn test_excessively_deep_schema_rejected() {
+		let guard = InputSchemaGuard::new(InputSchemaConfig {
+			max_depth: 8,
+			..Default::default()
+		});
+		let mut schema = serde_json::json!({"type": "string"});
+		for _ in 0..10 {
+			schema = serde_json::json!({"type": "object", "properties": {"nested": schema}});
+		}
+		let problem = problem(&guard, schema);
+		assert!(problem.contains("maximum depth of 8"), "{problem}");
+	}
+
+	#[test]
+	fn test_malformed_and_external_schemas_rejected() {
+		let guard = InputSchemaGuard::new(InputSchemaConfig {
+			max_properties: 3,
+			..Default::default()
+		});
+		for (schema, expected) in [
+			(
+				serde_json::json!({"type": "object", "properties": {"a": {"$ref": "https://evil.example/s.json"}}}),
+				"points outside the schema",
+			),
+			(
+				serde_json::json!({"type": "object", "properties": {"a": {"$ref": "#/$defs/missing"}}}),
+				"does not resolve",
+			),
+			(serde_json::json!({"type": "objekt"}), "invalid 'type'"),
+			(
+				serde_json::json!({"type": "object", "properties": {"a": 5}}),
+				"not an object or boolean",
+			),
+			(
+				serde_json::json!({"type": "object", "required": "a"}),
+				"'required'",
+			),
+			(
+				serde_json::json!({"properties": {"a": {}, "b": {}, "c": {"properties": {"d": {}}}}}),
+				"maximum of 3 properties",
+			),
+		] {
+			let problem = problem(&guard, schema);
+			assert!(problem.contains(expected), "{problem}");
+		}
+
+		// Exempt tools are not checked
+		let guard = InputSchemaGuard::new(InputSchemaConfig {
+			allowed_tools: vec!["tool".to_string()],
+			..Default::default()
+		});
+		let tool = create_tool("tool", serde_json::json!({"type": "objekt"}));
+		assert!(matches!(
+			guard.evaluate_tools_list(&[tool], &create_test_context()),
+			Ok(GuardDecision::Allow)
+		));
+	}
+}
@@ -0,0 +1,206 @@
+// Namespace Spoofing Detection
+//
+// When a backend multiplexes several targets, the relay exposes each tool as
+// `<target><delimiter><tool>`. A malicious server can name its tools so they
+// read as if they belonged to another target (`github_delete_repo` served by
+// `evil` is shown as `evil_github_delete_repo`), or so that its prefixed name
+// is ambiguous with another target's namespace when target names themselves
+// contain the delimiter.
+//
+// The relay passes its namespacing (delimiter and target names) to guards in
+// the context metadata; without it (single target) there is nothing to spoof.
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult, ToolNamespace};
+
+/// Configuration for Namespace Spoofing Detection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct NamespaceSpoofingConfig {
+	/// Tools exempt from this check (names as reported by the upstream server)
+	#[serde(default)]
+	pub allowed_tools: Vec<String>,
+
+	/// Compare names case-sensitively (default: case-insensitive)
+	#[serde(default)]
+	pub case_sensitive: bool,
+}
+
+/// Namespace Spoofing Guard implementation
+pub struct NamespaceSpoofingGuard {
+	config: NamespaceSpoofingConfig,
+}
+
+impl NamespaceSpoofingGuard {
+	pub fn new(config: NamespaceSpoofingConfig) -> Self {
+		Self { config }
+	}
+
+	fn normalize(&self, s: &str) -> String {
+		if self.config.case_sensitive {
+			s.to_string()
+		} else {
+			s.to_lowercase()
+		}
+	}
+
+	/// The other target a tool impersonates, if any
+	fn spoofed_target<'a>(
+		&self,
+		tool_name: &str,
+		server_name: &str,
+		namespace: &'a ToolNamespace,
+	) -> Option<&'a str> {
+		let delimiter = self.normalize(&namespace.delimiter);
+		let tool = self.normalize(tool_name);
+		let exposed = format!("{}{}{}", self.normalize(server_name), delimiter, tool);
+
+		namespace
+			.targets
+			.iter()
+			.filter(|t| *t != server_name)
+			.find(|target| {
+				let target = self.normalize(target);
+				let prefix = format!("{}{}", target, delimiter);
+				// The tool name carries another target's prefix, or the name the relay
+				// exposes falls inside a longer target's namespace (the shorter target is
+				// the one choosing tool names to reach into it). A suffix is not checked:
+				// `web_search` served next to a `search` target is an ordinary name.
+				tool.starts_with(&prefix)
+					|| (target.len() > server_name.len() && exposed.starts_with(&prefix))
+			})
+			.map(|t| t.as_str())
+	}
+}
+
+impl NativeGuard for NamespaceSpoofingGuard {
+	fn evaluate_tools_list(
+		&self,
+		tools: &[rmcp::model::Tool],
+		context: &GuardContext,
+	) -> GuardResult {
+		let Some(namespace) = context.tool_namespace() else {
+			return Ok(GuardDecision::Allow);
+		};
+
+		let spoofed: Vec<serde_json::Value> = tools
+			.iter()
+			.filter(|t| {
+				!self
+					.config
+					.allowed_tools
+					.iter()
+					.any(|a| a == t.name.as_ref())
+			})
+			.filter_map(|t| {
+				self
+					.spoofed_target(&t.name, &context.server_name, &namespace)
+					.map(|target| serde_json::json!({ "tool": t.name, "spoofed_target": target }))
+			})
+			.collect();
+
+		if spoofed.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "namespace_spoofing".to_string(),
+			message: format!(
+				"Server '{}' exposes {} tool(s) impersonating another target's namespace",
+				context.server_name,
+				spoofed.len()
+			),
+			details: Some(serde_json::json!({ "tools": spoofed })),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rmcp::model::Tool;
+
+	fn create_test_tool(name: &str) -> Tool {
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"description": "A tool",
+			"inputSchema": {"type": "object"}
+		}))
+		.unwrap()
+	}
+
+	fn create_test_context(server_name: &str, targets: &[&str]) -> GuardContext {
+		GuardContext {
+			server_name: server_name.to_string(),
+			identity: None,
+			metadata: ToolNamespace {
+				delimiter: "_".to_string(),
+				targets: targets.iter().map(|t| t.to_string()).collect(),
+			}
+			.into_metadata(),
+		}
+	}
+
+	#[test]
+	fn test_spoofed_prefix_denied() {
+		let guard = NamespaceSpoofingGuard::new(NamespaceSpoofingConfig::default());
+		let context = create_test_context("evil", &["github", "evil"]);
+		let tools = vec![
+			create_test_tool("list_files"),
+			create_test_tool("GitHub_delete_repo"),
+		];
+
+		let result = guard.evaluate_tools_list(&tools, &context);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "namespace_spoofing");
+		let details = reason.details.unwrap();
+		assert_eq!(details["tools"][0]["tool"], "GitHub_delete_repo");
+		assert_eq!(details["tools"][0]["spoofed_target"], "github");
+	}
+
+	#[test]
+	fn test_target_name_as_suffix_allowed() {
+		let guard = NamespaceSpoofingGuard::new(NamespaceSpoofingConfig::default());
+		let context = create_test_context("web", &["web", "search"]);
+		let result = guard.evaluate_tools_list(&[create_test_tool("web_search")], &context);
+		assert!(matches!(result, Ok(GuardDecision::Allow)), "{result:?}");
+	}
+
+	#[test]
+	fn test_ambiguous_exposed_name_denied() {
+		// "git" + "_hub_push" is exposed as "git_hub_push", inside the "git_hub" namespace
+		let guard = NamespaceSpoofingGuard::new(NamespaceSpoofingConfig::default());
+		let context = create_test_context("git", &["git", "git_hub"]);
+		let result = guard.evaluate_tools_list(&[create_test_tool("hub_push")], &context);
+		assert!(matches!(result, Ok(GuardDecision::Deny(_))));
+	}
+
+	#[test]
+	fn test_own_prefix_and_single_target_allowed() {
+		let guard = NamespaceSpoofingGuard::new(NamespaceSpoofingConfig::default());
+		let tools = vec![create_test_tool("github_search")];
+
+		// A server may use its own name in its tools
+		let context = create_test_context("github", &["github", "jira"]);
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		// Without namespacing there is nothing to spoof
+		let context = GuardContext {
+			server_name: "evil".to_string(),
+			identity: None,
+			metadata: serde_json::Value::Null,
+		};
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &context),
+			Ok(GuardDecision::Allow)
+		));
+	}
+}
@@ -3,22 +3,59 @@
 // Detects and optionally masks PII in MCP requests and responses.
 // Reuses the existing LLM PII recognizers for consistency.
 //
-// Supported PII types:
+// Supported PII types (the first six are detected by default, the rest only when
+// listed in `detect`):
 // - Email addresses
 // - Phone numbers (US, GB, DE, IL, IN, CA, BR)
 // - US Social Security Numbers (SSN)
 // - Credit card numbers (Visa, Mastercard, Amex, Discover, Diners Club)
 // - Canadian Social Insurance Numbers (SIN)
 // - URLs
+// - Dates of birth and ages
+// - UK National Insurance numbers (NINO)
+// - Cryptocurrency wallet addresses (Bitcoin, Ethereum)
+// - Street addresses
+//
+// Recognizers registered by embedders (`pii::register_recognizer`) can be enabled by
+// name through `custom_recognizers`.
+//
+// `type_actions` overrides `action` per type, e.g. masking emails while rejecting SSNs.
+// `sensitivity_actions` does the same per sensitivity tier (see `PiiType::sensitivity`),
+// e.g. masking `sensitive` types while rejecting `highly_sensitive` ones; a type's own
+// entry in `type_actions` takes precedence over its tier's.
+// A message with any rejecting type is rejected as a whole; otherwise the masking
+// types are masked.
+//
+// With `compressed` set, base64 gzip/deflate strings are decompressed (up to a size
+// limit) and their text is scanned instead; detections inside are reported with
+// `source: "gzip"` / `"deflate"`, and masking re-compresses the masked text.
+//
+// With `data_uris` set, base64 `data:` URIs of textual content are decoded (up to a
+// size limit) and their text is scanned instead; detections inside are reported with
+// `source: "data_uri"`, and masking re-encodes the masked text into the same URI.
+//
+// Resource contents (`resources/read` results, embedded resources) are scanned like any
+// other JSON: `text` contents directly, and `blob` contents with a textual `mimeType`
+// decoded from base64 (up to the `data_uris` size limit, or its default), reported with
+// `source: "resource_blob"` and re-encoded after masking.
 
+use std::collections::HashMap;
+
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use super::NativeGuard;
+use super::compressed::{self, CompressedContentConfig, DecompressError};
+use super::data_uri::{self, DataUriConfig};
+use super::resource_blob;
+use super::{NativeGuard, RegexStats};
 use crate::llm::policy::pii;
-use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult, ModifyAction};
+use crate::mcp::security::redact::redacted_tool_arguments;
+use crate::mcp::security::{
+	DenyReason, GuardContext, GuardDecision, GuardError, GuardResult, ModifyAction,
+};
 
 // Re-export PiiType from the shared pii module
-pub use crate::llm::policy::pii::PiiType;
+pub use crate::llm::policy::pii::{PiiSensitivity, PiiType};
 
 /// Action to take when PII is detected
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -36,7 +73,8 @@ pub enum PiiAction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PiiGuardConfig {
-	/// Which PII types to detect (defaults to all)
+	/// Which PII types to detect (defaults to email, phone number, SSN, credit card,
+	/// Canadian SIN and URL; other types must be listed explicitly)
 	#[serde(default = "default_pii_types")]
 	pub detect: Vec<PiiType>,
 
@@ -44,17 +82,60 @@ pub struct PiiGuardConfig {
 	#[serde(default)]
 	pub action: PiiAction,
 
+	/// Per-type overrides of `action`, e.g. reject SSNs while masking emails. Any
+	/// rejecting type found rejects the whole message.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub type_actions: HashMap<PiiType, PiiAction>,
+
+	/// Per-tier overrides of `action`, e.g. reject `highly_sensitive` types while masking
+	/// `sensitive` ones. Entries in `type_actions` take precedence.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub sensitivity_actions: HashMap<PiiSensitivity, PiiAction>,
+
+	/// Names of registered recognizers (see `pii::register_recognizer`) to run in
+	/// addition to `detect`; they use `min_score`
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub custom_recognizers: Vec<String>,
+
 	/// Minimum confidence score to trigger detection (0.0 - 1.0)
 	#[serde(default = "default_min_score")]
 	pub min_score: f32,
 
+	/// Per-type overrides of `min_score`, e.g. a higher bar for credit cards than emails
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub per_type_min_score: HashMap<PiiType, f32>,
+
+	/// Also mask PII in tool call arguments before they are forwarded upstream (only
+	/// used when action is Mask). Off by default, since many tools need the real values.
+	#[serde(default)]
+	pub mask_arguments: bool,
+
 	/// Custom rejection message (only used when action is Reject)
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub rejection_message: Option<String>,
+
+	/// Also scan base64-encoded gzip/deflate content, decompressed up to a size limit.
+	/// Content over the limit is rejected.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub compressed: Option<CompressedContentConfig>,
+
+	/// Also scan text embedded as base64 `data:` URIs, decoded up to a size limit.
+	/// Larger URIs are scanned as plain strings.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub data_uris: Option<DataUriConfig>,
 }
 
+/// Types detected when `detect` is not set. Types added since are opt-in, so existing
+/// guards do not start masking new kinds of content on upgrade.
 fn default_pii_types() -> Vec<PiiType> {
-	PiiType::all()
+	vec![
+		PiiType::Email,
+		PiiType::PhoneNumber,
+		PiiType::Ssn,
+		PiiType::CreditCard,
+		PiiType::CaSin,
+		PiiType::Url,
+	]
 }
 
 fn default_min_score() -> f32 {
@@ -66,97 +147,179 @@ impl Default for PiiGuardConfig {
 		Self {
 			detect: default_pii_types(),
 			action: PiiAction::default(),
+			type_actions: HashMap::new(),
+			sensitivity_actions: HashMap::new(),
+			custom_recognizers: Vec::new(),
 			min_score: default_min_score(),
+			per_type_min_score: HashMap::new(),
+			mask_arguments: false,
 			rejection_message: None,
+			compressed: None,
+			data_uris: None,
 		}
 	}
 }
 
+/// Reject confidence thresholds outside 0.0 - 1.0
+fn check_score(field: String, score: f32) -> Result<(), GuardError> {
+	if (0.0..=1.0).contains(&score) {
+		return Ok(());
+	}
+	Err(GuardError::InvalidField {
+		field,
+		reason: format!("score {} is outside 0.0 - 1.0", score),
+	})
+}
+
 /// PII Detection Guard for MCP Security
 pub struct PiiGuard {
 	config: PiiGuardConfig,
+	/// Registered recognizers named in `config.custom_recognizers`
+	custom: Vec<pii::SharedRecognizer>,
 }
 
 impl PiiGuard {
-	pub fn new(config: PiiGuardConfig) -> Self {
+	pub fn new(config: PiiGuardConfig) -> Result<Self, GuardError> {
 		tracing::info!(
 			detect_types = ?config.detect,
 			action = ?config.action,
 			min_score = config.min_score,
 			"PiiGuard::new - creating guard with config"
 		);
-		Self { config }
+		check_score("min_score".to_string(), config.min_score)?;
+		for (pii_type, score) in &config.per_type_min_score {
+			let key = serde_json::to_value(pii_type).unwrap_or_default();
+			check_score(
+				format!("per_type_min_score.{}", key.as_str().unwrap_or_default()),
+				*score,
+			)?;
+		}
+		let custom = config
+			.custom_recognizers
+			.iter()
+			.enumerate()
+			.map(|(i, name)| {
+				pii::registered_recognizer(name).ok_or_else(|| GuardError::InvalidField {
+					field: format!("custom_recognizers[{}]", i),
+					reason: format!(
+						"unknown PII recognizer '{}' (registered: {})",
+						name,
+						pii::registered_recognizer_names().join(", ")
+					),
+				})
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self { config, custom })
 	}
 
 	/// Scan text for all configured PII types
 	fn scan_text(&self, text: &str) -> Vec<pii::RecognizerResult> {
+		self
+			.scan(text)
+			.into_iter()
+			.map(|(result, _)| result)
+			.collect()
+	}
+
+	/// Scan text for all configured PII types, with the action each detection calls for
+	fn scan(&self, text: &str) -> Vec<(pii::RecognizerResult, PiiAction)> {
 		let mut all_results = Vec::new();
 
 		for pii_type in &self.config.detect {
+			let action = self
+				.config
+				.type_actions
+				.get(pii_type)
+				.or_else(|| self.config.sensitivity_actions.get(&pii_type.sensitivity()))
+				.copied()
+				.unwrap_or(self.config.action);
 			let results = pii_type.recognizer().recognize(text);
+			let min_score = self
+				.config
+				.per_type_min_score
+				.get(pii_type)
+				.copied()
+				.unwrap_or(self.config.min_score);
 
 			// Filter by minimum score
 			for result in results {
-				if result.score >= self.config.min_score {
-					all_results.push(result);
+				if result.score >= min_score {
+					all_results.push((result, action));
 				}
 			}
 		}
 
-		// Sort by position (reverse order for masking)
-		all_results.sort_by(|a, b| b.start.cmp(&a.start));
+		for recognizer in &self.custom {
+			all_results.extend(
+				recognizer
+					.recognize(text)
+					.into_iter()
+					.filter(|r| r.score >= self.config.min_score)
+					.map(|r| (r, self.config.action)),
+			);
+		}
+
+		// Sort by position (reverse order for masking), breaking ties on span end and
+		// entity type so the order does not depend on the order of `detect`
+		all_results.sort_by(|(a, _), (b, _)| {
+			b.start
+				.cmp(&a.start)
+				.then_with(|| b.end.cmp(&a.end))
+				.then_with(|| a.entity_type.cmp(&b.entity_type))
+		});
 		all_results
 	}
 
-	/// Apply masking to text, replacing PII with <ENTITY_TYPE> placeholders
-	fn mask_text(&self, text: &str, results: &[pii::RecognizerResult]) -> String {
-		if results.is_empty() {
-			return text.to_string();
+	/// The decompressed content of `text`, if it is compressed and `compressed` is set
+	fn decompress(&self, text: &str) -> Result<Option<compressed::Decompressed>, DecompressError> {
+		match &self.config.compressed {
+			Some(config) => compressed::decompress(text, config),
+			None => Ok(None),
 		}
+	}
 
-		// Sort by score descending so higher-confidence matches win overlap resolution.
-		// This prevents e.g. a URL match on "example.com" (score 0.5) from beating
-		// an email match on "user@example.com" (score 0.85).
-		let mut sorted_results: Vec<&pii::RecognizerResult> = results.iter().collect();
-		sorted_results.sort_by(|a, b| {
-			b.score
-				.partial_cmp(&a.score)
-				.unwrap_or(std::cmp::Ordering::Equal)
-				.then_with(|| {
-					// For equal scores, prefer longer matches
-					let a_len = a.end.saturating_sub(a.start);
-					let b_len = b.end.saturating_sub(b.start);
-					b_len.cmp(&a_len)
-				})
-		});
-
-		// Greedily select non-overlapping results (highest score first)
-		let mut non_overlapping: Vec<&pii::RecognizerResult> = Vec::new();
-		for result in &sorted_results {
-			// Validate byte indices are within bounds and at char boundaries
-			if result.start > text.len()
-				|| result.end > text.len()
-				|| !text.is_char_boundary(result.start)
-				|| !text.is_char_boundary(result.end)
-			{
-				continue;
-			}
+	/// The decoded content of `text`, if it is a textual data URI and `data_uris` is set
+	fn decode_data_uri(&self, text: &str) -> Option<data_uri::DataUri> {
+		data_uri::decode(text, self.config.data_uris.as_ref()?)
+	}
 
-			let overlaps = non_overlapping
-				.iter()
-				.any(|existing| result.end > existing.start && result.start < existing.end);
+	/// The decoded text of a resource's `blob`, if `contents` is a textual blob resource
+	fn decode_resource_blob(
+		&self,
+		contents: &serde_json::Map<String, serde_json::Value>,
+	) -> Option<String> {
+		let max_decoded_bytes = self
+			.config
+			.data_uris
+			.clone()
+			.unwrap_or_default()
+			.max_decoded_bytes;
+		resource_blob::decode(contents, max_decoded_bytes)
+	}
 
-			if !overlaps {
-				non_overlapping.push(result);
-			}
+	/// Apply masking to text, replacing PII with <ENTITY_TYPE> placeholders
+	fn mask_text(&self, text: &str, results: &[pii::RecognizerResult]) -> String {
+		if results.is_empty() {
+			return text.to_string();
 		}
 
-		// Sort by position (reverse order) for safe replacement from end to start
-		non_overlapping.sort_by(|a, b| b.start.cmp(&a.start));
-
-		// Build new string with replacements
+		// Validate byte indices are within bounds and at char boundaries
+		let valid = results
+			.iter()
+			.filter(|r| {
+				r.start <= text.len()
+					&& r.end <= text.len()
+					&& text.is_char_boundary(r.start)
+					&& text.is_char_boundary(r.end)
+			})
+			.cloned()
+			.collect();
+
+		let merged = pii::RecognizerResult::merge_overlapping(valid);
+
+		// Replace from end to start so earlier byte offsets stay valid
 		let mut masked = text.to_string();
-		for result in non_overlapping {
+		for result in merged.iter().rev() {
 			masked.replace_range(
 				result.start..result.end,
 				&format!("<{}>", result.entity_type.to_uppercase()),
@@ -172,6 +335,23 @@ impl PiiGuard {
 
 		match value {
 			serde_json::Value::String(s) => {
+				if let Some(uri) = self.decode_data_uri(s) {
+					let results = self.scan_text(&uri.text);
+					if !results.is_empty() {
+						*s = uri.encode(&self.mask_text(&uri.text, &results));
+						any_masked = true;
+					}
+					return any_masked;
+				}
+				// Oversized compressed content was already rejected by detection
+				if let Ok(Some(inner)) = self.decompress(s) {
+					let results = self.scan_text(&inner.text);
+					if !results.is_empty() {
+						*s = compressed::compress(&self.mask_text(&inner.text, &results), inner.compression);
+						any_masked = true;
+					}
+					return any_masked;
+				}
 				let results = self.scan_text(s);
 				if !results.is_empty() {
 					*s = self.mask_text(s, &results);
@@ -186,7 +366,20 @@ impl PiiGuard {
 				}
 			},
 			serde_json::Value::Object(obj) => {
-				for (_, val) in obj {
+				let blob = self.decode_resource_blob(obj);
+				// Members are masked independently, so traversal order does not affect the
+				// output; key order itself is preserved
+				for (key, val) in obj {
+					if key == "blob"
+						&& let Some(text) = &blob
+					{
+						let results = self.scan_text(text);
+						if !results.is_empty() {
+							*val = resource_blob::encode(&self.mask_text(text, &results)).into();
+							any_masked = true;
+						}
+						continue;
+					}
 					if self.mask_json_value(val) {
 						any_masked = true;
 					}
@@ -198,11 +391,15 @@ impl PiiGuard {
 		any_masked
 	}
 
-	/// Scan JSON for PII and collect all detections with their paths
-	fn collect_detections(&self, value: &serde_json::Value) -> Vec<PiiDetection> {
+	/// Scan JSON for PII and collect all detections with their paths.
+	/// Fails with the path of the first compressed string over the decompression limit.
+	fn collect_detections(
+		&self,
+		value: &serde_json::Value,
+	) -> Result<Vec<PiiDetection>, (Vec<String>, DecompressError)> {
 		let mut detections = Vec::new();
-		self.collect_detections_recursive(value, Vec::new(), &mut detections);
-		detections
+		self.collect_detections_recursive(value, Vec::new(), &mut detections)?;
+		Ok(detections)
 	}
 
 	fn collect_detections_recursive(
@@ -210,15 +407,29 @@ impl PiiGuard {
 		value: &serde_json::Value,
 		path: Vec<String>,
 		results: &mut Vec<PiiDetection>,
-	) {
+	) -> Result<(), (Vec<String>, DecompressError)> {
 		match value {
 			serde_json::Value::String(s) => {
-				let scan_results = self.scan_text(s);
-				for result in scan_results {
+				let uri = self.decode_data_uri(s);
+				let inner = match &uri {
+					Some(_) => None,
+					None => match self.decompress(s) {
+						Ok(inner) => inner,
+						Err(e) => return Err((path, e)),
+					},
+				};
+				let (text, source) = match (&uri, &inner) {
+					(Some(uri), _) => (uri.text.as_str(), Some(data_uri::SOURCE)),
+					(None, Some(inner)) => (inner.text.as_str(), Some(inner.compression.source())),
+					(None, None) => (s.as_str(), None),
+				};
+				for (result, action) in self.scan(text) {
 					results.push(PiiDetection {
 						path: path.clone(),
-						entity_type: result.entity_type.clone(),
+						entity_type: result.entity_type,
 						score: result.score,
+						source,
+						action,
 					});
 				}
 			},
@@ -226,23 +437,63 @@ impl PiiGuard {
 				for (i, item) in arr.iter().enumerate() {
 					let mut new_path = path.clone();
 					new_path.push(i.to_string());
-					self.collect_detections_recursive(item, new_path, results);
+					self.collect_detections_recursive(item, new_path, results)?;
 				}
 			},
 			serde_json::Value::Object(obj) => {
-				for (key, val) in obj {
+				let blob = self.decode_resource_blob(obj);
+				// Visit keys in sorted order so detections (and the audit details built from
+				// them) do not depend on how the object was constructed
+				for (key, val) in obj.iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
 					let mut new_path = path.clone();
 					new_path.push(key.clone());
-					self.collect_detections_recursive(val, new_path, results);
+					if key == "blob"
+						&& let Some(text) = &blob
+					{
+						for (result, action) in self.scan(text) {
+							results.push(PiiDetection {
+								path: new_path.clone(),
+								entity_type: result.entity_type,
+								score: result.score,
+								source: Some(resource_blob::SOURCE),
+								action,
+							});
+						}
+						continue;
+					}
+					self.collect_detections_recursive(val, new_path, results)?;
 				}
 			},
 			_ => {}, // Numbers, bools, nulls - skip
 		}
+		Ok(())
 	}
 
-	/// Evaluate a JSON value for PII and return the appropriate decision
-	fn evaluate_json(&self, json: &serde_json::Value, context: &GuardContext) -> GuardResult {
-		let detections = self.collect_detections(json);
+	/// Evaluate a JSON value for PII and return the appropriate decision.
+	/// With `warn_on_mask`, masking also attaches a warning telling the client that
+	/// content was redacted.
+	fn evaluate_json(
+		&self,
+		json: &serde_json::Value,
+		context: &GuardContext,
+		warn_on_mask: bool,
+	) -> GuardResult {
+		let detections = match self.collect_detections(json) {
+			Ok(detections) => detections,
+			Err((path, e)) => {
+				tracing::warn!(
+					server = %context.server_name,
+					path = %path.join("."),
+					error = %e,
+					"Compressed content too large to scan for PII"
+				);
+				return Ok(GuardDecision::Deny(DenyReason {
+					code: "compressed_content_too_large".to_string(),
+					message: format!("Rejected: {}", e),
+					details: Some(serde_json::json!({ "path": path.join(".") })),
+				}));
+			},
+		};
 
 		if detections.is_empty() {
 			return Ok(GuardDecision::Allow);
@@ -255,39 +506,57 @@ impl PiiGuard {
 				"PII detected in MCP message"
 		);
 
-		match self.config.action {
-			PiiAction::Reject => {
-				let message = self.config.rejection_message.clone().unwrap_or_else(|| {
-					format!(
-						"Request rejected: {} PII item(s) detected",
-						detections.len()
-					)
-				});
-
-				let details = serde_json::json!({
-						"detections": detections.iter().map(|d| {
-								serde_json::json!({
-										"type": d.entity_type,
-										"path": d.path.join("."),
-										"score": d.score,
-								})
-						}).collect::<Vec<_>>()
-				});
-
-				Ok(GuardDecision::Deny(DenyReason {
-					code: "pii_detected".to_string(),
-					message,
-					details: Some(details),
-				}))
-			},
-			PiiAction::Mask => {
-				// Return Modify decision with Transform action containing masked JSON
-				let mut masked_json = json.clone();
-				self.mask_json_value(&mut masked_json);
+		let rejected: Vec<&PiiDetection> = detections
+			.iter()
+			.filter(|d| d.action == PiiAction::Reject)
+			.collect();
+		if !rejected.is_empty() {
+			let message = self
+				.config
+				.rejection_message
+				.clone()
+				.unwrap_or_else(|| format!("Request rejected: {} PII item(s) detected", rejected.len()));
+
+			let details = serde_json::json!({
+					"detections": rejected.iter().map(|d| {
+							let mut detection = serde_json::json!({
+									"type": d.entity_type,
+									"path": d.path.join("."),
+									"score": d.score,
+							});
+							if let Some(source) = d.source {
+								detection["source"] = source.into();
+							}
+							detection
+					}).collect::<Vec<_>>()
+			});
 
-				Ok(GuardDecision::Modify(ModifyAction::Transform(masked_json)))
-			},
+			return Ok(GuardDecision::Deny(DenyReason {
+				code: "pii_detected".to_string(),
+				message,
+				details: Some(details),
+			}));
 		}
+
+		// Return Modify decision with Transform action containing masked JSON
+		let mut masked_json = json.clone();
+		self.mask_json_value(&mut masked_json);
+
+		if !warn_on_mask {
+			return Ok(GuardDecision::Modify(ModifyAction::Transform(masked_json)));
+		}
+
+		let mut types: Vec<&str> = detections.iter().map(|d| d.entity_type.as_str()).collect();
+		types.sort_unstable();
+		types.dedup();
+		Ok(GuardDecision::Modify(ModifyAction::Multiple(vec![
+			ModifyAction::Transform(masked_json),
+			ModifyAction::AddWarning(format!(
+				"{} PII item(s) redacted: {}",
+				detections.len(),
+				types.join(", ")
+			)),
+		])))
 	}
 }
 
@@ -296,6 +565,10 @@ struct PiiDetection {
 	path: Vec<String>,
 	entity_type: String,
 	score: f32,
+	/// Encoding the PII was found inside (compression or data URI), if any
+	source: Option<&'static str>,
+	/// Action configured for the detected type
+	action: PiiAction,
 }
 
 impl NativeGuard for PiiGuard {
@@ -311,32 +584,43 @@ impl NativeGuard for PiiGuard {
 		);
 
 		// For tools/list, we scan tool descriptions
-		for tool in tools {
-			// Scan tool description
-			if let Some(desc) = &tool.description {
-				let results = self.scan_text(desc.as_ref());
-				if !results.is_empty() {
-					match self.config.action {
-						PiiAction::Reject => {
-							return Ok(GuardDecision::Deny(DenyReason {
-								code: "pii_in_tool_description".to_string(),
-								message: format!("PII detected in tool '{}' description", tool.name),
-								details: None,
-							}));
-						},
-						PiiAction::Mask => {
-							// For tools_list, we log warning but allow (can't modify the slice)
-							tracing::warn!(
-									tool = %tool.name,
-									"PII detected in tool description (mask mode - allowing)"
-							);
-						},
-					}
-				}
+		let mut masked_tools = Vec::new();
+		for (index, tool) in tools.iter().enumerate() {
+			let Some(desc) = &tool.description else {
+				continue;
+			};
+			let results = self.scan(desc.as_ref());
+			if results.is_empty() {
+				continue;
+			}
+			if results
+				.iter()
+				.any(|(_, action)| *action == PiiAction::Reject)
+			{
+				return Ok(GuardDecision::Deny(DenyReason {
+					code: "pii_in_tool_description".to_string(),
+					message: format!("PII detected in tool '{}' description", tool.name),
+					details: None,
+				}));
 			}
+			let results = results.into_iter().map(|(result, _)| result).collect_vec();
+			masked_tools.push((index, self.mask_text(desc, &results)));
+		}
+		if masked_tools.is_empty() {
+			return Ok(GuardDecision::Allow);
 		}
 
-		Ok(GuardDecision::Allow)
+		tracing::warn!(
+			server = %context.server_name,
+			tools = ?masked_tools.iter().map(|(i, _)| &tools[*i].name).collect::<Vec<_>>(),
+			"PII masked in tool descriptions"
+		);
+		let mut value = serde_json::to_value(tools)
+			.map_err(|e| GuardError::ExecutionError(format!("Failed to serialize tools: {}", e)))?;
+		for (index, masked) in masked_tools {
+			value[index]["description"] = serde_json::Value::String(masked);
+		}
+		Ok(GuardDecision::Modify(ModifyAction::Transform(value)))
 	}
 
 	fn evaluate_tool_invoke(
@@ -346,26 +630,23 @@ impl NativeGuard for PiiGuard {
 		context: &GuardContext,
 	) -> GuardResult {
 		tracing::info!(
-				tool = %tool_name,
-				server = %context.server_name,
-				arguments = %arguments,
-				action = ?self.config.action,
-				detect_types = ?self.config.detect,
-				"PiiGuard::evaluate_tool_invoke called"
+			tool = %tool_name,
+			server = %context.server_name,
+			arguments = %redacted_tool_arguments(tool_name, arguments),
+			action = ?self.config.action,
+			detect_types = ?self.config.detect,
+			"PiiGuard::evaluate_tool_invoke called"
 		);
 
-		match self.config.action {
-			PiiAction::Reject => {
-				// For reject mode, check arguments and deny if PII found
-				let result = self.evaluate_json(arguments, context);
-				tracing::info!(result = ?result, "PiiGuard::evaluate_tool_invoke result");
-				result
-			},
-			PiiAction::Mask => {
-				// For mask mode, allow the tool invocation to proceed.
-				// Masking arguments would break the MCP server (it needs real values).
-				// PII masking will happen on the RESPONSE path instead.
-				let detections = self.collect_detections(arguments);
+		if self.config.mask_arguments {
+			// The masked arguments replace the originals in the forwarded tools/call
+			return self.evaluate_json(arguments, context, false);
+		}
+		match self.collect_detections(arguments) {
+			// Only masking types found: allow the tool invocation to proceed.
+			// Masking arguments would break the MCP server (it needs real values).
+			// PII masking will happen on the RESPONSE path instead.
+			Ok(detections) if detections.iter().all(|d| d.action == PiiAction::Mask) => {
 				if !detections.is_empty() {
 					tracing::info!(
 						tool = %tool_name,
@@ -375,6 +656,13 @@ impl NativeGuard for PiiGuard {
 				}
 				Ok(GuardDecision::Allow)
 			},
+			Err(_) if self.config.action == PiiAction::Mask => Ok(GuardDecision::Allow),
+			// For reject mode, deny if rejecting PII found
+			_ => {
+				let result = self.evaluate_json(arguments, context, false);
+				tracing::info!(result = ?result, "PiiGuard::evaluate_tool_invoke result");
+				result
+			},
 		}
 	}
 
@@ -384,7 +672,7 @@ impl NativeGuard for PiiGuard {
 				"PiiGuard::evaluate_request called"
 		);
 
-		self.evaluate_json(request, context)
+		self.evaluate_json(request, context, false)
 	}
 
 	fn evaluate_response(&self, response: &serde_json::Value, context: &GuardContext) -> GuardResult {
@@ -393,7 +681,25 @@ impl NativeGuard for PiiGuard {
 				"PiiGuard::evaluate_response called"
 		);
 
-		self.evaluate_json(response, context)
+		self.evaluate_json(response, context, true)
+	}
+
+	fn get_settings_schema(&self) -> Option<String> {
+		super::settings_schema("pii")
+	}
+
+	fn get_default_config(&self) -> Option<String> {
+		super::default_config("pii")
+	}
+
+	fn regex_stats(&self) -> RegexStats {
+		let built_in = self
+			.config
+			.detect
+			.iter()
+			.flat_map(|pii_type| pii_type.recognizer().regexes());
+		let custom = self.custom.iter().flat_map(|r| r.regexes());
+		RegexStats::of(built_in.chain(custom))
 	}
 }
 
@@ -416,9 +722,10 @@ mod tests {
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let request = serde_json::json!({
@@ -462,9 +769,10 @@ mod tests {
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: Some("SSN data not allowed".to_string()),
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let request = serde_json::json!({
@@ -489,9 +797,10 @@ mod tests {
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let request = serde_json::json!({
@@ -510,9 +819,10 @@ mod tests {
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let request = serde_json::json!({
@@ -543,9 +853,10 @@ mod tests {
 			action: PiiAction::Reject,
 			min_score: 0.6, // High threshold - weak SSN patterns won't trigger
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		// Weak SSN pattern (just 9 digits) has low confidence
@@ -580,12 +891,61 @@ rejection_message: "PII not allowed in MCP requests"
 	#[test]
 	fn test_default_config() {
 		let config = PiiGuardConfig::default();
-		assert_eq!(config.detect.len(), 6); // All PII types
+		assert_eq!(config.detect.len(), 6); // Types added later are opt-in
+		assert!(!config.detect.contains(&PiiType::DateOfBirth));
 		assert_eq!(config.action, PiiAction::Mask);
 		assert_eq!(config.min_score, 0.3);
 		assert!(config.rejection_message.is_none());
 	}
 
+	#[test]
+	fn test_per_type_min_score_deserialization() {
+		let yaml = r#"
+min_score: 0.3
+per_type_min_score:
+  credit_card: 0.9
+  email: 0.5
+"#;
+
+		let config: PiiGuardConfig = serde_yaml::from_str(yaml).unwrap();
+		assert_eq!(config.min_score, 0.3);
+		assert_eq!(config.per_type_min_score.len(), 2);
+		assert_eq!(config.per_type_min_score[&PiiType::CreditCard], 0.9);
+		assert_eq!(config.per_type_min_score[&PiiType::Email], 0.5);
+	}
+
+	#[test]
+	fn test_per_type_min_score_overrides_global() {
+		let config = PiiGuardConfig {
+			detect: vec![PiiType::Email, PiiType::CreditCard],
+			action: PiiAction::Mask,
+			min_score: 0.3,
+			// Credit card matches score 0.3, emails 0.85
+			per_type_min_score: HashMap::from([(PiiType::CreditCard, 0.5), (PiiType::Email, 0.8)]),
+			rejection_message: None,
+			..Default::default()
+		};
+
+		let guard = PiiGuard::new(config).unwrap();
+		let context = create_test_context();
+
+		let response = serde_json::json!({
+				"text": "Card 4111111111111111, contact alice@example.com"
+		});
+
+		match guard.evaluate_response(&response, &context) {
+			Ok(GuardDecision::Modify(action)) => {
+				let mut masked = response.clone();
+				action.apply(&mut masked, &mut Vec::new());
+				assert_eq!(
+					masked["text"],
+					"Card 4111111111111111, contact <EMAIL_ADDRESS>"
+				);
+			},
+			other => panic!("Expected masked email, got {:?}", other),
+		}
+	}
+
 	#[test]
 	fn test_credit_card_detection() {
 		let config = PiiGuardConfig {
@@ -593,9 +953,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: Some("Credit card not allowed".to_string()),
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		// Test various credit card formats
@@ -642,9 +1003,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let request = serde_json::json!({
@@ -675,9 +1037,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let test_cases = vec![
@@ -720,9 +1083,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: Some("Phone numbers not allowed".to_string()),
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		// Test various phone formats (based on phonenumber library validation)
@@ -760,6 +1124,65 @@ rejection_message: "PII not allowed in MCP requests"
 		}
 	}
 
+	#[test]
+	fn test_date_of_birth_masking() {
+		let config = PiiGuardConfig {
+			detect: vec![PiiType::DateOfBirth],
+			action: PiiAction::Mask,
+			min_score: 0.3,
+			rejection_message: None,
+			..Default::default()
+		};
+
+		let guard = PiiGuard::new(config).unwrap();
+		let context = create_test_context();
+
+		let response = serde_json::json!({
+				"patient": "DOB: 01/02/1990",
+				"visit": "Scheduled for 2024-06-15"
+		});
+
+		match guard.evaluate_response(&response, &context) {
+			Ok(GuardDecision::Modify(action)) => {
+				let mut masked = response.clone();
+				action.apply(&mut masked, &mut Vec::new());
+				assert_eq!(masked["patient"], "DOB: <DATE_OF_BIRTH>");
+				// Arbitrary dates without birthdate context stay below min_score
+				assert_eq!(masked["visit"], "Scheduled for 2024-06-15");
+			},
+			other => panic!("Expected masked date of birth, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn test_address_masking() {
+		let config = PiiGuardConfig {
+			detect: vec![PiiType::Address],
+			action: PiiAction::Mask,
+			min_score: 0.3,
+			rejection_message: None,
+			..Default::default()
+		};
+
+		let guard = PiiGuard::new(config).unwrap();
+		let context = create_test_context();
+
+		let response = serde_json::json!({
+				"customer": "Mailing address: 742 Evergreen Terrace, Springfield, IL 62704",
+				"note": "Came up with 123 ideas"
+		});
+
+		match guard.evaluate_response(&response, &context) {
+			Ok(GuardDecision::Modify(action)) => {
+				let mut masked = response.clone();
+				action.apply(&mut masked, &mut Vec::new());
+				assert_eq!(masked["customer"], "Mailing address: <ADDRESS>");
+				assert_eq!(masked["note"], "Came up with 123 ideas");
+			},
+			other => panic!("Expected masked address, got {:?}", other),
+		}
+	}
+
 	#[test]
 	fn test_canadian_sin_detection() {
 		let config = PiiGuardConfig {
@@ -767,9 +1190,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: Some("Canadian SIN not allowed".to_string()),
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let test_cases = vec![
@@ -813,9 +1237,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let arguments = serde_json::json!({
@@ -832,6 +1257,105 @@ rejection_message: "PII not allowed in MCP requests"
 		);
 	}
 
+	#[test]
+	fn test_tool_invoke_mask_arguments() {
+		let config: PiiGuardConfig =
+			serde_yaml::from_str("detect: [email]\nmin_score: 0.0\nmask_arguments: true\n").unwrap();
+		let guard = PiiGuard::new(config).unwrap();
+
+		let arguments = serde_json::json!({
+			"to": "john.doe@company.com",
+			"subject": "hello"
+		});
+		let result = guard.evaluate_tool_invoke("send_email", &arguments, &create_test_context());
+		let Ok(GuardDecision::Modify(ModifyAction::Transform(masked))) = result else {
+			panic!("Expected Modify(Transform), got {:?}", result);
+		};
+		assert_eq!(
+			masked,
+			serde_json::json!({"to": "<EMAIL_ADDRESS>", "subject": "hello"})
+		);
+	}
+
+	/// Proprietary account numbers, e.g. ACCT-12345678
+	struct AccountNumberRecognizer;
+
+	impl pii::Recognizer for AccountNumberRecognizer {
+		fn recognize(&self, text: &str) -> Vec<pii::RecognizerResult> {
+			let re = regex::Regex::new(r"\bACCT-[0-9]{8}\b").unwrap();
+			re.find_iter(text)
+				.map(|m| pii::RecognizerResult {
+					entity_type: "ACCOUNT_NUMBER".to_string(),
+					matched: m.as_str().to_string(),
+					start: m.start(),
+					end: m.end(),
+					score: 0.9,
+				})
+				.collect()
+		}
+		fn name(&self) -> &str {
+			"AccountNumberRecognizer"
+		}
+	}
+
+	#[test]
+	fn test_invalid_settings_name_field() {
+		let config: PiiGuardConfig = serde_yaml::from_str(
+			"detect: [email]\ncustom_recognizers: [test_field_account, no_such_recognizer]\n",
+		)
+		.unwrap();
+		pii::register_recognizer("test_field_account", Box::new(AccountNumberRecognizer));
+		let result = PiiGuard::new(config);
+		assert!(pii::unregister_recognizer("test_field_account"));
+		let Err(GuardError::InvalidField { field, reason }) = result else {
+			panic!("Expected an unknown recognizer error");
+		};
+		assert_eq!(field, "custom_recognizers[1]");
+		assert!(reason.contains("no_such_recognizer"), "{reason}");
+
+		let config: PiiGuardConfig =
+			serde_yaml::from_str("per_type_min_score: {credit_card: 1.5}\n").unwrap();
+		let Err(err) = PiiGuard::new(config) else {
+			panic!("Expected an out of range score error");
+		};
+		assert!(
+			err
+				.to_string()
+				.starts_with("per_type_min_score.credit_card: "),
+			"{err}"
+		);
+	}
+
+	#[test]
+	fn test_registered_recognizer() {
+		let config: PiiGuardConfig =
+			serde_yaml::from_str("detect: [email]\ncustom_recognizers: [test_account_number]\n").unwrap();
+		// Names are resolved when the guard is created
+		let Err(GuardError::InvalidField { field, reason }) = PiiGuard::new(config.clone()) else {
+			panic!("Expected an unknown recognizer error");
+		};
+		assert_eq!(field, "custom_recognizers[0]");
+		assert!(reason.contains("test_account_number"));
+
+		pii::register_recognizer("test_account_number", Box::new(AccountNumberRecognizer));
+		let guard = PiiGuard::new(config).unwrap();
+		assert!(pii::unregister_recognizer("test_account_number"));
+
+		let response = serde_json::json!({
+			"result": {"text": "Account ACCT-12345678 belongs to jane.doe@example.com"}
+		});
+		let result = guard.evaluate_response(&response, &create_test_context());
+		let Ok(GuardDecision::Modify(action)) = result else {
+			panic!("Expected Modify, got {:?}", result);
+		};
+		let mut masked = response.clone();
+		action.apply(&mut masked, &mut Vec::new());
+		assert_eq!(
+			masked["result"]["text"],
+			"Account <ACCOUNT_NUMBER> belongs to <EMAIL_ADDRESS>"
+		);
+	}
+
 	#[test]
 	fn test_tool_invoke_rejection() {
 		let config = PiiGuardConfig {
@@ -839,9 +1363,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: Some("Credit card data not allowed in tool calls".to_string()),
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let arguments = serde_json::json!({
@@ -866,9 +1391,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let response = serde_json::json!({
@@ -883,7 +1409,9 @@ rejection_message: "PII not allowed in MCP requests"
 		let result = guard.evaluate_response(&response, &context);
 
 		match result {
-			Ok(GuardDecision::Modify(ModifyAction::Transform(masked))) => {
+			Ok(GuardDecision::Modify(action)) => {
+				let mut masked = response.clone();
+				action.apply(&mut masked, &mut Vec::new());
 				let users = masked["result"]["users"].as_array().unwrap();
 				for user in users {
 					assert!(
@@ -911,9 +1439,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let tool_with_pii = Tool {
@@ -942,6 +1471,39 @@ rejection_message: "PII not allowed in MCP requests"
 		}
 	}
 
+	#[test]
+	fn test_tools_list_pii_in_description_masked() {
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			min_score: 0.0,
+			..Default::default()
+		})
+		.unwrap();
+		let tools: Vec<rmcp::model::Tool> = serde_json::from_value(serde_json::json!([
+			{"name": "file_reader", "description": "Reads files", "inputSchema": {"type": "object"}},
+			{"name": "no_description", "inputSchema": {"type": "object"}},
+			{
+				"name": "support",
+				"description": "Escalates to admin@example.com",
+				"inputSchema": {"type": "object"}
+			}
+		]))
+		.unwrap();
+
+		let result = guard.evaluate_tools_list(&tools, &create_test_context());
+		let Ok(GuardDecision::Modify(ModifyAction::Transform(value))) = result else {
+			panic!("Expected Transform, got {:?}", result);
+		};
+		let masked: Vec<rmcp::model::Tool> = serde_json::from_value(value).unwrap();
+		assert_eq!(masked.len(), 3);
+		assert_eq!(masked[0].description.as_deref(), Some("Reads files"));
+		assert!(masked[1].description.is_none());
+		assert_eq!(
+			masked[2].description.as_deref(),
+			Some("Escalates to <EMAIL_ADDRESS>")
+		);
+	}
+
 	#[test]
 	fn test_tools_list_clean_descriptions() {
 		use rmcp::model::Tool;
@@ -953,9 +1515,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Reject,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let clean_tool = Tool {
@@ -984,9 +1547,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let request = serde_json::json!({
@@ -1018,6 +1582,54 @@ rejection_message: "PII not allowed in MCP requests"
 		}
 	}
 
+	#[test]
+	fn test_masking_is_deterministic() {
+		let request = serde_json::json!({
+			"zeta": "Reach me at jane.doe@example.com or 555-123-4567",
+			"alpha": ["SSN 123-45-6789", {"url": "https://example.com/u/jane.doe@example.com"}],
+			"card": "4111 1111 1111 1111, call +44 20 7946 0958",
+		});
+		let evaluate = |detect: Vec<PiiType>, request: &serde_json::Value| {
+			let guard = PiiGuard::new(PiiGuardConfig {
+				detect,
+				min_score: 0.0,
+				..Default::default()
+			})
+			.unwrap();
+			match guard.evaluate_request(request, &create_test_context()) {
+				Ok(GuardDecision::Modify(ModifyAction::Transform(masked))) => {
+					serde_json::to_vec(&masked).unwrap()
+				},
+				other => panic!("Expected Modify decision, got {:?}", other),
+			}
+		};
+
+		let expected = evaluate(PiiType::all(), &request);
+		for _ in 0..50 {
+			assert_eq!(evaluate(PiiType::all(), &request), expected);
+		}
+		// Recognizer order does not change which overlapping match wins
+		let reversed = PiiType::all().into_iter().rev().collect();
+		assert_eq!(evaluate(reversed, &request), expected);
+
+		// Detections are reported in sorted key order, however the object was built
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			..Default::default()
+		})
+		.unwrap();
+		let mut reordered = serde_json::Map::new();
+		reordered.insert("b".to_string(), "b@example.com".into());
+		reordered.insert("a".to_string(), "a@example.com".into());
+		let paths = guard
+			.collect_detections(&serde_json::Value::Object(reordered))
+			.unwrap()
+			.into_iter()
+			.map(|d| d.path)
+			.collect_vec();
+		assert_eq!(paths, vec![vec!["a".to_string()], vec!["b".to_string()]]);
+	}
+
 	#[test]
 	fn test_mixed_pii_types_in_single_field() {
 		let config = PiiGuardConfig {
@@ -1025,9 +1637,10 @@ rejection_message: "PII not allowed in MCP requests"
 			action: PiiAction::Mask,
 			min_score: 0.0,
 			rejection_message: None,
+			..Default::default()
 		};
 
-		let guard = PiiGuard::new(config);
+		let guard = PiiGuard::new(config).unwrap();
 		let context = create_test_context();
 
 		let request = serde_json::json!({
@@ -1063,4 +1676,322 @@ rejection_message: "PII not allowed in MCP requests"
 			other => panic!("Expected Modify decision, got {:?}", other),
 		}
 	}
+
+	#[test]
+	fn test_type_actions_reject_overrides_mask() {
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email, PiiType::Ssn],
+			action: PiiAction::Mask,
+			type_actions: HashMap::from([(PiiType::Ssn, PiiAction::Reject)]),
+			..Default::default()
+		})
+		.unwrap();
+		let context = create_test_context();
+
+		// Email only: masked
+		let response = serde_json::json!({"text": "Contact john@example.com"});
+		let Ok(GuardDecision::Modify(ModifyAction::Multiple(actions))) =
+			guard.evaluate_response(&response, &context)
+		else {
+			panic!("expected Modify");
+		};
+		let ModifyAction::Transform(masked) = &actions[0] else {
+			panic!("expected Transform");
+		};
+		assert_eq!(masked["text"], "Contact <EMAIL_ADDRESS>");
+
+		// An SSN rejects the whole message, even alongside masking types
+		let response = serde_json::json!({"text": "Contact john@example.com, SSN 123-45-6789"});
+		let Ok(GuardDecision::Deny(reason)) = guard.evaluate_response(&response, &context) else {
+			panic!("expected Deny");
+		};
+		assert_eq!(reason.code, "pii_detected");
+		let detections = &reason.details.unwrap()["detections"];
+		assert_eq!(detections.as_array().unwrap().len(), 1);
+		assert_eq!(detections[0]["type"], "SSN");
+
+		// Tool arguments are only checked for rejecting types
+		assert!(matches!(
+			guard.evaluate_tool_invoke(
+				"send",
+				&serde_json::json!({"to": "john@example.com"}),
+				&context
+			),
+			Ok(GuardDecision::Allow)
+		));
+		assert!(matches!(
+			guard.evaluate_tool_invoke("send", &serde_json::json!({"ssn": "123-45-6789"}), &context),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+
+	#[test]
+	fn test_sensitivity_actions() {
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email, PiiType::PhoneNumber, PiiType::CreditCard],
+			action: PiiAction::Mask,
+			sensitivity_actions: HashMap::from([
+				(PiiSensitivity::Sensitive, PiiAction::Mask),
+				(PiiSensitivity::HighlySensitive, PiiAction::Reject),
+			]),
+			type_actions: HashMap::from([(PiiType::PhoneNumber, PiiAction::Reject)]),
+			min_score: 0.0,
+			..Default::default()
+		})
+		.unwrap();
+		let context = create_test_context();
+
+		// A sensitive type is masked
+		let response = serde_json::json!({"text": "Contact john@example.com"});
+		let Ok(GuardDecision::Modify(ModifyAction::Multiple(actions))) =
+			guard.evaluate_response(&response, &context)
+		else {
+			panic!("expected Modify");
+		};
+		let ModifyAction::Transform(masked) = &actions[0] else {
+			panic!("expected Transform");
+		};
+		assert_eq!(masked["text"], "Contact <EMAIL_ADDRESS>");
+
+		// A highly sensitive type rejects the message
+		let response =
+			serde_json::json!({"text": "Contact john@example.com, card 4111 1111 1111 1111"});
+		let Ok(GuardDecision::Deny(reason)) = guard.evaluate_response(&response, &context) else {
+			panic!("expected Deny");
+		};
+		let details = reason.details.unwrap();
+		let types = details["detections"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|d| d["type"].as_str().unwrap())
+			.collect_vec();
+		assert!(types.contains(&"CREDIT_CARD"), "{types:?}");
+		assert!(!types.contains(&"EMAIL_ADDRESS"), "{types:?}");
+
+		// A type's own action overrides its tier's
+		let response = serde_json::json!({"text": "Call (555) 123-4567"});
+		assert!(matches!(
+			guard.evaluate_response(&response, &context),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+
+	#[test]
+	fn test_gzipped_pii_detected() {
+		let gzipped = compressed::compress(
+			"Customer record: jane.doe@example.com",
+			compressed::Compression::Gzip,
+		);
+		let response = serde_json::json!({
+			"result": {"content": [{"type": "text", "text": gzipped}]}
+		});
+		let context = create_test_context();
+
+		// Not decompressed unless enabled
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			action: PiiAction::Reject,
+			..Default::default()
+		})
+		.unwrap();
+		assert!(matches!(
+			guard.evaluate_response(&response, &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			action: PiiAction::Reject,
+			compressed: Some(CompressedContentConfig::default()),
+			..Default::default()
+		})
+		.unwrap();
+		let Ok(GuardDecision::Deny(reason)) = guard.evaluate_response(&response, &context) else {
+			panic!("expected Deny");
+		};
+		let detection = &reason.details.unwrap()["detections"][0];
+		assert_eq!(detection["type"], "EMAIL_ADDRESS");
+		assert_eq!(detection["source"], "gzip");
+		assert_eq!(detection["path"], "result.content.0.text");
+
+		// Masking re-compresses the masked text
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			compressed: Some(CompressedContentConfig::default()),
+			..Default::default()
+		})
+		.unwrap();
+		let Ok(GuardDecision::Modify(ModifyAction::Multiple(actions))) =
+			guard.evaluate_response(&response, &context)
+		else {
+			panic!("expected Modify");
+		};
+		let ModifyAction::Transform(masked) = &actions[0] else {
+			panic!("expected Transform");
+		};
+		let masked = masked["result"]["content"][0]["text"].as_str().unwrap();
+		let inner = compressed::decompress(masked, &CompressedContentConfig::default())
+			.unwrap()
+			.unwrap();
+		assert_eq!(inner.text, "Customer record: <EMAIL_ADDRESS>");
+	}
+
+	#[test]
+	fn test_decompression_bomb_rejected() {
+		let guard = PiiGuard::new(PiiGuardConfig {
+			action: PiiAction::Mask,
+			compressed: Some(CompressedContentConfig {
+				max_decompressed_bytes: 64 * 1024,
+			}),
+			..Default::default()
+		})
+		.unwrap();
+		let bomb = compressed::compress(&"a".repeat(8 * 1024 * 1024), compressed::Compression::Gzip);
+		let response = serde_json::json!({
+			"result": {"content": [{"type": "text", "text": bomb}]}
+		});
+
+		let Ok(GuardDecision::Deny(reason)) =
+			guard.evaluate_response(&response, &create_test_context())
+		else {
+			panic!("expected Deny");
+		};
+		assert_eq!(reason.code, "compressed_content_too_large");
+		assert_eq!(reason.details.unwrap()["path"], "result.content.0.text");
+	}
+
+	#[test]
+	fn test_data_uri_pii_masked() {
+		use base64::Engine as _;
+		use base64::engine::general_purpose::STANDARD;
+
+		let uri = format!(
+			"data:text/plain;charset=utf-8;base64,{}",
+			STANDARD.encode("Customer record: jane.doe@example.com")
+		);
+		let response = serde_json::json!({
+			"result": {"content": [{"type": "resource", "resource": {"uri": uri}}]}
+		});
+		let context = create_test_context();
+
+		// Opaque unless enabled
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			action: PiiAction::Reject,
+			..Default::default()
+		})
+		.unwrap();
+		assert!(matches!(
+			guard.evaluate_response(&response, &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			action: PiiAction::Reject,
+			data_uris: Some(DataUriConfig::default()),
+			..Default::default()
+		})
+		.unwrap();
+		let Ok(GuardDecision::Deny(reason)) = guard.evaluate_response(&response, &context) else {
+			panic!("expected Deny");
+		};
+		let detection = &reason.details.unwrap()["detections"][0];
+		assert_eq!(detection["type"], "EMAIL_ADDRESS");
+		assert_eq!(detection["source"], "data_uri");
+		assert_eq!(detection["path"], "result.content.0.resource.uri");
+
+		// Masking re-encodes the masked text into a valid URI of the same media type
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			data_uris: Some(DataUriConfig::default()),
+			..Default::default()
+		})
+		.unwrap();
+		let Ok(GuardDecision::Modify(ModifyAction::Multiple(actions))) =
+			guard.evaluate_response(&response, &context)
+		else {
+			panic!("expected Modify");
+		};
+		let ModifyAction::Transform(masked) = &actions[0] else {
+			panic!("expected Transform");
+		};
+		let masked = masked["result"]["content"][0]["resource"]["uri"]
+			.as_str()
+			.unwrap();
+		let (header, payload) = masked.split_once(',').unwrap();
+		assert_eq!(header, "data:text/plain;charset=utf-8;base64");
+		assert_eq!(
+			STANDARD.decode(payload).unwrap(),
+			b"Customer record: <EMAIL_ADDRESS>"
+		);
+	}
+
+	#[test]
+	fn test_read_resource_contents_masked() {
+		use base64::Engine as _;
+		use base64::engine::general_purpose::STANDARD;
+
+		let response = serde_json::json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"result": {"contents": [
+				{
+					"uri": "file:///records/customer.txt",
+					"mimeType": "text/plain",
+					"text": "Email: jane.doe@example.com, SSN: 123-45-6789"
+				},
+				{
+					"uri": "file:///records/customer.json",
+					"mimeType": "application/json",
+					"blob": STANDARD.encode(r#"{"email": "john.smith@example.com"}"#)
+				},
+				{
+					"uri": "file:///records/photo.png",
+					"mimeType": "image/png",
+					"blob": "iVBORw0KGgo="
+				}
+			]}
+		});
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email, PiiType::Ssn],
+			..Default::default()
+		})
+		.unwrap();
+
+		let Ok(GuardDecision::Modify(ModifyAction::Multiple(actions))) =
+			guard.evaluate_response(&response, &create_test_context())
+		else {
+			panic!("expected Modify");
+		};
+		let ModifyAction::Transform(masked) = &actions[0] else {
+			panic!("expected Transform");
+		};
+		let contents = &masked["result"]["contents"];
+		assert_eq!(contents[0]["text"], "Email: <EMAIL_ADDRESS>, SSN: <SSN>");
+		assert_eq!(contents[0]["uri"], "file:///records/customer.txt");
+		let blob = STANDARD
+			.decode(contents[1]["blob"].as_str().unwrap())
+			.unwrap();
+		assert_eq!(blob, br#"{"email": "<EMAIL_ADDRESS>"}"#);
+		assert_eq!(contents[2]["blob"], "iVBORw0KGgo=");
+
+		// Rejection reports blob detections by the blob's path
+		let guard = PiiGuard::new(PiiGuardConfig {
+			detect: vec![PiiType::Email],
+			action: PiiAction::Reject,
+			..Default::default()
+		})
+		.unwrap();
+		let Ok(GuardDecision::Deny(reason)) =
+			guard.evaluate_response(&response, &create_test_context())
+		else {
+			panic!("expected Deny");
+		};
+		let detections = reason.details.unwrap()["detections"].clone();
+		assert_eq!(detections[0]["path"], "result.contents.0.text");
+		assert_eq!(detections[1]["path"], "result.contents.1.blob");
+		assert_eq!(detections[1]["source"], "resource_blob");
+	}
 }
@@ -0,0 +1,218 @@
+// Reserved Tool Name Detection
+//
+// Blocks tools whose names duplicate MCP protocol methods (`tools/call`,
+// `resources/read`, ...) or names the gateway uses for itself. Such tools are
+// confusing to clients and models at best, and at worst are an attempt to have a
+// tool call mistaken for a protocol operation.
+//
+// Names are compared after lowercasing and dropping separators, so `tools_call`,
+// `Tools.Call` and `toolscall` all match `tools/call`.
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult};
+
+/// Configuration for Reserved Tool Name Detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ReservedNamesConfig {
+	/// Reserved names (defaults to the MCP methods and the gateway's own names)
+	#[serde(default = "default_reserved_names")]
+	pub reserved_names: Vec<String>,
+
+	/// Names reserved in addition to `reserved_names`
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub additional_names: Vec<String>,
+
+	/// Tools exempt from this check (names as reported by the upstream server)
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub allowed_tools: Vec<String>,
+}
+
+fn default_reserved_names() -> Vec<String> {
+	// `ping` is an MCP method too, but it is also a common, legitimate tool name
+	const MCP_METHODS: &[&str] = &[
+		"initialize",
+		"tools/list",
+		"tools/call",
+		"resources/list",
+		"resources/templates/list",
+		"resources/read",
+		"resources/subscribe",
+		"resources/unsubscribe",
+		"prompts/list",
+		"prompts/get",
+		"completion/complete",
+		"logging/setLevel",
+		"sampling/createMessage",
+		"roots/list",
+		"elicitation/create",
+	];
+	const GATEWAY_NAMES: &[&str] = &["agentgateway"];
+	MCP_METHODS
+		.iter()
+		.chain(GATEWAY_NAMES)
+		.map(|s| s.to_string())
+		.collect()
+}
+
+impl Default for ReservedNamesConfig {
+	fn default() -> Self {
+		Self {
+			reserved_names: default_reserved_names(),
+			additional_names: Vec::new(),
+			allowed_tools: Vec::new(),
+		}
+	}
+}
+
+/// Reserved Tool Name Guard implementation
+pub struct ReservedNamesGuard {
+	config: ReservedNamesConfig,
+	/// Normalized reserved name -> configured name
+	reserved: Vec<(String, String)>,
+}
+
+impl ReservedNamesGuard {
+	pub fn new(config: ReservedNamesConfig) -> Self {
+		let reserved = config
+			.reserved_names
+			.iter()
+			.chain(&config.additional_names)
+			.map(|name| (normalize(name), name.clone()))
+			.filter(|(normalized, _)| !normalized.is_empty())
+			.collect();
+		Self { config, reserved }
+	}
+
+	/// The reserved name a tool name duplicates, if any
+	fn reserved_match(&self, tool_name: &str) -> Option<&str> {
+		let tool = normalize(tool_name);
+		self
+			.reserved
+			.iter()
+			.find(|(normalized, _)| *normalized == tool)
+			.map(|(_, name)| name.as_str())
+	}
+}
+
+/// Lowercase and keep only alphanumerics
+fn normalize(name: &str) -> String {
+	name
+		.chars()
+		.filter(|c| c.is_alphanumeric())
+		.flat_map(char::to_lowercase)
+		.collect()
+}
+
+impl NativeGuard for ReservedNamesGuard {
+	fn evaluate_tools_list(
+		&self,
+		tools: &[rmcp::model::Tool],
+		context: &GuardContext,
+	) -> GuardResult {
+		let reserved: Vec<serde_json::Value> = tools
+			.iter()
+			.filter(|t| {
+				!self
+					.config
+					.allowed_tools
+					.iter()
+					.any(|a| a == t.name.as_ref())
+			})
+			.filter_map(|t| {
+				self
+					.reserved_match(&t.name)
+					.map(|name| serde_json::json!({ "tool": t.name, "reserved_name": name }))
+			})
+			.collect();
+
+		if reserved.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "reserved_tool_name".to_string(),
+			message: format!(
+				"Server '{}' exposes {} tool(s) named after reserved protocol or gateway methods",
+				context.server_name,
+				reserved.len()
+			),
+			details: Some(serde_json::json!({ "tools": reserved })),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rmcp::model::Tool;
+
+	fn create_test_tool(name: &str) -> Tool {
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"description": "A tool",
+			"inputSchema": {"type": "object"}
+		}))
+		.unwrap()
+	}
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	#[test]
+	fn test_reserved_tool_name_denied() {
+		let guard = ReservedNamesGuard::new(ReservedNamesConfig::default());
+		for name in ["tools/call", "tools_call", "Resources.Read", "agentgateway"] {
+			let tools = vec![create_test_tool("search"), create_test_tool(name)];
+			let result = guard.evaluate_tools_list(&tools, &create_test_context());
+			let Ok(GuardDecision::Deny(reason)) = result else {
+				panic!("Expected Deny for {name}, got {:?}", result);
+			};
+			assert_eq!(reason.code, "reserved_tool_name");
+			let details = reason.details.unwrap();
+			assert_eq!(details["tools"].as_array().unwrap().len(), 1);
+			assert_eq!(details["tools"][0]["tool"], name);
+		}
+	}
+
+	#[test]
+	fn test_additional_and_allowed_names() {
+		let config: ReservedNamesConfig = serde_yaml::from_str(
+			r#"
+additional_names: [admin/reload]
+allowed_tools: [initialize]
+"#,
+		)
+		.unwrap();
+		assert_eq!(config.reserved_names, default_reserved_names());
+		let guard = ReservedNamesGuard::new(config);
+		let context = create_test_context();
+
+		let result = guard.evaluate_tools_list(&[create_test_tool("admin_reload")], &context);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(
+			reason.details.unwrap()["tools"][0]["reserved_name"],
+			"admin/reload"
+		);
+
+		let tools = vec![
+			create_test_tool("initialize"),
+			create_test_tool("ping"),
+			create_test_tool("list_tools_for_repo"),
+		];
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &context),
+			Ok(GuardDecision::Allow)
+		));
+	}
+}
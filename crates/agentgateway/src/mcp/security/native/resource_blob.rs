@@ -0,0 +1,94 @@
+// Resource Blob Content Scanning
+//
+// `resources/read` results carry binary resource contents as a base64 `blob` next to
+// the resource's `uri` and `mimeType` (MCP `BlobResourceContents`). Documents served
+// this way with a textual media type (`text/*`, JSON, XML, YAML) are decoded so guards
+// can scan their text, and re-encoded after masking. Blobs of other media types, or
+// without one, are left as they are.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+
+/// Marker reported as the `source` of detections inside resource blobs
+pub(crate) const SOURCE: &str = "resource_blob";
+
+/// The text of `contents` if it is a blob resource with a textual media type whose
+/// payload decodes to at most `max_decoded_bytes` of UTF-8
+pub(crate) fn decode(
+	contents: &serde_json::Map<String, serde_json::Value>,
+	max_decoded_bytes: usize,
+) -> Option<String> {
+	contents.get("uri")?.as_str()?;
+	let blob = contents.get("blob")?.as_str()?;
+	let mime_type = contents.get("mimeType")?.as_str()?;
+	if !is_textual(mime_type) {
+		return None;
+	}
+	// Checked before decoding, so oversized payloads are never materialized
+	if blob.len() / 4 * 3 > max_decoded_bytes {
+		return None;
+	}
+	let bytes = STANDARD.decode(blob.trim()).ok()?;
+	String::from_utf8(bytes).ok()
+}
+
+/// The `blob` value for `text`, the inverse of `decode`
+pub(crate) fn encode(text: &str) -> String {
+	STANDARD.encode(text)
+}
+
+fn is_textual(mime_type: &str) -> bool {
+	let essence = mime_type
+		.split(';')
+		.next()
+		.unwrap_or_default()
+		.trim()
+		.to_ascii_lowercase();
+	essence.starts_with("text/")
+		|| essence.ends_with("+json")
+		|| essence.ends_with("+xml")
+		|| matches!(
+			essence.as_str(),
+			"application/json" | "application/xml" | "application/yaml" | "application/x-yaml"
+		)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn contents(mime_type: &str, blob: &str) -> serde_json::Map<String, serde_json::Value> {
+		serde_json::json!({ "uri": "file:///doc", "mimeType": mime_type, "blob": blob })
+			.as_object()
+			.unwrap()
+			.clone()
+	}
+
+	#[test]
+	fn test_textual_blob_round_trip() {
+		let blob = encode("contact jane@example.com");
+		for mime_type in [
+			"text/plain",
+			"application/json; charset=utf-8",
+			"application/ld+json",
+		] {
+			assert_eq!(
+				decode(&contents(mime_type, &blob), 1024).as_deref(),
+				Some("contact jane@example.com"),
+				"{mime_type}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_binary_and_oversized_blobs_ignored() {
+		let blob = encode("contact jane@example.com");
+		assert!(decode(&contents("image/png", &blob), 1024).is_none());
+		assert!(decode(&contents("text/plain", &blob), 8).is_none());
+		assert!(decode(&contents("text/plain", "not base64!"), 1024).is_none());
+
+		let mut untyped = contents("text/plain", &blob);
+		untyped.remove("mimeType");
+		assert!(decode(&untyped, 1024).is_none());
+	}
+}
@@ -0,0 +1,167 @@
+// Secure Transport Enforcement
+//
+// Connection-phase guard that refuses MCP servers reached over plaintext transports
+// (`http://`, `ws://`): tool lists, arguments and results would otherwise cross the
+// network unencrypted and unauthenticated. Hosts that are legitimately plaintext,
+// such as a sidecar on localhost, can be allowlisted.
+//
+// Targets without a URL (stdio servers) have no network transport and are allowed.
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardError, GuardResult};
+
+/// Configuration for Secure Transport Enforcement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct SecureTransportConfig {
+	/// Hosts that may be reached over plaintext (exact host names or IP addresses)
+	#[serde(default = "default_plaintext_hosts")]
+	pub plaintext_hosts: Vec<String>,
+}
+
+fn default_plaintext_hosts() -> Vec<String> {
+	["localhost", "127.0.0.1", "::1"]
+		.iter()
+		.map(|s| s.to_string())
+		.collect()
+}
+
+impl Default for SecureTransportConfig {
+	fn default() -> Self {
+		Self {
+			plaintext_hosts: default_plaintext_hosts(),
+		}
+	}
+}
+
+/// Secure Transport Guard implementation
+pub struct SecureTransportGuard {
+	config: SecureTransportConfig,
+}
+
+impl SecureTransportGuard {
+	pub fn new(config: SecureTransportConfig) -> Self {
+		Self { config }
+	}
+
+	fn is_plaintext_allowed(&self, host: &str) -> bool {
+		// IPv6 hosts are bracketed in URLs
+		let host = host.trim_start_matches('[').trim_end_matches(']');
+		self
+			.config
+			.plaintext_hosts
+			.iter()
+			.any(|h| h.eq_ignore_ascii_case(host))
+	}
+}
+
+impl NativeGuard for SecureTransportGuard {
+	fn evaluate_connection(
+		&self,
+		server_name: &str,
+		server_url: Option<&str>,
+		_context: &GuardContext,
+	) -> GuardResult {
+		let Some(server_url) = server_url else {
+			return Ok(GuardDecision::Allow);
+		};
+		let url = url::Url::parse(server_url).map_err(|e| {
+			GuardError::ExecutionError(format!("Invalid server URL '{}': {}", server_url, e))
+		})?;
+
+		if !matches!(url.scheme(), "http" | "ws") {
+			return Ok(GuardDecision::Allow);
+		}
+		let host = url.host_str().unwrap_or_default();
+		if self.is_plaintext_allowed(host) {
+			return Ok(GuardDecision::Allow);
+		}
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "insecure_transport".to_string(),
+			message: format!(
+				"Server '{}' is reached over plaintext {} and '{}' is not allowlisted for it",
+				server_name,
+				url.scheme(),
+				host
+			),
+			details: Some(serde_json::json!({
+				"server": server_name,
+				"scheme": url.scheme(),
+				"host": host,
+			})),
+		}))
+	}
+
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		// Enforced at connection time
+		Ok(GuardDecision::Allow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	#[test]
+	fn test_plaintext_url_denied() {
+		let guard = SecureTransportGuard::new(SecureTransportConfig::default());
+		for url in ["http://mcp.example.com/mcp", "ws://mcp.example.com:8080/ws"] {
+			let result = guard.evaluate_connection("remote", Some(url), &create_test_context());
+			let Ok(GuardDecision::Deny(reason)) = result else {
+				panic!("Expected Deny for {url}, got {:?}", result);
+			};
+			assert_eq!(reason.code, "insecure_transport");
+			assert_eq!(reason.details.unwrap()["host"], "mcp.example.com");
+		}
+	}
+
+	#[test]
+	fn test_tls_and_allowlisted_hosts_allowed() {
+		let guard = SecureTransportGuard::new(SecureTransportConfig::default());
+		let context = create_test_context();
+		for url in [
+			Some("https://mcp.example.com/mcp"),
+			Some("wss://mcp.example.com/ws"),
+			Some("http://localhost:3000/mcp"),
+			Some("http://[::1]:3000/sse"),
+			// stdio target
+			None,
+		] {
+			assert!(
+				matches!(
+					guard.evaluate_connection("server", url, &context),
+					Ok(GuardDecision::Allow)
+				),
+				"{url:?}"
+			);
+		}
+
+		let guard = SecureTransportGuard::new(SecureTransportConfig {
+			plaintext_hosts: vec!["mcp.internal".to_string()],
+		});
+		assert!(matches!(
+			guard.evaluate_connection("internal", Some("http://mcp.internal/mcp"), &context),
+			Ok(GuardDecision::Allow)
+		));
+		assert!(matches!(
+			guard.evaluate_connection("local", Some("http://localhost/mcp"), &context),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+}
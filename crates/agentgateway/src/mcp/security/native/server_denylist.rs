@@ -0,0 +1,280 @@
+// Server Denylist Enforcement
+//
+// Blocks known-bad MCP servers by name or by the host of their URL, for setups that
+// are otherwise permissive and don't maintain an exhaustive whitelist. Any match
+// denies the connection, so a denylisted server stays blocked even when it is also
+// whitelisted.
+//
+// Server names and hosts match exactly or as globs (`*` matches any run of characters,
+// e.g. `*.evil.example`); host entries may also be IP CIDR ranges (`10.0.0.0/8`), which
+// only match servers addressed by IP (host names are not resolved).
+
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{
+	DenyReason, GuardContext, GuardDecision, GuardError, GuardResult, glob_matches,
+};
+
+/// Configuration for Server Denylist
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ServerDenylistConfig {
+	/// Server names to block (exact or `*` globs)
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub servers: Vec<String>,
+
+	/// Server URL hosts to block (exact, `*` globs, or IP CIDR ranges)
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub hosts: Vec<String>,
+}
+
+/// A parsed `hosts` entry
+enum HostEntry {
+	Glob(String),
+	Cidr(IpNet),
+}
+
+impl HostEntry {
+	fn matches(&self, host: &url::Host<&str>) -> bool {
+		match (self, host) {
+			(HostEntry::Cidr(net), url::Host::Ipv4(ip)) => net.contains(&std::net::IpAddr::V4(*ip)),
+			(HostEntry::Cidr(net), url::Host::Ipv6(ip)) => net.contains(&std::net::IpAddr::V6(*ip)),
+			(HostEntry::Cidr(_), url::Host::Domain(_)) => false,
+			(HostEntry::Glob(pattern), host) => {
+				let host = host.to_string();
+				// IPv6 hosts are bracketed in URLs
+				let host = host.trim_start_matches('[').trim_end_matches(']');
+				glob_matches(pattern, &host.to_ascii_lowercase())
+			},
+		}
+	}
+}
+
+/// Server Denylist Guard implementation
+pub struct ServerDenylistGuard {
+	config: ServerDenylistConfig,
+	/// Parsed `config.hosts`, in order
+	hosts: Vec<HostEntry>,
+}
+
+impl ServerDenylistGuard {
+	pub fn new(config: ServerDenylistConfig) -> Result<Self, GuardError> {
+		let hosts = config
+			.hosts
+			.iter()
+			.enumerate()
+			.map(|(i, entry)| {
+				if !entry.contains('/') {
+					return Ok(HostEntry::Glob(entry.to_ascii_lowercase()));
+				}
+				let net = entry.parse().map_err(|e| GuardError::InvalidField {
+					field: format!("hosts[{}]", i),
+					reason: format!("invalid CIDR '{}': {}", entry, e),
+				})?;
+				Ok(HostEntry::Cidr(net))
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self { config, hosts })
+	}
+
+	/// The configured entry blocking this server, if any
+	fn matching_entry(
+		&self,
+		server_name: &str,
+		server_url: Option<&str>,
+	) -> Result<Option<(&'static str, &str)>, GuardError> {
+		if let Some(entry) = self
+			.config
+			.servers
+			.iter()
+			.find(|s| glob_matches(s, server_name))
+		{
+			return Ok(Some(("server", entry)));
+		}
+
+		let Some(server_url) = server_url else {
+			return Ok(None);
+		};
+		let url = url::Url::parse(server_url).map_err(|e| {
+			GuardError::ExecutionError(format!("Invalid server URL '{}': {}", server_url, e))
+		})?;
+		let Some(host) = url.host() else {
+			return Ok(None);
+		};
+		Ok(
+			self
+				.hosts
+				.iter()
+				.position(|entry| entry.matches(&host))
+				.map(|i| ("host", self.config.hosts[i].as_str())),
+		)
+	}
+}
+
+impl NativeGuard for ServerDenylistGuard {
+	fn evaluate_connection(
+		&self,
+		server_name: &str,
+		server_url: Option<&str>,
+		context: &GuardContext,
+	) -> GuardResult {
+		let Some((kind, entry)) = self.matching_entry(server_name, server_url)? else {
+			return Ok(GuardDecision::Allow);
+		};
+
+		tracing::warn!(
+			server = %server_name,
+			backend_server = %context.server_name,
+			entry = %entry,
+			"Server denylisted"
+		);
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "server_denylisted".to_string(),
+			message: format!(
+				"Server '{}' is denylisted ({} '{}')",
+				server_name, kind, entry
+			),
+			details: Some(serde_json::json!({
+				"server": server_name,
+				"url": server_url,
+				"matched": { kind: entry },
+			})),
+		}))
+	}
+
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		// Enforced at connection time
+		Ok(GuardDecision::Allow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mcp::security::GuardExecutor;
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	fn create_guard() -> ServerDenylistGuard {
+		let config: ServerDenylistConfig = serde_yaml::from_str(
+			r#"
+servers: ["shady-*"]
+hosts: ["*.evil.example", "Paste.Example.com", "10.13.0.0/16", "fd00::/8"]
+"#,
+		)
+		.unwrap();
+		ServerDenylistGuard::new(config).unwrap()
+	}
+
+	#[test]
+	fn test_denylisted_names_and_hosts_blocked() {
+		let guard = create_guard();
+		let context = create_test_context();
+		for (name, url, matched) in [
+			(
+				"shady-tools",
+				None,
+				serde_json::json!({"server": "shady-*"}),
+			),
+			(
+				"github",
+				Some("https://mcp.evil.example/mcp"),
+				serde_json::json!({"host": "*.evil.example"}),
+			),
+			(
+				"github",
+				Some("https://paste.example.com/mcp"),
+				serde_json::json!({"host": "Paste.Example.com"}),
+			),
+			(
+				"github",
+				Some("http://10.13.4.2:8080/mcp"),
+				serde_json::json!({"host": "10.13.0.0/16"}),
+			),
+			(
+				"github",
+				Some("http://[fd12::1]/mcp"),
+				serde_json::json!({"host": "fd00::/8"}),
+			),
+		] {
+			let result = guard.evaluate_connection(name, url, &context);
+			let Ok(GuardDecision::Deny(reason)) = result else {
+				panic!("Expected Deny for {name} {url:?}, got {:?}", result);
+			};
+			assert_eq!(reason.code, "server_denylisted");
+			assert_eq!(reason.details.unwrap()["matched"], matched);
+		}
+
+		for (name, url) in [
+			("github", Some("https://api.github.com/mcp")),
+			("github", Some("https://evil.example/mcp")),
+			("github", Some("http://10.14.0.1/mcp")),
+			("local", None),
+		] {
+			assert!(
+				matches!(
+					guard.evaluate_connection(name, url, &context),
+					Ok(GuardDecision::Allow)
+				),
+				"{name} {url:?}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_invalid_cidr_rejected() {
+		let result = ServerDenylistGuard::new(ServerDenylistConfig {
+			hosts: vec!["10.0.0.0/33".to_string()],
+			..Default::default()
+		});
+		assert!(matches!(
+			result,
+			Err(GuardError::InvalidField { field, .. }) if field == "hosts[0]"
+		));
+	}
+
+	#[test]
+	fn test_denylist_takes_precedence_over_whitelist() {
+		let executor = GuardExecutor::new(
+			serde_yaml::from_str(
+				r#"
+- id: whitelist
+  type: server_whitelist
+  runs_on: [connection]
+  allowed_servers: [github]
+- id: denylist
+  type: server_denylist
+  runs_on: [connection]
+  hosts: ["*.evil.example"]
+"#,
+			)
+			.unwrap(),
+		)
+		.unwrap();
+		let context = create_test_context();
+
+		assert!(matches!(
+			executor.evaluate_connection("github", Some("https://api.github.com/mcp"), &context),
+			Ok(GuardDecision::Allow)
+		));
+		let result =
+			executor.evaluate_connection("github", Some("https://mcp.evil.example/mcp"), &context);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "server_denylisted");
+	}
+}
@@ -0,0 +1,362 @@
+// Server Implementation Verification
+//
+// Compares the implementation an upstream reports in its `initialize` result
+// (`serverInfo.name` / `serverInfo.version`) against the one expected for that server.
+// A backend silently swapped for a different implementation (or an unexpected major
+// version) is a supply-chain signal, even when its tools still look the same.
+//
+// `version` is either an exact version string or a comma-separated list of semver
+// comparators, all of which must hold: `=`, `>`, `>=`, `<`, `<=`, `^` (same leftmost
+// non-zero component) and `~` (same major and minor). Pre-release and build suffixes
+// of the reported version are ignored when comparing against a range.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardError, GuardResult};
+
+/// Implementation expected from one server
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedImplementation {
+	/// Expected `serverInfo.name` (exact match)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// Expected `serverInfo.version`: an exact version, or semver comparators such as
+	/// `>=1.2.0, <2.0.0` or `^1.4`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+}
+
+/// Configuration for Server Implementation Verification
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ServerImplementationConfig {
+	/// Expected implementation per server name. Servers not listed are not checked.
+	#[serde(default)]
+	pub expected: BTreeMap<String, ExpectedImplementation>,
+}
+
+/// Version with its major, minor and patch components; `parts` is how many were given
+#[derive(Debug, Clone, Copy)]
+struct Version {
+	major: u64,
+	minor: u64,
+	patch: u64,
+	parts: usize,
+}
+
+impl Version {
+	fn parse(s: &str) -> Option<Self> {
+		let s = s.trim().trim_start_matches('v');
+		let core = s.split(['-', '+']).next().unwrap_or(s);
+		let components = core
+			.split('.')
+			.map(|c| c.parse::<u64>().ok())
+			.collect::<Option<Vec<_>>>()?;
+		if components.is_empty() || components.len() > 3 {
+			return None;
+		}
+		Some(Self {
+			major: components[0],
+			minor: components.get(1).copied().unwrap_or(0),
+			patch: components.get(2).copied().unwrap_or(0),
+			parts: components.len(),
+		})
+	}
+
+	fn cmp_numbers(&self, other: &Self) -> Ordering {
+		(self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+	}
+
+	fn with_numbers(major: u64, minor: u64, patch: u64) -> Self {
+		Self {
+			major,
+			minor,
+			patch,
+			parts: 3,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+	Eq,
+	Gt,
+	Ge,
+	Lt,
+	Le,
+	Caret,
+	Tilde,
+}
+
+/// Parsed `version` expectation
+#[derive(Debug)]
+enum VersionReq {
+	/// No operator: the reported version must equal this string
+	Exact(String),
+	/// All comparators must hold
+	Range(Vec<(Op, Version)>),
+}
+
+impl VersionReq {
+	fn parse(s: &str) -> Result<Self, String> {
+		let s = s.trim();
+		if !s.starts_with(['=', '>', '<', '^', '~']) {
+			return Ok(VersionReq::Exact(s.to_string()));
+		}
+		s.split(',')
+			.map(|c| {
+				let c = c.trim();
+				let (op, rest) = [
+					(">=", Op::Ge),
+					("<=", Op::Le),
+					(">", Op::Gt),
+					("<", Op::Lt),
+					("=", Op::Eq),
+					("^", Op::Caret),
+					("~", Op::Tilde),
+				]
+				.iter()
+				.find_map(|(prefix, op)| c.strip_prefix(prefix).map(|rest| (*op, rest)))
+				.ok_or_else(|| format!("comparator '{}' has no operator", c))?;
+				let version =
+					Version::parse(rest).ok_or_else(|| format!("invalid version in comparator '{}'", c))?;
+				Ok((op, version))
+			})
+			.collect::<Result<_, _>>()
+			.map(VersionReq::Range)
+	}
+
+	fn matches(&self, reported: &str) -> bool {
+		match self {
+			VersionReq::Exact(expected) => expected == reported.trim(),
+			VersionReq::Range(comparators) => {
+				let Some(v) = Version::parse(reported) else {
+					return false;
+				};
+				comparators
+					.iter()
+					.all(|(op, req)| comparator_matches(*op, req, &v))
+			},
+		}
+	}
+}
+
+fn comparator_matches(op: Op, req: &Version, v: &Version) -> bool {
+	let ord = v.cmp_numbers(req);
+	match op {
+		Op::Eq => ord == Ordering::Equal,
+		Op::Gt => ord == Ordering::Greater,
+		Op::Ge => ord != Ordering::Less,
+		Op::Lt => ord == Ordering::Less,
+		Op::Le => ord != Ordering::Greater,
+		Op::Caret => {
+			let upper = if req.major > 0 || req.parts == 1 {
+				Version::with_numbers(req.major + 1, 0, 0)
+			} else if req.minor > 0 || req.parts == 2 {
+				Version::with_numbers(0, req.minor + 1, 0)
+			} else {
+				Version::with_numbers(0, 0, req.patch + 1)
+			};
+			ord != Ordering::Less && v.cmp_numbers(&upper) == Ordering::Less
+		},
+		Op::Tilde => {
+			let upper = if req.parts == 1 {
+				Version::with_numbers(req.major + 1, 0, 0)
+			} else {
+				Version::with_numbers(req.major, req.minor + 1, 0)
+			};
+			ord != Ordering::Less && v.cmp_numbers(&upper) == Ordering::Less
+		},
+	}
+}
+
+/// Server Implementation Guard implementation
+pub struct ServerImplementationGuard {
+	config: ServerImplementationConfig,
+	/// Parsed `version` per server name
+	versions: BTreeMap<String, VersionReq>,
+}
+
+impl ServerImplementationGuard {
+	pub fn new(config: ServerImplementationConfig) -> Result<Self, GuardError> {
+		let versions = config
+			.expected
+			.iter()
+			.filter_map(|(server, expected)| Some((server, expected.version.as_deref()?)))
+			.map(|(server, version)| {
+				VersionReq::parse(version)
+					.map(|req| (server.clone(), req))
+					.map_err(|e| GuardError::InvalidField {
+						field: format!("expected.{}.version", server),
+						reason: format!("invalid version requirement '{}': {}", version, e),
+					})
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self { config, versions })
+	}
+}
+
+impl NativeGuard for ServerImplementationGuard {
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		Ok(GuardDecision::Allow)
+	}
+
+	fn evaluate_initialize(
+		&self,
+		info: &rmcp::model::ServerInfo,
+		context: &GuardContext,
+	) -> GuardResult {
+		let server = context.server_name.as_str();
+		let Some(expected) = self.config.expected.get(server) else {
+			return Ok(GuardDecision::Allow);
+		};
+		let actual = &info.server_info;
+
+		let name_matches = expected.name.as_ref().is_none_or(|n| *n == actual.name);
+		let version_matches = self
+			.versions
+			.get(server)
+			.is_none_or(|req| req.matches(&actual.version));
+		if name_matches && version_matches {
+			return Ok(GuardDecision::Allow);
+		}
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "unexpected_server_implementation".to_string(),
+			message: format!(
+				"Server '{}' reports implementation '{}' version '{}', which does not match the expected implementation",
+				server, actual.name, actual.version
+			),
+			details: Some(serde_json::json!({
+				"expected": expected,
+				"actual": { "name": actual.name, "version": actual.version },
+			})),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_server_info(name: &str, version: &str) -> rmcp::model::ServerInfo {
+		serde_json::from_value(serde_json::json!({
+			"protocolVersion": "2025-06-18",
+			"capabilities": {},
+			"serverInfo": {"name": name, "version": version}
+		}))
+		.unwrap()
+	}
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "github".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	fn guard_expecting(name: Option<&str>, version: Option<&str>) -> ServerImplementationGuard {
+		ServerImplementationGuard::new(ServerImplementationConfig {
+			expected: BTreeMap::from([(
+				"github".to_string(),
+				ExpectedImplementation {
+					name: name.map(str::to_string),
+					version: version.map(str::to_string),
+				},
+			)]),
+		})
+		.unwrap()
+	}
+
+	#[test]
+	fn test_matching_implementation_allowed() {
+		let context = create_test_context();
+		let guard = guard_expecting(Some("github-mcp-server"), Some("1.4.2"));
+		assert!(matches!(
+			guard.evaluate_initialize(&create_server_info("github-mcp-server", "1.4.2"), &context),
+			Ok(GuardDecision::Allow)
+		));
+
+		let guard = guard_expecting(Some("github-mcp-server"), Some(">=1.2.0, <2.0.0"));
+		assert!(matches!(
+			guard.evaluate_initialize(
+				&create_server_info("github-mcp-server", "1.9.0-beta.1"),
+				&context
+			),
+			Ok(GuardDecision::Allow)
+		));
+
+		// Servers without an expectation are not checked
+		let other = GuardContext {
+			server_name: "other".to_string(),
+			..create_test_context()
+		};
+		assert!(matches!(
+			guard.evaluate_initialize(&create_server_info("anything", "0.0.1"), &other),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_mismatching_implementation_denied() {
+		let context = create_test_context();
+		let guard = guard_expecting(Some("github-mcp-server"), Some("^1.4"));
+
+		let result =
+			guard.evaluate_initialize(&create_server_info("evil-mcp-server", "1.4.2"), &context);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "unexpected_server_implementation");
+		let details = reason.details.unwrap();
+		assert_eq!(details["actual"]["name"], "evil-mcp-server");
+		assert_eq!(details["expected"]["version"], "^1.4");
+
+		// Right name, but outside the version range
+		assert!(matches!(
+			guard.evaluate_initialize(&create_server_info("github-mcp-server", "2.0.0"), &context),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+
+	#[test]
+	fn test_version_requirements() {
+		let matches = |req: &str, v: &str| VersionReq::parse(req).unwrap().matches(v);
+		assert!(matches("^1.4", "1.9.3"));
+		assert!(!matches("^1.4", "1.3.0"));
+		assert!(matches("^0.3.1", "0.3.9"));
+		assert!(!matches("^0.3.1", "0.4.0"));
+		assert!(matches("~1.4.2", "1.4.7"));
+		assert!(!matches("~1.4.2", "1.5.0"));
+		assert!(matches("= 2.0", "v2.0.0"));
+		assert!(!matches(">=1.0", "nightly"));
+		assert!(matches("2024.06-build7", "2024.06-build7"));
+
+		assert!(VersionReq::parse(">=one").is_err());
+		assert!(
+			ServerImplementationGuard::new(ServerImplementationConfig {
+				expected: BTreeMap::from([(
+					"github".to_string(),
+					ExpectedImplementation {
+						name: None,
+						version: Some("<1.0, foo".to_string()),
+					},
+				)]),
+			})
+			.is_err()
+		);
+	}
+}
@@ -3,12 +3,20 @@
 // Ensures that only trusted MCP servers can be accessed through the gateway.
 // Detects typosquatting attempts and validates server identity.
 //
-// NOTE: This is a placeholder implementation.
+// Servers are allowed by exact name (`allowed_servers`) or by documented entries
+// (`entries`): a regex over the server name plus who owns it and why it is allowed.
+// A blocked server's denial lists the closest allowed names and the configured
+// entries, so operators can tell a legitimate-but-unlisted server from an impostor.
+// An empty whitelist allows every server.
 
+use regex::RegexSet;
 use serde::{Deserialize, Serialize};
 
-use super::NativeGuard;
-use crate::mcp::security::{GuardContext, GuardDecision, GuardResult};
+use super::{NativeGuard, compile_pattern, edit_distance, reject_unresolved_refs};
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardError, GuardResult};
+
+/// Closest allowed names reported when a server is blocked
+const MAX_CANDIDATES: usize = 3;
 
 /// Configuration for Server Whitelist
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +27,14 @@ pub struct ServerWhitelistConfig {
 	#[serde(default)]
 	pub allowed_servers: Vec<String>,
 
+	/// Names of shared pattern lists whose entries are appended to `allowed_servers`
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub allowed_servers_ref: Vec<String>,
+
+	/// Documented whitelist entries matching server names by regex
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub entries: Vec<WhitelistEntry>,
+
 	/// Detect typosquatting attempts
 	#[serde(default = "default_detect_typosquats")]
 	pub detect_typosquats: bool,
@@ -28,6 +44,33 @@ pub struct ServerWhitelistConfig {
 	pub similarity_threshold: f32,
 }
 
+/// An allowed server (or family of servers) and why it is allowed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct WhitelistEntry {
+	/// Label for the entry, reported in denials (defaults to the pattern)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// Regex the whole server name must match
+	pub pattern: String,
+
+	/// Why these servers are allowed
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub reason: Option<String>,
+
+	/// Team or person responsible for these servers
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub owner: Option<String>,
+}
+
+impl WhitelistEntry {
+	fn label(&self) -> &str {
+		self.name.as_deref().unwrap_or(&self.pattern)
+	}
+}
+
 fn default_detect_typosquats() -> bool {
 	true
 }
@@ -38,25 +81,255 @@ fn default_similarity_threshold() -> f32 {
 
 /// Server Whitelist Checker implementation
 pub struct ServerWhitelistChecker {
-	#[allow(dead_code)]
 	config: ServerWhitelistConfig,
+	/// One anchored pattern per entry, in `config.entries` order
+	entry_set: RegexSet,
 }
 
 impl ServerWhitelistChecker {
-	pub fn new(config: ServerWhitelistConfig) -> Self {
-		Self { config }
+	pub fn new(config: ServerWhitelistConfig) -> Result<Self, GuardError> {
+		reject_unresolved_refs("allowed_servers_ref", &config.allowed_servers_ref)?;
+		let anchored: Vec<String> = config
+			.entries
+			.iter()
+			.map(|e| format!("^(?:{})$", e.pattern))
+			.collect();
+		// Compile individually first so an error names the offending entry
+		for (i, entry) in config.entries.iter().enumerate() {
+			compile_pattern(format!("entries[{}].pattern", i), &entry.pattern)?;
+		}
+		let entry_set = RegexSet::new(&anchored)
+			.map_err(|e| GuardError::ConfigError(format!("Invalid whitelist entries: {}", e)))?;
+		Ok(Self { config, entry_set })
+	}
+
+	/// Whether `server_name` is listed exactly or matches an entry
+	fn is_allowed(&self, server_name: &str) -> bool {
+		self.config.allowed_servers.iter().any(|s| s == server_name)
+			|| self.entry_set.is_match(server_name)
+	}
+
+	/// Allowed names closest to `server_name`, most similar first
+	fn closest_candidates(&self, server_name: &str) -> Vec<(String, f32)> {
+		let mut candidates: Vec<(String, f32)> = self
+			.config
+			.allowed_servers
+			.iter()
+			.map(String::as_str)
+			.chain(self.config.entries.iter().map(|e| e.label()))
+			.map(|name| (name.to_string(), similarity(server_name, name)))
+			.collect();
+		candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+		candidates.dedup_by(|a, b| a.0 == b.0);
+		candidates.truncate(MAX_CANDIDATES);
+		candidates
+	}
+
+	fn deny(&self, server_name: &str) -> DenyReason {
+		let candidates = self.closest_candidates(server_name);
+		let typosquat = self.config.detect_typosquats
+			&& candidates
+				.first()
+				.is_some_and(|(_, score)| *score >= self.config.similarity_threshold);
+
+		let message = match (typosquat, candidates.first()) {
+			(true, Some((name, _))) => format!(
+				"Server '{}' is not whitelisted and closely resembles allowed server '{}'",
+				server_name, name
+			),
+			_ => format!("Server '{}' is not whitelisted", server_name),
+		};
+
+		DenyReason {
+			code: if typosquat {
+				"server_typosquat_suspected"
+			} else {
+				"server_not_whitelisted"
+			}
+			.to_string(),
+			message,
+			details: Some(serde_json::json!({
+				"server": server_name,
+				"closest_candidates": candidates
+					.iter()
+					.map(|(name, score)| serde_json::json!({ "name": name, "similarity": score }))
+					.collect::<Vec<_>>(),
+				"allowed_servers": self.config.allowed_servers,
+				"entries": self.config.entries,
+			})),
+		}
+	}
+}
+
+/// Normalized Levenshtein similarity (1.0 = identical), case-insensitive
+fn similarity(a: &str, b: &str) -> f32 {
+	let a: Vec<char> = a.to_lowercase().chars().collect();
+	let b: Vec<char> = b.to_lowercase().chars().collect();
+	let max_len = a.len().max(b.len());
+	if max_len == 0 {
+		return 1.0;
 	}
+	1.0 - edit_distance(&a, &b) as f32 / max_len as f32
 }
 
 impl NativeGuard for ServerWhitelistChecker {
+	fn evaluate_connection(
+		&self,
+		server_name: &str,
+		_server_url: Option<&str>,
+		context: &GuardContext,
+	) -> GuardResult {
+		if (self.config.allowed_servers.is_empty() && self.config.entries.is_empty())
+			|| self.is_allowed(server_name)
+		{
+			return Ok(GuardDecision::Allow);
+		}
+
+		let reason = self.deny(server_name);
+		tracing::warn!(
+			server = %server_name,
+			backend_server = %context.server_name,
+			code = %reason.code,
+			"Server not whitelisted"
+		);
+		Ok(GuardDecision::Deny(reason))
+	}
+
 	fn evaluate_tools_list(
 		&self,
 		_tools: &[rmcp::model::Tool],
 		_context: &GuardContext,
 	) -> GuardResult {
-		tracing::info!("ServerWhitelistChecker::evaluate_tools_list called");
-		// TODO: Implement whitelist checking and typosquatting detection
-		// For now, always allow
+		// Enforced at connection time
 		Ok(GuardDecision::Allow)
 	}
+
+	fn get_settings_schema(&self) -> Option<String> {
+		super::settings_schema("server_whitelist")
+	}
+
+	fn get_default_config(&self) -> Option<String> {
+		super::default_config("server_whitelist")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	fn create_checker() -> ServerWhitelistChecker {
+		let config: ServerWhitelistConfig = serde_yaml::from_str(
+			r#"
+allowed_servers: [github]
+entries:
+  - name: internal
+    pattern: "internal-[a-z]+"
+    reason: Company MCP servers deployed by the platform team
+    owner: platform@example.com
+  - pattern: "jira(-staging)?"
+    reason: Ticketing
+"#,
+		)
+		.unwrap();
+		ServerWhitelistChecker::new(config).unwrap()
+	}
+
+	#[test]
+	fn test_entries_deserialization() {
+		let checker = create_checker();
+		let entries = &checker.config.entries;
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].name.as_deref(), Some("internal"));
+		assert_eq!(entries[0].owner.as_deref(), Some("platform@example.com"));
+		assert_eq!(entries[1].label(), "jira(-staging)?");
+		assert!(entries[1].owner.is_none());
+
+		// A bad regex is reported with the pattern that failed
+		let result = ServerWhitelistChecker::new(ServerWhitelistConfig {
+			entries: vec![WhitelistEntry {
+				name: None,
+				pattern: "internal-(".to_string(),
+				reason: None,
+				owner: None,
+			}],
+			..checker.config.clone()
+		});
+		assert!(matches!(result, Err(GuardError::InvalidPattern { .. })));
+	}
+
+	#[test]
+	fn test_exact_and_pattern_matching() {
+		let checker = create_checker();
+		let context = create_test_context();
+		for allowed in ["github", "internal-search", "jira", "jira-staging"] {
+			assert!(
+				matches!(
+					checker.evaluate_connection(allowed, None, &context),
+					Ok(GuardDecision::Allow)
+				),
+				"{allowed}"
+			);
+		}
+		// Patterns match the whole name
+		for blocked in ["internal-", "my-jira", "jira-staging-2"] {
+			assert!(
+				matches!(
+					checker.evaluate_connection(blocked, None, &context),
+					Ok(GuardDecision::Deny(_))
+				),
+				"{blocked}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_deny_details_list_candidates_and_reasons() {
+		let checker = create_checker();
+		let result = checker.evaluate_connection("githib", None, &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "server_not_whitelisted");
+		let details = reason.details.unwrap();
+		assert_eq!(details["server"], "githib");
+		assert_eq!(details["closest_candidates"][0]["name"], "github");
+		assert_eq!(details["allowed_servers"], serde_json::json!(["github"]));
+		assert_eq!(details["entries"][0]["name"], "internal");
+		assert_eq!(
+			details["entries"][0]["reason"],
+			"Company MCP servers deployed by the platform team"
+		);
+		assert_eq!(details["entries"][1]["reason"], "Ticketing");
+
+		// Close enough to an allowed name to look like a typosquat
+		let checker = ServerWhitelistChecker::new(ServerWhitelistConfig {
+			similarity_threshold: 0.8,
+			..checker.config.clone()
+		})
+		.unwrap();
+		let result = checker.evaluate_connection("githib", None, &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "server_typosquat_suspected");
+		assert!(reason.message.contains("'github'"));
+	}
+
+	#[test]
+	fn test_empty_whitelist_allows() {
+		let config: ServerWhitelistConfig = serde_yaml::from_str("{}").unwrap();
+		let checker = ServerWhitelistChecker::new(config).unwrap();
+		assert!(matches!(
+			checker.evaluate_connection("anything", None, &create_test_context()),
+			Ok(GuardDecision::Allow)
+		));
+	}
 }
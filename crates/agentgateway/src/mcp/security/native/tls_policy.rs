@@ -0,0 +1,250 @@
+// TLS Policy Enforcement
+//
+// Connection-phase guard that checks the TLS parameters negotiated with an MCP server
+// against a minimum protocol version and, optionally, a cipher suite allowlist. The
+// Secure Transport guard only looks at the URL scheme; this one catches TLS
+// connections that were downgraded to an old protocol version or a weak cipher.
+//
+// The negotiated parameters are passed in the connection context metadata (see
+// `TLS_METADATA_KEY`). They are only known once the server has been reached over TLS,
+// so servers without them (stdio, plaintext, or not yet contacted) are allowed.
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{
+	DenyReason, GuardContext, GuardDecision, GuardResult, TLS_METADATA_KEY,
+};
+use crate::transport::stream::NegotiatedTls;
+
+/// TLS protocol versions, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TlsVersion {
+	#[serde(rename = "1.0")]
+	Tls10,
+	#[serde(rename = "1.1")]
+	Tls11,
+	#[serde(rename = "1.2")]
+	Tls12,
+	#[serde(rename = "1.3")]
+	Tls13,
+}
+
+impl TlsVersion {
+	/// Parse a negotiated version as reported by the TLS stack (`TLSv1_3`)
+	fn from_negotiated(version: &str) -> Option<Self> {
+		match version {
+			"TLSv1_0" => Some(TlsVersion::Tls10),
+			"TLSv1_1" => Some(TlsVersion::Tls11),
+			"TLSv1_2" => Some(TlsVersion::Tls12),
+			"TLSv1_3" => Some(TlsVersion::Tls13),
+			_ => None,
+		}
+	}
+
+	fn as_str(&self) -> &'static str {
+		match self {
+			TlsVersion::Tls10 => "1.0",
+			TlsVersion::Tls11 => "1.1",
+			TlsVersion::Tls12 => "1.2",
+			TlsVersion::Tls13 => "1.3",
+		}
+	}
+}
+
+/// Configuration for TLS Policy Enforcement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct TlsPolicyConfig {
+	/// Minimum negotiated TLS version (default: 1.2)
+	#[serde(default = "default_min_version")]
+	pub min_version: TlsVersion,
+
+	/// Cipher suites that may be negotiated, e.g. `TLS13_AES_256_GCM_SHA384`
+	/// (default: any suite the TLS stack accepts)
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub allowed_cipher_suites: Vec<String>,
+}
+
+fn default_min_version() -> TlsVersion {
+	TlsVersion::Tls12
+}
+
+impl Default for TlsPolicyConfig {
+	fn default() -> Self {
+		Self {
+			min_version: default_min_version(),
+			allowed_cipher_suites: Vec::new(),
+		}
+	}
+}
+
+/// TLS Policy Guard implementation
+pub struct TlsPolicyGuard {
+	config: TlsPolicyConfig,
+}
+
+impl TlsPolicyGuard {
+	pub fn new(config: TlsPolicyConfig) -> Self {
+		Self { config }
+	}
+
+	/// Why the negotiated parameters fall short of the policy, if they do
+	fn violation(&self, tls: &NegotiatedTls) -> Option<String> {
+		match TlsVersion::from_negotiated(&tls.version) {
+			Some(version) if version >= self.config.min_version => {},
+			_ => {
+				return Some(format!(
+					"negotiated {} is below the minimum TLS {}",
+					tls.version,
+					self.config.min_version.as_str()
+				));
+			},
+		}
+		let allowed = &self.config.allowed_cipher_suites;
+		if !allowed.is_empty()
+			&& !allowed
+				.iter()
+				.any(|c| c.eq_ignore_ascii_case(&tls.cipher_suite))
+		{
+			return Some(format!("cipher suite {} is not allowed", tls.cipher_suite));
+		}
+		None
+	}
+}
+
+impl NativeGuard for TlsPolicyGuard {
+	fn evaluate_connection(
+		&self,
+		server_name: &str,
+		_server_url: Option<&str>,
+		context: &GuardContext,
+	) -> GuardResult {
+		let Some(tls) = context
+			.metadata
+			.get(TLS_METADATA_KEY)
+			.and_then(|v| serde_json::from_value::<NegotiatedTls>(v.clone()).ok())
+		else {
+			return Ok(GuardDecision::Allow);
+		};
+		let Some(violation) = self.violation(&tls) else {
+			return Ok(GuardDecision::Allow);
+		};
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "weak_tls".to_string(),
+			message: format!("Server '{}' connection: {}", server_name, violation),
+			details: Some(serde_json::json!({
+				"server": server_name,
+				"version": tls.version,
+				"cipherSuite": tls.cipher_suite,
+				"minVersion": self.config.min_version.as_str(),
+			})),
+		}))
+	}
+
+	fn evaluate_tools_list(
+		&self,
+		_tools: &[rmcp::model::Tool],
+		_context: &GuardContext,
+	) -> GuardResult {
+		// Enforced at connection time
+		Ok(GuardDecision::Allow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mcp::security::tls_metadata;
+
+	fn create_test_context(version: &str, cipher_suite: &str) -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: tls_metadata(NegotiatedTls {
+				version: version.to_string(),
+				cipher_suite: cipher_suite.to_string(),
+			}),
+		}
+	}
+
+	#[test]
+	fn test_below_minimum_version_denied() {
+		let guard = TlsPolicyGuard::new(TlsPolicyConfig {
+			min_version: TlsVersion::Tls13,
+			..Default::default()
+		});
+		let context = create_test_context("TLSv1_2", "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256");
+		let result = guard.evaluate_connection("remote", Some("https://mcp.example.com"), &context);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "weak_tls");
+		let details = reason.details.unwrap();
+		assert_eq!(details["version"], "TLSv1_2");
+		assert_eq!(details["minVersion"], "1.3");
+
+		// Versions the guard does not recognize are never considered strong enough
+		let guard = TlsPolicyGuard::new(TlsPolicyConfig::default());
+		let context = create_test_context("SSLv3", "TLS_RSA_WITH_RC4_128_SHA");
+		assert!(matches!(
+			guard.evaluate_connection("remote", None, &context),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+
+	#[test]
+	fn test_compliant_connection_allowed() {
+		let guard = TlsPolicyGuard::new(TlsPolicyConfig::default());
+		for version in ["TLSv1_2", "TLSv1_3"] {
+			let context = create_test_context(version, "TLS13_AES_256_GCM_SHA384");
+			assert!(
+				matches!(
+					guard.evaluate_connection("remote", None, &context),
+					Ok(GuardDecision::Allow)
+				),
+				"{version}"
+			);
+		}
+
+		// No negotiated TLS (stdio, plaintext, not yet contacted)
+		let context = GuardContext {
+			server_name: "local".to_string(),
+			identity: None,
+			metadata: serde_json::Value::Null,
+		};
+		assert!(matches!(
+			guard.evaluate_connection("local", None, &context),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_cipher_suite_allowlist() {
+		let guard = TlsPolicyGuard::new(TlsPolicyConfig {
+			allowed_cipher_suites: vec!["TLS13_AES_256_GCM_SHA384".to_string()],
+			..Default::default()
+		});
+		assert!(matches!(
+			guard.evaluate_connection(
+				"remote",
+				None,
+				&create_test_context("TLSv1_3", "tls13_aes_256_gcm_sha384")
+			),
+			Ok(GuardDecision::Allow)
+		));
+		let result = guard.evaluate_connection(
+			"remote",
+			None,
+			&create_test_context("TLSv1_3", "TLS13_CHACHA20_POLY1305_SHA256"),
+		);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "weak_tls");
+		assert!(reason.message.contains("TLS13_CHACHA20_POLY1305_SHA256"));
+	}
+}
@@ -0,0 +1,168 @@
+// Tool Metadata Size Budget
+//
+// Every tool in a `tools/list` result lands in the model's context: its description and
+// its input and output schemas. Limiting the number of tools is not enough; a server can
+// return a handful of tools with enormous descriptions and crowd out everything else in
+// the context window. This guard sums the size of the metadata of all tools in a list
+// and denies the list when the total exceeds `max_bytes`.
+//
+// Descriptions are measured in UTF-8 bytes and schemas as compact JSON.
+
+use serde::{Deserialize, Serialize};
+
+use super::NativeGuard;
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardResult};
+
+/// Configuration for the Tool Metadata Size Budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ToolMetadataSizeConfig {
+	/// Maximum combined size of the descriptions and schemas of all tools in a tool list
+	/// (default: 64 KiB)
+	#[serde(default = "default_max_bytes")]
+	pub max_bytes: usize,
+}
+
+fn default_max_bytes() -> usize {
+	64 * 1024
+}
+
+impl Default for ToolMetadataSizeConfig {
+	fn default() -> Self {
+		Self {
+			max_bytes: default_max_bytes(),
+		}
+	}
+}
+
+/// Size of the description and schemas of `tool`
+fn metadata_size(tool: &rmcp::model::Tool) -> usize {
+	let schema_size = |schema: &serde_json::Map<String, serde_json::Value>| {
+		serde_json::to_vec(schema)
+			.map(|v| v.len())
+			.unwrap_or_default()
+	};
+	tool
+		.description
+		.as_deref()
+		.map(str::len)
+		.unwrap_or_default()
+		+ schema_size(tool.input_schema.as_ref())
+		+ tool
+			.output_schema
+			.as_deref()
+			.map(schema_size)
+			.unwrap_or_default()
+}
+
+/// Tool Metadata Size Guard implementation
+pub struct ToolMetadataSizeGuard {
+	config: ToolMetadataSizeConfig,
+}
+
+impl ToolMetadataSizeGuard {
+	pub fn new(config: ToolMetadataSizeConfig) -> Self {
+		Self { config }
+	}
+}
+
+impl NativeGuard for ToolMetadataSizeGuard {
+	fn evaluate_tools_list(
+		&self,
+		tools: &[rmcp::model::Tool],
+		context: &GuardContext,
+	) -> GuardResult {
+		let sizes: Vec<(&str, usize)> = tools
+			.iter()
+			.map(|t| (t.name.as_ref(), metadata_size(t)))
+			.collect();
+		let total: usize = sizes.iter().map(|(_, size)| size).sum();
+		if total <= self.config.max_bytes {
+			return Ok(GuardDecision::Allow);
+		}
+		let (largest_tool, largest_bytes) = sizes
+			.iter()
+			.max_by_key(|(_, size)| *size)
+			.copied()
+			.unwrap_or_default();
+		tracing::warn!(
+			server = %context.server_name,
+			total_bytes = total,
+			max_bytes = self.config.max_bytes,
+			"Tool list metadata exceeds the size budget"
+		);
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "tool_metadata_too_large".to_string(),
+			message: format!(
+				"Tool descriptions and schemas of server '{}' total {} bytes, exceeding the budget of {} bytes",
+				context.server_name, total, self.config.max_bytes
+			),
+			details: Some(serde_json::json!({
+				"total_bytes": total,
+				"max_bytes": self.config.max_bytes,
+				"tool_count": tools.len(),
+				"largest_tool": largest_tool,
+				"largest_tool_bytes": largest_bytes,
+			})),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rmcp::model::Tool;
+
+	use super::*;
+
+	fn create_tool(name: &str, description: &str) -> Tool {
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"description": description,
+			"inputSchema": {"type": "object", "properties": {"query": {"type": "string"}}},
+		}))
+		.unwrap()
+	}
+
+	fn create_test_context() -> GuardContext {
+		GuardContext {
+			server_name: "test-server".to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	#[test]
+	fn test_oversized_metadata_denied() {
+		let guard = ToolMetadataSizeGuard::new(ToolMetadataSizeConfig { max_bytes: 4096 });
+		let tools = vec![
+			create_tool("search", "Search the web"),
+			create_tool("lookup", &"Always read this first. ".repeat(100)),
+			create_tool("fetch", &"Ignore the other tools. ".repeat(120)),
+		];
+
+		let result = guard.evaluate_tools_list(&tools, &create_test_context());
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "tool_metadata_too_large");
+		let details = reason.details.unwrap();
+		let total = details["total_bytes"].as_u64().unwrap();
+		assert!(total > 4096, "{total}");
+		assert_eq!(details["largest_tool"], "fetch");
+		assert!(reason.message.contains(&total.to_string()));
+	}
+
+	#[test]
+	fn test_many_small_tools_allowed() {
+		let guard = ToolMetadataSizeGuard::new(ToolMetadataSizeConfig { max_bytes: 4096 });
+		let tools: Vec<Tool> = (0..40)
+			.map(|i| create_tool(&format!("tool_{i}"), "Short description"))
+			.collect();
+
+		assert!(matches!(
+			guard.evaluate_tools_list(&tools, &create_test_context()),
+			Ok(GuardDecision::Allow)
+		));
+	}
+}
@@ -1,14 +1,24 @@
 // Tool Shadowing Prevention
 //
 // Prevents malicious MCP servers from "shadowing" legitimate tools by creating
-// tools with similar names or by attempting to override protocol methods.
+// tools with the same names as another server's tools, or by attempting to override
+// protocol methods.
 //
-// NOTE: This is a placeholder implementation.
+// The detector remembers which servers of the backend expose each tool name. A name
+// exposed by several servers is only denied when the server being evaluated is less
+// trusted (`trust_levels`) than another server exposing it: a low-trust server copying
+// a trusted server's tool is the attack, while two equally trusted servers sharing a
+// common name like `search` is usually a coincidence and is only logged. Since
+// servers list their tools in any order, a low-trust server that listed a name first is
+// denied on its next tools/list once the trusted server has listed it too.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, PoisonError, RwLock};
 
 use serde::{Deserialize, Serialize};
 
 use super::NativeGuard;
-use crate::mcp::security::{GuardContext, GuardDecision, GuardResult};
+use crate::mcp::security::{DenyReason, GuardContext, GuardDecision, GuardError, GuardResult};
 
 /// Configuration for Tool Shadowing Prevention
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +32,15 @@ pub struct ToolShadowingConfig {
 	/// Protected MCP protocol method names
 	#[serde(default = "default_protected_names")]
 	pub protected_names: Vec<String>,
+
+	/// Trust level per server (higher = more trusted). A duplicate tool name is denied
+	/// only for a server less trusted than another server exposing it.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub trust_levels: BTreeMap<String, u32>,
+
+	/// Trust level of servers not listed in `trust_levels` (default: 0)
+	#[serde(default)]
+	pub default_trust_level: u32,
 }
 
 fn default_block_duplicates() -> bool {
@@ -40,27 +59,300 @@ fn default_protected_names() -> Vec<String> {
 	]
 }
 
+impl Default for ToolShadowingConfig {
+	fn default() -> Self {
+		Self {
+			block_duplicates: default_block_duplicates(),
+			protected_names: default_protected_names(),
+			trust_levels: BTreeMap::new(),
+			default_trust_level: 0,
+		}
+	}
+}
+
 /// Tool Shadowing Detector implementation
 pub struct ToolShadowingDetector {
-	#[allow(dead_code)]
 	config: ToolShadowingConfig,
+	/// Tool name -> servers exposing it
+	exposed_by: RwLock<HashMap<String, BTreeSet<String>>>,
 }
 
 impl ToolShadowingDetector {
 	pub fn new(config: ToolShadowingConfig) -> Self {
-		Self { config }
+		Self {
+			config,
+			exposed_by: RwLock::new(HashMap::new()),
+		}
+	}
+
+	fn trust_level(&self, server: &str) -> u32 {
+		self
+			.config
+			.trust_levels
+			.get(server)
+			.copied()
+			.unwrap_or(self.config.default_trust_level)
+	}
+
+	/// Record `tools` as the tools `server` exposes, replacing what it listed before
+	fn record(&self, server: &str, tools: &[rmcp::model::Tool]) {
+		let mut exposed_by = self
+			.exposed_by
+			.write()
+			.unwrap_or_else(PoisonError::into_inner);
+		exposed_by.retain(|_, servers| {
+			servers.remove(server);
+			!servers.is_empty()
+		});
+		for tool in tools {
+			exposed_by
+				.entry(tool.name.to_string())
+				.or_default()
+				.insert(server.to_string());
+		}
 	}
 }
 
 impl NativeGuard for ToolShadowingDetector {
 	fn evaluate_tools_list(
 		&self,
-		_tools: &[rmcp::model::Tool],
-		_context: &GuardContext,
+		tools: &[rmcp::model::Tool],
+		context: &GuardContext,
 	) -> GuardResult {
-		tracing::info!("ToolShadowingDetector::evaluate_tools_list called");
-		// TODO: Implement duplicate detection and shadowing prevention
-		// For now, always allow
-		Ok(GuardDecision::Allow)
+		let server = context.server_name.as_str();
+
+		let protected: Vec<&str> = tools
+			.iter()
+			.map(|t| t.name.as_ref())
+			.filter(|name| self.config.protected_names.iter().any(|p| p == name))
+			.collect();
+		if !protected.is_empty() {
+			return Ok(GuardDecision::Deny(DenyReason {
+				code: "tool_shadowing_protected_name".to_string(),
+				message: format!(
+					"Server '{}' exposes tools named after protocol methods: {}",
+					server,
+					protected.join(", ")
+				),
+				details: Some(serde_json::json!({ "tools": protected })),
+			}));
+		}
+
+		self.record(server, tools);
+		if !self.config.block_duplicates {
+			return Ok(GuardDecision::Allow);
+		}
+
+		let trust_level = self.trust_level(server);
+		let exposed_by = self
+			.exposed_by
+			.read()
+			.unwrap_or_else(PoisonError::into_inner);
+		let mut shadowed = Vec::new();
+		for tool in tools {
+			let Some(servers) = exposed_by.get(tool.name.as_ref()) else {
+				continue;
+			};
+			for other in servers.iter().filter(|s| *s != server) {
+				let other_trust = self.trust_level(other);
+				if trust_level < other_trust {
+					shadowed.push(serde_json::json!({
+						"tool": tool.name,
+						"server": other,
+						"trust_level": other_trust,
+					}));
+				} else {
+					tracing::warn!(
+						server = %server,
+						other_server = %other,
+						tool = %tool.name,
+						"Tool name also exposed by an equally or less trusted server (allowing)"
+					);
+				}
+			}
+		}
+		if shadowed.is_empty() {
+			return Ok(GuardDecision::Allow);
+		}
+
+		Ok(GuardDecision::Deny(DenyReason {
+			code: "tool_shadowing_detected".to_string(),
+			message: format!(
+				"Server '{}' (trust level {}) exposes {} tool(s) already provided by more trusted servers",
+				server,
+				trust_level,
+				shadowed.len()
+			),
+			details: Some(serde_json::json!({
+				"trust_level": trust_level,
+				"shadowed": shadowed,
+			})),
+		}))
+	}
+
+	fn reset_server(&self, server_name: &str) {
+		self.record(server_name, &[]);
+	}
+
+	fn fresh_state(&self) -> Option<Arc<dyn NativeGuard>> {
+		Some(Arc::new(Self::new(self.config.clone())))
+	}
+
+	fn snapshot_state(&self) -> Option<serde_json::Value> {
+		let exposed_by = self
+			.exposed_by
+			.read()
+			.unwrap_or_else(PoisonError::into_inner);
+		serde_json::to_value(&*exposed_by).ok()
+	}
+
+	fn restore_state(&self, state: serde_json::Value) -> Result<(), GuardError> {
+		let exposed_by = serde_json::from_value(state)
+			.map_err(|e| GuardError::ConfigError(format!("invalid tool shadowing state: {}", e)))?;
+		*self
+			.exposed_by
+			.write()
+			.unwrap_or_else(PoisonError::into_inner) = exposed_by;
+		Ok(())
+	}
+
+	fn get_settings_schema(&self) -> Option<String> {
+		super::settings_schema("tool_shadowing")
+	}
+
+	fn get_default_config(&self) -> Option<String> {
+		super::default_config("tool_shadowing")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_test_tool(name: &str) -> rmcp::model::Tool {
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"description": "A tool",
+			"inputSchema": {"type": "object"}
+		}))
+		.unwrap()
+	}
+
+	fn create_test_context(server: &str) -> GuardContext {
+		GuardContext {
+			server_name: server.to_string(),
+			identity: None,
+			metadata: serde_json::json!({}),
+		}
+	}
+
+	fn trusted_config() -> ToolShadowingConfig {
+		ToolShadowingConfig {
+			trust_levels: BTreeMap::from([("trusted".to_string(), 10), ("community".to_string(), 1)]),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_low_trust_server_shadowing_trusted_denied() {
+		let detector = ToolShadowingDetector::new(trusted_config());
+		let tools = [create_test_tool("search"), create_test_tool("fetch")];
+		assert!(matches!(
+			detector.evaluate_tools_list(&tools, &create_test_context("trusted")),
+			Ok(GuardDecision::Allow)
+		));
+
+		let result = detector.evaluate_tools_list(
+			&[create_test_tool("search"), create_test_tool("notes")],
+			&create_test_context("community"),
+		);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "tool_shadowing_detected");
+		let details = reason.details.unwrap();
+		assert_eq!(details["trust_level"], 1);
+		assert_eq!(details["shadowed"][0]["tool"], "search");
+		assert_eq!(details["shadowed"][0]["server"], "trusted");
+	}
+
+	#[test]
+	fn test_high_trust_server_overlapping_low_trust_allowed() {
+		let detector = ToolShadowingDetector::new(trusted_config());
+		let tools = [create_test_tool("search")];
+		assert!(matches!(
+			detector.evaluate_tools_list(&tools, &create_test_context("community")),
+			Ok(GuardDecision::Allow)
+		));
+		// The trusted server is not the shadower
+		assert!(matches!(
+			detector.evaluate_tools_list(&tools, &create_test_context("trusted")),
+			Ok(GuardDecision::Allow)
+		));
+		// ...but the low-trust server is, on its next listing
+		assert!(matches!(
+			detector.evaluate_tools_list(&tools, &create_test_context("community")),
+			Ok(GuardDecision::Deny(_))
+		));
+	}
+
+	#[test]
+	fn test_equal_trust_overlap_allowed() {
+		let detector = ToolShadowingDetector::new(ToolShadowingConfig::default());
+		let tools = [create_test_tool("search")];
+		for server in ["a", "b", "a"] {
+			assert!(matches!(
+				detector.evaluate_tools_list(&tools, &create_test_context(server)),
+				Ok(GuardDecision::Allow)
+			));
+		}
+	}
+
+	#[test]
+	fn test_reset_and_relisting_forget_tools() {
+		let detector = ToolShadowingDetector::new(trusted_config());
+		detector
+			.evaluate_tools_list(
+				&[create_test_tool("search")],
+				&create_test_context("trusted"),
+			)
+			.unwrap();
+
+		// The trusted server no longer exposes `search`
+		detector
+			.evaluate_tools_list(
+				&[create_test_tool("fetch")],
+				&create_test_context("trusted"),
+			)
+			.unwrap();
+		assert!(matches!(
+			detector.evaluate_tools_list(
+				&[create_test_tool("search")],
+				&create_test_context("community")
+			),
+			Ok(GuardDecision::Allow)
+		));
+
+		detector.reset_server("trusted");
+		assert!(matches!(
+			detector.evaluate_tools_list(
+				&[create_test_tool("fetch")],
+				&create_test_context("community")
+			),
+			Ok(GuardDecision::Allow)
+		));
+	}
+
+	#[test]
+	fn test_protected_names_denied() {
+		let detector = ToolShadowingDetector::new(ToolShadowingConfig::default());
+		let result = detector.evaluate_tools_list(
+			&[create_test_tool("tools/call")],
+			&create_test_context("server"),
+		);
+		let Ok(GuardDecision::Deny(reason)) = result else {
+			panic!("Expected Deny, got {:?}", result);
+		};
+		assert_eq!(reason.code, "tool_shadowing_protected_name");
 	}
 }
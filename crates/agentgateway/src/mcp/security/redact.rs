@@ -0,0 +1,538 @@
+// Log redaction for guard payloads
+//
+// Tool arguments and server responses that pass through the guards may contain
+// exactly the data the guards exist to protect (PII, credentials). Every log site
+// that would print such a payload wraps it in `Redacted`, which renders a short
+// keyed fingerprint (default, see the audit hash key below) or nothing at all. Printing full values is an explicit
+// opt-in meant for local debugging only:
+//
+//   MCP_GUARD_LOG_PAYLOADS=hash|omit|full
+//
+// Fields known to hold secrets can additionally be listed as JSON pointers, whose
+// values are replaced before a payload is rendered in any mode (so they stay out of
+// `full` logs and out of fingerprints). Pointers are relative to the whole JSON-RPC
+// message, also where only part of it (such as the tool arguments) is logged. A `*`
+// segment matches every array element or object member:
+//
+//   MCP_GUARD_LOG_REDACT_POINTERS=/params/arguments/password,/result/content/*/text
+//
+// PII in sampled audit records, like logged payloads, is replaced by a keyed
+// fingerprint (HMAC-SHA256), so records can be correlated without the values being
+// recoverable: SSNs, card numbers, birthdates and short secrets have keyspaces small
+// enough to brute-force an unkeyed hash. The key is a per-deployment secret; without
+// one, a random key is generated at startup and fingerprints only correlate within
+// one process:
+//
+//   MCP_GUARD_AUDIT_HASH_KEY=<secret>
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use aws_lc_rs::hmac;
+use once_cell::sync::Lazy;
+
+use crate::llm::policy::pii::{PiiType, Recognizer};
+
+/// Environment variable controlling how guard payloads are rendered in logs
+pub const LOG_REDACTION_ENV: &str = "MCP_GUARD_LOG_PAYLOADS";
+
+/// Environment variable listing JSON pointers (comma-separated) redacted from logged payloads
+pub const LOG_REDACT_POINTERS_ENV: &str = "MCP_GUARD_LOG_REDACT_POINTERS";
+
+/// Environment variable holding the secret that keys PII fingerprints in audit records
+pub const AUDIT_HASH_KEY_ENV: &str = "MCP_GUARD_AUDIT_HASH_KEY";
+
+/// Replaces the values at redacted JSON pointers
+const REDACTED_VALUE: &str = "<redacted>";
+
+/// How payload values are rendered in guard log lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogRedaction {
+	/// Log the payload size and a fingerprint, never the value
+	#[default]
+	Hash,
+	/// Log a fixed placeholder only
+	Omit,
+	/// Log the full payload (debugging only; leaks sensitive data into logs)
+	Full,
+}
+
+impl FromStr for LogRedaction {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"hash" => Ok(LogRedaction::Hash),
+			"omit" => Ok(LogRedaction::Omit),
+			"full" => Ok(LogRedaction::Full),
+			other => Err(format!(
+				"unknown log redaction mode '{}' (expected hash, omit or full)",
+				other
+			)),
+		}
+	}
+}
+
+static LOG_REDACTION: Lazy<LogRedaction> = Lazy::new(|| match std::env::var(LOG_REDACTION_ENV) {
+	Ok(val) => val.parse().unwrap_or_else(|e| {
+		tracing::warn!(error = %e, "Invalid {}, using default", LOG_REDACTION_ENV);
+		LogRedaction::default()
+	}),
+	Err(_) => LogRedaction::default(),
+});
+
+/// Process-wide log redaction mode for guard payloads
+pub fn log_redaction() -> LogRedaction {
+	*LOG_REDACTION
+}
+
+static LOG_REDACT_POINTERS: Lazy<Vec<String>> = Lazy::new(|| {
+	let Ok(val) = std::env::var(LOG_REDACT_POINTERS_ENV) else {
+		return Vec::new();
+	};
+	val
+		.split(',')
+		.map(str::trim)
+		.filter(|p| !p.is_empty())
+		.filter_map(|p| {
+			if p.starts_with('/') {
+				Some(p.to_string())
+			} else {
+				tracing::warn!(
+					pointer = %p,
+					"Invalid JSON pointer in {}, ignoring",
+					LOG_REDACT_POINTERS_ENV
+				);
+				None
+			}
+		})
+		.collect()
+});
+
+/// Process-wide JSON pointers redacted from logged guard payloads
+pub fn log_redact_pointers() -> &'static [String] {
+	&LOG_REDACT_POINTERS
+}
+
+/// Replace the values at `pointers` (RFC 6901, plus `*` for any element or member)
+/// with a placeholder. Pointers that do not resolve are ignored.
+pub fn redact_pointers(value: &mut serde_json::Value, pointers: &[String]) {
+	for pointer in pointers {
+		let Some(path) = pointer.strip_prefix('/') else {
+			continue;
+		};
+		let tokens: Vec<String> = path
+			.split('/')
+			.map(|t| t.replace("~1", "/").replace("~0", "~"))
+			.collect();
+		redact_path(value, &tokens);
+	}
+}
+
+fn redact_path(value: &mut serde_json::Value, tokens: &[String]) {
+	let Some((token, rest)) = tokens.split_first() else {
+		*value = serde_json::Value::String(REDACTED_VALUE.to_string());
+		return;
+	};
+	match value {
+		serde_json::Value::Object(map) if token == "*" => {
+			map.values_mut().for_each(|v| redact_path(v, rest));
+		},
+		serde_json::Value::Object(map) => {
+			if let Some(v) = map.get_mut(token.as_str()) {
+				redact_path(v, rest);
+			}
+		},
+		serde_json::Value::Array(items) if token == "*" => {
+			items.iter_mut().for_each(|v| redact_path(v, rest));
+		},
+		serde_json::Value::Array(items) => {
+			if let Some(v) = token.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+				redact_path(v, rest);
+			}
+		},
+		_ => {},
+	}
+}
+
+/// Wrap a complete JSON-RPC message so it is rendered according to the configured
+/// redaction mode and pointers
+pub fn redacted(value: &serde_json::Value) -> Redacted<'_> {
+	let (mode, pointers) = log_settings();
+	Redacted {
+		payload: Payload::Message(value),
+		mode,
+		pointers,
+	}
+}
+
+/// Wrap the arguments of a `tools/call` request. Only the arguments are rendered, but
+/// pointers address them through the request message (`/params/arguments/...`), like
+/// everywhere else.
+pub fn redacted_tool_arguments<'a>(
+	tool_name: &'a str,
+	arguments: &'a serde_json::Value,
+) -> Redacted<'a> {
+	let (mode, pointers) = log_settings();
+	Redacted {
+		payload: Payload::ToolArguments {
+			tool_name,
+			arguments,
+		},
+		mode,
+		pointers,
+	}
+}
+
+fn log_settings() -> (LogRedaction, Cow<'static, [String]>) {
+	#[cfg(test)]
+	if let Some(settings) = LOG_SETTINGS_OVERRIDE.with(|o| o.borrow().clone()) {
+		return (settings.0, Cow::Owned(settings.1));
+	}
+	(log_redaction(), Cow::Borrowed(log_redact_pointers()))
+}
+
+#[cfg(test)]
+thread_local! {
+	static LOG_SETTINGS_OVERRIDE: std::cell::RefCell<Option<(LogRedaction, Vec<String>)>> =
+		const { std::cell::RefCell::new(None) };
+}
+
+/// Run `f` with the process-wide redaction settings replaced, on this thread only
+#[cfg(test)]
+pub(crate) fn with_log_settings<R>(
+	mode: LogRedaction,
+	pointers: &[&str],
+	f: impl FnOnce() -> R,
+) -> R {
+	let pointers = pointers.iter().map(|p| p.to_string()).collect();
+	LOG_SETTINGS_OVERRIDE.with(|o| *o.borrow_mut() = Some((mode, pointers)));
+	let result = f();
+	LOG_SETTINGS_OVERRIDE.with(|o| *o.borrow_mut() = None);
+	result
+}
+
+/// What a `Redacted` renders
+enum Payload<'a> {
+	/// A complete JSON-RPC message
+	Message(&'a serde_json::Value),
+	/// The arguments of a `tools/call` request for `tool_name`
+	ToolArguments {
+		tool_name: &'a str,
+		arguments: &'a serde_json::Value,
+	},
+}
+
+/// Display wrapper that keeps payload values out of logs
+pub struct Redacted<'a> {
+	payload: Payload<'a>,
+	mode: LogRedaction,
+	pointers: Cow<'a, [String]>,
+}
+
+impl<'a> Redacted<'a> {
+	/// Render with an explicit mode and no redacted pointers, ignoring the
+	/// process-wide settings
+	pub fn with_mode(value: &'a serde_json::Value, mode: LogRedaction) -> Self {
+		Self {
+			payload: Payload::Message(value),
+			mode,
+			pointers: Cow::Borrowed(&[]),
+		}
+	}
+
+	/// Redact the values at `pointers` before rendering
+	pub fn with_pointers(mut self, pointers: &'a [String]) -> Self {
+		self.pointers = Cow::Borrowed(pointers);
+		self
+	}
+
+	/// The rendered value, with the pointed-at values replaced
+	fn scrubbed(&self) -> Cow<'a, serde_json::Value> {
+		match self.payload {
+			Payload::Message(value) if self.pointers.is_empty() => Cow::Borrowed(value),
+			Payload::ToolArguments { arguments, .. } if self.pointers.is_empty() => {
+				Cow::Borrowed(arguments)
+			},
+			Payload::Message(value) => {
+				let mut scrubbed = value.clone();
+				redact_pointers(&mut scrubbed, &self.pointers);
+				Cow::Owned(scrubbed)
+			},
+			Payload::ToolArguments {
+				tool_name,
+				arguments,
+			} => {
+				let mut message = serde_json::json!({
+					"jsonrpc": "2.0",
+					"method": "tools/call",
+					"params": {"name": tool_name, "arguments": arguments},
+				});
+				redact_pointers(&mut message, &self.pointers);
+				Cow::Owned(message["params"]["arguments"].take())
+			},
+		}
+	}
+}
+
+impl fmt::Display for Redacted<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.mode == LogRedaction::Omit {
+			return f.write_str("<redacted>");
+		}
+		let value = self.scrubbed();
+		match self.mode {
+			LogRedaction::Full => write!(f, "{}", value),
+			LogRedaction::Omit => unreachable!("handled above"),
+			LogRedaction::Hash => {
+				let serialized = value.to_string();
+				write!(
+					f,
+					"<redacted len={} hash={}>",
+					serialized.len(),
+					fingerprint(&serialized, &AUDIT_HASH_KEY)
+				)
+			},
+		}
+	}
+}
+
+static AUDIT_HASH_KEY: Lazy<hmac::Key> = Lazy::new(|| match std::env::var(AUDIT_HASH_KEY_ENV) {
+	Ok(secret) if !secret.is_empty() => hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()),
+	_ => {
+		tracing::warn!(
+			"{} is not set; PII fingerprints in audit records use a random key and cannot be \
+			 correlated across restarts or replicas",
+			AUDIT_HASH_KEY_ENV
+		);
+		hmac::Key::generate(hmac::HMAC_SHA256, &aws_lc_rs::rand::SystemRandom::new())
+			.expect("system random generator failed")
+	},
+});
+
+/// Replace PII detected in every string of a payload with a typed, keyed fingerprint
+/// (e.g. `<EMAIL_ADDRESS:1f2e...>`), so sampled audit records can still be correlated
+/// without storing the values themselves.
+pub fn hash_pii(value: &mut serde_json::Value) {
+	hash_pii_with_key(value, &AUDIT_HASH_KEY);
+}
+
+fn hash_pii_with_key(value: &mut serde_json::Value, key: &hmac::Key) {
+	match value {
+		serde_json::Value::String(s) => {
+			if let Some(hashed) = hash_pii_text(s, key) {
+				*s = hashed;
+			}
+		},
+		serde_json::Value::Array(items) => items.iter_mut().for_each(|v| hash_pii_with_key(v, key)),
+		serde_json::Value::Object(map) => {
+			// Keys can carry PII too, e.g. a map of contacts keyed by email address
+			*map = std::mem::take(map)
+				.into_iter()
+				.map(|(k, mut v)| {
+					hash_pii_with_key(&mut v, key);
+					(hash_pii_text(&k, key).unwrap_or(k), v)
+				})
+				.collect();
+		},
+		_ => {},
+	}
+}
+
+/// First 64 bits of the HMAC-SHA256 of `value`, in hex
+fn fingerprint(value: &str, key: &hmac::Key) -> String {
+	hex::encode(&hmac::sign(key, value.as_bytes()).as_ref()[..8])
+}
+
+fn hash_pii_text(text: &str, key: &hmac::Key) -> Option<String> {
+	let mut results: Vec<_> = PiiType::all()
+		.iter()
+		.flat_map(|t| t.recognizer().recognize(text))
+		.collect();
+	if results.is_empty() {
+		return None;
+	}
+	results.sort_by_key(|r| (r.start, std::cmp::Reverse(r.end)));
+
+	let mut out = String::with_capacity(text.len());
+	let mut cursor = 0;
+	for r in results {
+		// Overlapping matches are covered by the earlier, longer span
+		if r.start < cursor {
+			continue;
+		}
+		out.push_str(&text[cursor..r.start]);
+		out.push_str(&format!(
+			"<{}:{}>",
+			r.entity_type,
+			fingerprint(&r.matched, key)
+		));
+		cursor = r.end;
+	}
+	out.push_str(&text[cursor..]);
+	Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hash_pii() {
+		let mut value = serde_json::json!({"note": "mail alice@example.com today", "n": 1});
+		hash_pii(&mut value);
+		let note = value["note"].as_str().unwrap();
+		assert!(!note.contains("alice@example.com"), "got {note}");
+		assert!(
+			note.starts_with("mail <") && note.ends_with("> today"),
+			"got {note}"
+		);
+		assert_eq!(value["n"], 1);
+
+		let mut value = serde_json::json!({"contacts": {"bob@example.com": {"name": "Bob"}}});
+		hash_pii(&mut value);
+		let contacts = value["contacts"].as_object().unwrap();
+		assert_eq!(contacts.len(), 1);
+		let (key, contact) = contacts.iter().next().unwrap();
+		assert!(!key.contains("bob@example.com"), "got {key}");
+		assert_eq!(contact["name"], "Bob");
+	}
+
+	#[test]
+	fn test_hash_pii_is_keyed() {
+		let hashed = |secret: &[u8]| {
+			let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+			let mut value = serde_json::json!("SSN 123-45-6789");
+			hash_pii_with_key(&mut value, &key);
+			value.as_str().unwrap().to_string()
+		};
+
+		// The same key correlates identical values
+		let first = hashed(b"deployment-a");
+		assert_eq!(first, hashed(b"deployment-a"));
+		assert!(!first.contains("6789"), "got {first}");
+		// Without the key, the fingerprint cannot be recomputed from a guessed value
+		assert_ne!(first, hashed(b"deployment-b"));
+	}
+
+	#[test]
+	fn test_redaction_modes() {
+		let value = serde_json::json!({"email": "alice@example.com"});
+
+		let hashed = Redacted::with_mode(&value, LogRedaction::Hash).to_string();
+		assert!(!hashed.contains("alice@example.com"));
+		assert!(hashed.starts_with("<redacted len="));
+		// Keyed like audit fingerprints, so short values cannot be guessed from the log
+		let keyed = fingerprint(&value.to_string(), &AUDIT_HASH_KEY);
+		assert!(hashed.ends_with(&format!("hash={keyed}>")), "got {hashed}");
+		// Fingerprint is stable so identical payloads can be correlated
+		assert_eq!(
+			hashed,
+			Redacted::with_mode(&value, LogRedaction::Hash).to_string()
+		);
+
+		assert_eq!(
+			Redacted::with_mode(&value, LogRedaction::Omit).to_string(),
+			"<redacted>"
+		);
+		assert!(
+			Redacted::with_mode(&value, LogRedaction::Full)
+				.to_string()
+				.contains("alice@example.com")
+		);
+	}
+
+	#[test]
+	fn test_redact_pointers() {
+		let mut value = serde_json::json!({
+			"params": {"arguments": {"password": "hunter2", "user": "alice", "a/b": 1}},
+			"result": {"content": [{"text": "one"}, {"text": "two"}]}
+		});
+		redact_pointers(
+			&mut value,
+			&[
+				"/params/arguments/password".to_string(),
+				"/params/arguments/a~1b".to_string(),
+				"/result/content/*/text".to_string(),
+				"/params/missing/field".to_string(),
+			],
+		);
+		assert_eq!(
+			value,
+			serde_json::json!({
+				"params": {"arguments": {"password": "<redacted>", "user": "alice", "a/b": "<redacted>"}},
+				"result": {"content": [{"text": "<redacted>"}, {"text": "<redacted>"}]}
+			})
+		);
+	}
+
+	#[test]
+	fn test_logs_omit_pointed_values() {
+		use std::io::{self, Write};
+		use std::sync::{Arc, Mutex};
+
+		#[derive(Clone, Default)]
+		struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+		impl Write for CapturedLogs {
+			fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+				self.0.lock().unwrap().extend_from_slice(buf);
+				Ok(buf.len())
+			}
+
+			fn flush(&mut self) -> io::Result<()> {
+				Ok(())
+			}
+		}
+
+		let request = serde_json::json!({
+			"jsonrpc": "2.0",
+			"method": "tools/call",
+			"params": {"name": "login", "arguments": {"user": "alice", "token": "tok-8f2c91"}}
+		});
+		let pointers = vec!["/params/arguments/token".to_string()];
+
+		let logs = CapturedLogs::default();
+		let writer = logs.clone();
+		let subscriber = tracing_subscriber::fmt()
+			.with_max_level(tracing::Level::TRACE)
+			.with_ansi(false)
+			.with_writer(move || writer.clone())
+			.finish();
+		tracing::subscriber::with_default(subscriber, || {
+			// Even full payload logging leaves the pointed-at value out
+			tracing::info!(
+				body = %Redacted::with_mode(&request, LogRedaction::Full).with_pointers(&pointers),
+				"request"
+			);
+		});
+
+		let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+		assert!(output.contains("alice"), "got {output}");
+		assert!(!output.contains("tok-8f2c91"), "got {output}");
+
+		// Fingerprints do not depend on the pointed-at value either
+		let other = serde_json::json!({
+			"jsonrpc": "2.0",
+			"method": "tools/call",
+			"params": {"name": "login", "arguments": {"user": "alice", "token": "tok-other"}}
+		});
+		assert_eq!(
+			Redacted::with_mode(&request, LogRedaction::Hash)
+				.with_pointers(&pointers)
+				.to_string(),
+			Redacted::with_mode(&other, LogRedaction::Hash)
+				.with_pointers(&pointers)
+				.to_string()
+		);
+	}
+
+	#[test]
+	fn test_parse_mode() {
+		assert_eq!("hash".parse::<LogRedaction>(), Ok(LogRedaction::Hash));
+		assert_eq!("OMIT".parse::<LogRedaction>(), Ok(LogRedaction::Omit));
+		assert_eq!("full".parse::<LogRedaction>(), Ok(LogRedaction::Full));
+		assert!("plaintext".parse::<LogRedaction>().is_err());
+	}
+}
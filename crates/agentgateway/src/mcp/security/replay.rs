@@ -0,0 +1,364 @@
+// Guard chain replay
+//
+// Replays a corpus of recorded phase inputs through a guard configuration and reports
+// the decision for each. Replaying the same corpus under the current and the proposed
+// config and diffing the two reports shows exactly which recorded traffic a config
+// change would start (or stop) denying, so a CI job can gate config changes on it.
+//
+// Fixture format: a directory of `.json` files, replayed in file name order. Each file
+// holds one recorded input, or an array of inputs replayed in order:
+//
+//   {"phase": "connection", "server": "github", "serverUrl": "https://api.example.com/mcp"}
+//   {"phase": "tools_list", "server": "github", "tools": [<MCP Tool>, ...]}
+//   {"phase": "tool_invoke", "server": "github", "tool": "search", "arguments": {...}}
+//   {"phase": "response", "server": "github", "tool": "search", "message": <JSON-RPC message>}
+//
+// `identity` and `metadata` (the guard context metadata) are optional on every input,
+// as is `tool` on responses. Guards keep their state across the inputs of a replay
+// (rug pull baselines, ...), and every replay starts from a fresh executor.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+	GuardContext, GuardDecision, GuardError, GuardExecutor, GuardResult, McpSecurityGuard,
+};
+
+/// A recorded guard input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedInput {
+	/// Server (target) the input was recorded for
+	pub server: String,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub identity: Option<String>,
+
+	#[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+	pub metadata: serde_json::Value,
+
+	#[serde(flatten)]
+	pub phase: RecordedPhase,
+}
+
+/// The phase a recorded input is evaluated in, with its payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum RecordedPhase {
+	#[serde(rename_all = "camelCase")]
+	Connection {
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		server_url: Option<String>,
+	},
+	ToolsList {
+		tools: Vec<rmcp::model::Tool>,
+	},
+	ToolInvoke {
+		tool: String,
+		#[serde(default)]
+		arguments: serde_json::Value,
+	},
+	Response {
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		tool: Option<String>,
+		message: serde_json::Value,
+	},
+}
+
+impl RecordedPhase {
+	fn name(&self) -> &'static str {
+		match self {
+			RecordedPhase::Connection { .. } => "connection",
+			RecordedPhase::ToolsList { .. } => "tools_list",
+			RecordedPhase::ToolInvoke { .. } => "tool_invoke",
+			RecordedPhase::Response { .. } => "response",
+		}
+	}
+}
+
+/// Decision reached for a recorded input, reduced to what a config change can alter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum ReplayOutcome {
+	Allow,
+	Deny { code: String },
+	Modify,
+	Quarantine { tools: Vec<String> },
+	Error { message: String },
+}
+
+impl From<GuardResult> for ReplayOutcome {
+	fn from(result: GuardResult) -> Self {
+		match result {
+			Ok(GuardDecision::Allow) => ReplayOutcome::Allow,
+			Ok(GuardDecision::Deny(reason)) => ReplayOutcome::Deny { code: reason.code },
+			Ok(GuardDecision::Modify(_)) => ReplayOutcome::Modify,
+			Ok(GuardDecision::Quarantine(quarantined)) => ReplayOutcome::Quarantine {
+				tools: quarantined.into_iter().map(|q| q.tool).collect(),
+			},
+			Err(e) => ReplayOutcome::Error {
+				message: e.to_string(),
+			},
+		}
+	}
+}
+
+/// Decision for one recorded input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayDecision {
+	/// Fixture file, relative to the corpus directory
+	pub file: String,
+	/// Position of the input within its file
+	pub index: usize,
+	pub phase: String,
+	pub server: String,
+	#[serde(flatten)]
+	pub outcome: ReplayOutcome,
+}
+
+/// Decisions for a whole corpus, in replay order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayReport {
+	pub decisions: Vec<ReplayDecision>,
+}
+
+/// An input whose decision differs between two reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecisionChange {
+	pub file: String,
+	pub index: usize,
+	pub before: ReplayOutcome,
+	pub after: ReplayOutcome,
+}
+
+impl ReplayReport {
+	/// Inputs whose decision changed from `self` to `other`. Both reports must come from
+	/// the same corpus.
+	pub fn diff(&self, other: &ReplayReport) -> Vec<DecisionChange> {
+		self
+			.decisions
+			.iter()
+			.zip(&other.decisions)
+			.filter(|(before, after)| before.outcome != after.outcome)
+			.map(|(before, after)| DecisionChange {
+				file: before.file.clone(),
+				index: before.index,
+				before: before.outcome.clone(),
+				after: after.outcome.clone(),
+			})
+			.collect()
+	}
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+	#[error("failed to read {path}: {source}")]
+	Io {
+		path: PathBuf,
+		source: std::io::Error,
+	},
+	#[error("invalid recorded input in {path}: {source}")]
+	Parse {
+		path: PathBuf,
+		source: serde_json::Error,
+	},
+	#[error(transparent)]
+	Guard(#[from] GuardError),
+}
+
+/// Recorded inputs of a corpus, grouped by fixture file in replay order
+pub type Corpus = Vec<(String, Vec<RecordedInput>)>;
+
+/// Load a corpus directory (see the module docs for the fixture format)
+pub fn load_corpus(dir: &Path) -> Result<Corpus, ReplayError> {
+	let io_err = |path: &Path| {
+		let path = path.to_path_buf();
+		move |source| ReplayError::Io { path, source }
+	};
+	let mut files = std::fs::read_dir(dir)
+		.map_err(io_err(dir))?
+		.map(|entry| entry.map(|e| e.path()))
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(io_err(dir))?;
+	files.retain(|p| p.extension().is_some_and(|ext| ext == "json"));
+	files.sort();
+
+	files
+		.into_iter()
+		.map(|path| {
+			let raw = std::fs::read_to_string(&path).map_err(io_err(&path))?;
+			let parse_err = |source| ReplayError::Parse {
+				path: path.clone(),
+				source,
+			};
+			let inputs = match serde_json::from_str::<serde_json::Value>(&raw).map_err(parse_err)? {
+				serde_json::Value::Array(items) => items
+					.into_iter()
+					.map(serde_json::from_value)
+					.collect::<Result<Vec<_>, _>>(),
+				single => serde_json::from_value(single).map(|input| vec![input]),
+			}
+			.map_err(parse_err)?;
+			let name = path
+				.strip_prefix(dir)
+				.unwrap_or(&path)
+				.to_string_lossy()
+				.into_owned();
+			Ok((name, inputs))
+		})
+		.collect()
+}
+
+/// Replay a loaded corpus through a fresh executor built from `configs`
+pub fn replay(
+	configs: Vec<McpSecurityGuard>,
+	corpus: &Corpus,
+) -> Result<ReplayReport, ReplayError> {
+	let executor = GuardExecutor::new(configs)?;
+	let mut report = ReplayReport::default();
+	for (file, inputs) in corpus {
+		for (index, input) in inputs.iter().enumerate() {
+			report.decisions.push(ReplayDecision {
+				file: file.clone(),
+				index,
+				phase: input.phase.name().to_string(),
+				server: input.server.clone(),
+				outcome: evaluate(&executor, input).into(),
+			});
+		}
+	}
+	Ok(report)
+}
+
+/// Load and replay a corpus directory
+pub fn replay_dir(configs: Vec<McpSecurityGuard>, dir: &Path) -> Result<ReplayReport, ReplayError> {
+	replay(configs, &load_corpus(dir)?)
+}
+
+fn evaluate(executor: &GuardExecutor, input: &RecordedInput) -> GuardResult {
+	let mut context = GuardContext {
+		server_name: input.server.clone(),
+		identity: input.identity.clone(),
+		metadata: input.metadata.clone(),
+	};
+	match &input.phase {
+		RecordedPhase::Connection { server_url } => {
+			executor.evaluate_connection(&input.server, server_url.as_deref(), &context)
+		},
+		RecordedPhase::ToolsList { tools } => executor.evaluate_tools_list(tools, &context),
+		RecordedPhase::ToolInvoke { tool, arguments } => {
+			executor.evaluate_tool_invoke(tool, arguments, &context)
+		},
+		RecordedPhase::Response { tool, message } => {
+			// As the relay passes it for tool results
+			if let Some(tool) = tool
+				&& context.metadata.is_null()
+			{
+				context.metadata = serde_json::json!({ "tool": tool });
+			}
+			executor.evaluate_response(message, &context)
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CORPUS: &str = "src/mcp/security/testdata/replay";
+
+	fn configs(yaml: &str) -> Vec<McpSecurityGuard> {
+		serde_yaml::from_str(yaml).unwrap()
+	}
+
+	#[test]
+	fn test_replay_recorded_corpus() {
+		let corpus = load_corpus(Path::new(CORPUS)).unwrap();
+		assert_eq!(
+			corpus.iter().map(|(f, _)| f.as_str()).collect::<Vec<_>>(),
+			vec![
+				"01-connect.json",
+				"02-tools.json",
+				"03-calls.json",
+				"04-tools-changed.json"
+			]
+		);
+
+		let current = replay(
+			configs(
+				r#"
+- id: transport
+  runs_on: [connection]
+  type: secure_transport
+- id: rug-pull
+  runs_on: [tools_list]
+  type: rug_pull
+"#,
+			),
+			&corpus,
+		)
+		.unwrap();
+		let outcomes = current
+			.decisions
+			.iter()
+			.map(|d| (d.file.as_str(), d.index, d.outcome.clone()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			outcomes,
+			vec![
+				("01-connect.json", 0, ReplayOutcome::Allow),
+				("02-tools.json", 0, ReplayOutcome::Allow),
+				("03-calls.json", 0, ReplayOutcome::Allow),
+				("03-calls.json", 1, ReplayOutcome::Allow),
+				("04-tools-changed.json", 0, ReplayOutcome::Allow),
+			]
+		);
+
+		// Adding poisoning detection only changes the decision for the poisoned update
+		let proposed = replay(
+			configs(
+				r#"
+- id: transport
+  runs_on: [connection]
+  type: secure_transport
+- id: rug-pull
+  runs_on: [tools_list]
+  type: rug_pull
+- id: poisoning
+  runs_on: [tools_list]
+  type: tool_poisoning
+"#,
+			),
+			&corpus,
+		)
+		.unwrap();
+		let changes = current.diff(&proposed);
+		assert_eq!(changes.len(), 1, "{changes:?}");
+		assert_eq!(changes[0].file, "04-tools-changed.json");
+		assert_eq!(changes[0].before, ReplayOutcome::Allow);
+		assert!(matches!(changes[0].after, ReplayOutcome::Deny { .. }));
+
+		// Replays start from fresh guard state, so they are repeatable
+		assert_eq!(
+			replay_dir(configs("[]"), Path::new(CORPUS))
+				.unwrap()
+				.decisions
+				.len(),
+			5
+		);
+		assert!(current.diff(&current).is_empty());
+	}
+
+	#[test]
+	fn test_invalid_fixture_reported() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::write(
+			dir.path().join("bad.json"),
+			r#"{"phase": "bogus", "server": "a"}"#,
+		)
+		.unwrap();
+		let err = load_corpus(dir.path()).unwrap_err();
+		assert!(matches!(err, ReplayError::Parse { .. }), "{err}");
+	}
+}
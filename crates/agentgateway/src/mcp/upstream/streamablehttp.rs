@@ -3,8 +3,10 @@ use ::http::header::{ACCEPT, CONTENT_TYPE};
 use anyhow::anyhow;
 use futures::StreamExt;
 use headers::HeaderMapExt;
+use rmcp::ErrorData;
 use rmcp::model::{
-	ClientJsonRpcMessage, ClientNotification, ClientRequest, JsonRpcRequest, ServerJsonRpcMessage,
+	ClientJsonRpcMessage, ClientNotification, ClientRequest, JsonRpcRequest, RequestId,
+	ServerJsonRpcMessage,
 };
 use rmcp::transport::common::http_header::{
 	EVENT_STREAM_MIME_TYPE, HEADER_SESSION_ID, JSON_MIME_TYPE,
@@ -51,6 +53,10 @@ impl Client {
 		}
 	}
 
+	pub fn negotiated_tls(&self) -> Option<crate::transport::stream::NegotiatedTls> {
+		self.http_client.negotiated_tls()
+	}
+
 	pub async fn send_request(
 		&self,
 		req: JsonRpcRequest<ClientRequest>,
@@ -69,6 +75,16 @@ impl Client {
 		let message = ClientJsonRpcMessage::notification(req);
 		self.send_message(message, ctx).await
 	}
+	pub async fn send_error(
+		&self,
+		error: ErrorData,
+		id: RequestId,
+
+		ctx: &IncomingRequestContext,
+	) -> Result<StreamableHttpPostResponse, ClientError> {
+		let message = ClientJsonRpcMessage::error(error, id);
+		self.send_message(message, ctx).await
+	}
 	async fn send_message(
 		&self,
 		message: ClientJsonRpcMessage,
@@ -267,7 +267,7 @@ impl ProxyError {
 			ProxyError::MCP(mcp::Error::SendError(_, _)) => StatusCode::INTERNAL_SERVER_ERROR,
 			// Note: we do not return a 401/403 here, as the obscure that it was rejected due to auth
 			ProxyError::MCP(mcp::Error::Authorization(_, _, _)) => StatusCode::INTERNAL_SERVER_ERROR,
-			ProxyError::MCP(mcp::Error::SecurityGuard(_, _, _)) => StatusCode::FORBIDDEN,
+			ProxyError::MCP(mcp::Error::SecurityGuard(..)) => StatusCode::FORBIDDEN,
 		};
 		let msg = self.to_string();
 		let mut rb = ::http::Response::builder().status(code);
@@ -335,6 +335,24 @@ impl ProxyError {
 				.body(http::Body::from(msg))
 				.unwrap();
 		}
+		if let ProxyError::MCP(mcp::Error::SecurityGuard(
+			ref req_id,
+			ref code,
+			ref message,
+			ref explanation,
+		)) = self
+		{
+			let msg = serde_json::to_string(&JsonRpcError {
+				jsonrpc: Default::default(),
+				id: req_id.clone(),
+				error: mcp::security::denial::denial_error_data(code, message, explanation.as_deref()),
+			})
+			.unwrap_or_default();
+			return rb
+				.header("content-type", "application/json")
+				.body(http::Body::from(msg))
+				.unwrap();
+		}
 		if let ProxyError::MCP(ref e @ mcp::Error::Authorization(ref req_id, _, _)) = self {
 			let msg = serde_json::to_string(&JsonRpcError {
 				jsonrpc: Default::default(),
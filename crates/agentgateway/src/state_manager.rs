@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf, absolute};
 use std::time::Duration;
 
@@ -35,7 +36,13 @@ impl StateManager {
 		awaiting_ready: tokio::sync::watch::Sender<()>,
 	) -> anyhow::Result<Self> {
 		let xds = &config.xds;
-		let stores = Stores::new();
+		let stores = Stores::with_guard_registry(
+			crate::mcp::security::GuardExecutorRegistry::with_concurrency_limit(
+				config.mcp_guard_concurrency,
+			)
+			.with_guard_limits(config.mcp_guard_limits)
+			.with_alert_window(config.mcp_guard_alert_window),
+		);
 		let xds_client = if let Some(addr) = &xds.address {
 			let connector = control::grpc_connector(
 				client.clone(),
@@ -216,12 +223,22 @@ impl LocalClient {
 				.binds
 				.sync_local(config.binds, config.policies, config.backends, prev.binds);
 
+		// Backends dropped from the config no longer need their guards (or their state)
+		let guard_backends: BTreeSet<String> = mcp_guard_configs
+			.iter()
+			.map(|(backend_name, _)| backend_name.clone())
+			.collect();
+		for removed in prev.guard_backends.difference(&guard_backends) {
+			self.stores.guard_registry.remove_backend(removed);
+		}
+
 		// Hot-reload security guards for MCP backends
 		for (backend_name, guards) in mcp_guard_configs {
 			if let Err(e) = self
 				.stores
 				.guard_registry
-				.update_backend(&backend_name, guards)
+				.update_backend_async(&backend_name, guards)
+				.await
 			{
 				warn!(
 					backend = %backend_name,
@@ -240,6 +257,7 @@ impl LocalClient {
 		Ok(PreviousState {
 			binds: next_binds,
 			discovery: next_discovery,
+			guard_backends,
 		})
 	}
 }
@@ -248,4 +266,86 @@ impl LocalClient {
 pub struct PreviousState {
 	pub binds: store::BindPreviousState,
 	pub discovery: store::DiscoveryPreviousState,
+	/// MCP backends whose guards are in the guard registry
+	pub guard_backends: BTreeSet<String>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn config_with_guard(guard_id: &str) -> String {
+		format!(
+			r#"
+binds:
+- port: 3000
+  listeners:
+  - routes:
+    - backends:
+      - mcp:
+          targets:
+          - name: time
+            stdio:
+              cmd: uvx
+          securityGuards:
+          - id: {guard_id}
+            type: tool_poisoning
+"#
+		)
+	}
+
+	fn guard_ids(stores: &Stores) -> Vec<Vec<String>> {
+		stores
+			.guard_registry
+			.effective_configs()
+			.into_values()
+			.map(|guards| guards.into_iter().map(|g| g.id).collect())
+			.collect()
+	}
+
+	async fn wait_for_guards(stores: &Stores, expected: Vec<Vec<String>>) {
+		for _ in 0..100 {
+			if guard_ids(stores) == expected {
+				return;
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+		assert_eq!(guard_ids(stores), expected);
+	}
+
+	#[tokio::test]
+	async fn test_config_file_change_reloads_guards() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("config.yaml");
+		std::fs::write(&path, config_with_guard("poisoning-v1")).unwrap();
+
+		let config = Arc::new(crate::config::parse_config("{}".to_string(), None).unwrap());
+		let stores = Stores::new();
+		let local_client = LocalClient {
+			client: client::Client::new(&config.dns, None, Default::default(), None),
+			gateway: ListenerTarget {
+				gateway_name: config.xds.gateway.clone(),
+				gateway_namespace: config.xds.namespace.clone(),
+				listener_name: None,
+			},
+			config,
+			cfg: ConfigSource::File(path.clone()),
+			stores: stores.clone(),
+		};
+		local_client.run().await.unwrap();
+		assert_eq!(guard_ids(&stores), vec![vec!["poisoning-v1".to_string()]]);
+
+		// An edit to the file is applied to the live guards
+		std::fs::write(&path, config_with_guard("poisoning-v2")).unwrap();
+		wait_for_guards(&stores, vec![vec!["poisoning-v2".to_string()]]).await;
+
+		// A broken file keeps the previous guards
+		std::fs::write(&path, "binds: [").unwrap();
+		tokio::time::sleep(Duration::from_millis(500)).await;
+		assert_eq!(guard_ids(&stores), vec![vec!["poisoning-v2".to_string()]]);
+
+		// Removing the backend removes its guards
+		std::fs::write(&path, "binds: []").unwrap();
+		wait_for_guards(&stores, vec![]).await;
+	}
 }
@@ -39,10 +39,13 @@ impl Default for Stores {
 
 impl Stores {
 	pub fn new() -> Stores {
+		Self::with_guard_registry(GuardExecutorRegistry::new())
+	}
+	pub fn with_guard_registry(guard_registry: GuardExecutorRegistry) -> Stores {
 		Stores {
 			discovery: discovery::StoreUpdater::new(Arc::new(RwLock::new(discovery::Store::new()))),
 			binds: binds::StoreUpdater::new(Arc::new(RwLock::new(binds::Store::new()))),
-			guard_registry: GuardExecutorRegistry::new(),
+			guard_registry,
 		}
 	}
 	pub fn read_binds(&self) -> std::sync::RwLockReadGuard<'_, store::BindStore> {
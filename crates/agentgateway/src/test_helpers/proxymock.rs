@@ -27,6 +27,7 @@ use wiremock::{Mock, MockServer, ResponseTemplate};
 use crate::http::backendtls::BackendTLS;
 use crate::http::{Body, Response};
 use crate::llm::AIProvider;
+use crate::mcp::security::McpSecurityGuard;
 use crate::proxy::Gateway;
 use crate::proxy::request_builder::RequestBuilder;
 use crate::store::Stores;
@@ -398,6 +399,27 @@ impl TestBind {
 		stateful: bool,
 		legacy_sse: bool,
 		policies: Vec<BackendPolicy>,
+	) -> Self {
+		self.with_mcp_backend_config(b, stateful, legacy_sse, policies, Vec::new())
+	}
+
+	pub fn with_mcp_backend_guards(
+		self,
+		b: SocketAddr,
+		stateful: bool,
+		legacy_sse: bool,
+		security_guards: Vec<McpSecurityGuard>,
+	) -> Self {
+		self.with_mcp_backend_config(b, stateful, legacy_sse, Vec::new(), security_guards)
+	}
+
+	fn with_mcp_backend_config(
+		self,
+		b: SocketAddr,
+		stateful: bool,
+		legacy_sse: bool,
+		policies: Vec<BackendPolicy>,
+		security_guards: Vec<McpSecurityGuard>,
 	) -> Self {
 		let opb = Backend::Opaque(
 			ResourceName::new(strng::format!("basic-{}", b), "".into()),
@@ -423,7 +445,14 @@ impl TestBind {
 				})],
 				stateful,
 				always_use_prefix: false,
-				security_guards: Vec::new(),
+				security_guards,
+				on_server_deny: Default::default(),
+				tool_dedup: None,
+				explain_denials: false,
+				guard_state: Default::default(),
+				notification_guards: Default::default(),
+				event_stream_guards: Default::default(),
+				tool_name_delimiter: crate::types::agent::default_tool_name_delimiter(),
 			},
 		);
 		{
@@ -472,6 +501,13 @@ impl TestBind {
 				stateful,
 				always_use_prefix: false,
 				security_guards: Vec::new(),
+				on_server_deny: Default::default(),
+				tool_dedup: None,
+				explain_denials: false,
+				guard_state: Default::default(),
+				notification_guards: Default::default(),
+				event_stream_guards: Default::default(),
+				tool_name_delimiter: crate::types::agent::default_tool_name_delimiter(),
 			},
 		);
 		{
@@ -58,6 +58,15 @@ pub struct TLSConnectionInfo {
 	pub negotiated_alpn: Option<Alpn>,
 }
 
+/// TLS parameters negotiated on a connection, such as `TLSv1_3` with
+/// `TLS13_AES_256_GCM_SHA384`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NegotiatedTls {
+	pub version: String,
+	pub cipher_suite: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct HBONEConnectionInfo {
 	pub hbone_address: SocketAddr,
@@ -128,6 +137,10 @@ impl hyper_util_fork::client::legacy::connect::Connection for Socket {
 			Some(Alpn::Http11) => con = con.negotiated_h1(),
 			_ => {},
 		}
+		// Exposed on every response served over this connection
+		if let Some(tls) = self.ext.get::<NegotiatedTls>() {
+			con = con.extra(tls.clone());
+		}
 		con
 	}
 }
@@ -204,6 +217,13 @@ impl Socket {
 				_ => None,
 			};
 			let (_, ssl) = tls.get_ref();
+			if let (Some(version), Some(suite)) = (ssl.protocol_version(), ssl.negotiated_cipher_suite())
+			{
+				ext.insert(NegotiatedTls {
+					version: format!("{version:?}"),
+					cipher_suite: format!("{:?}", suite.suite()),
+				});
+			}
 			TLSConnectionInfo {
 				src_identity: crate::transport::tls::identity_from_connection(ssl),
 				negotiated_alpn: ssl.alpn_protocol().map(Alpn::from),
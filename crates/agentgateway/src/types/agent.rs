@@ -26,7 +26,10 @@ use crate::http::{
 	HeaderOrPseudo, HeaderValue, ext_authz, ext_proc, filters, remoteratelimit, retry, timeout,
 };
 use crate::mcp::McpAuthorization;
-use crate::mcp::security::McpSecurityGuard;
+use crate::mcp::security::{
+	EventStreamGuarding, GuardStateScope, McpSecurityGuard, NotificationGuarding, OnServerDeny,
+	ToolDedupConfig,
+};
 use crate::telemetry::log::OrderedStringMap;
 use crate::types::discovery::{NamespacedHostname, Service};
 use crate::types::local::SimpleLocalBackend;
@@ -1095,6 +1098,31 @@ pub struct McpBackend {
 	/// Security guards to apply to this MCP backend
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub security_guards: Vec<McpSecurityGuard>,
+	/// What a multiplexed tools/list does when a guard denies one of the servers
+	#[serde(default)]
+	pub on_server_deny: OnServerDeny,
+	/// Collapse equivalent tools exposed by several servers of a multiplexed tools/list
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub tool_dedup: Option<ToolDedupConfig>,
+	/// Name the deciding guard (and those that passed before it) in guard denial errors
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub explain_denials: bool,
+	/// Whether sessions share guard state or each session keeps its own
+	#[serde(default)]
+	pub guard_state: GuardStateScope,
+	/// Whether guards evaluate server notifications
+	#[serde(default)]
+	pub notification_guards: NotificationGuarding,
+	/// Whether guards evaluate the messages servers push on the event stream
+	#[serde(default)]
+	pub event_stream_guards: EventStreamGuarding,
+	/// Separates target and resource name in multiplexed tool and prompt names
+	#[serde(default = "default_tool_name_delimiter")]
+	pub tool_name_delimiter: String,
+}
+
+pub(crate) fn default_tool_name_delimiter() -> String {
+	crate::mcp::DEFAULT_TOOL_NAME_DELIMITER.to_string()
 }
 
 impl McpBackend {
@@ -720,26 +720,45 @@ impl TryFrom<&proto::agent::Backend> for BackendWithPolicies {
 				let es = crate::types::loadbalancer::EndpointSet::new(provider_groups);
 				Backend::AI(name.into(), AIBackend { providers: es })
 			},
-			Some(proto::agent::backend::Kind::Mcp(m)) => Backend::MCP(
-				name.into(),
-				McpBackend {
-					targets: m
-						.targets
-						.iter()
-						.map(|t| McpTarget::try_from(t).map(Arc::new))
-						.collect::<Result<Vec<_>, _>>()?,
-					stateful: match m.stateful_mode() {
-						proto::agent::mcp_backend::StatefulMode::Stateful => true,
-						proto::agent::mcp_backend::StatefulMode::Stateless => false,
-					},
-					always_use_prefix: match m.prefix_mode() {
-						proto::agent::mcp_backend::PrefixMode::Always => true,
-						proto::agent::mcp_backend::PrefixMode::Conditional => false,
+			Some(proto::agent::backend::Kind::Mcp(m)) => {
+				let targets = m
+					.targets
+					.iter()
+					.map(|t| McpTarget::try_from(t).map(Arc::new))
+					.collect::<Result<Vec<_>, _>>()?;
+				let server_names: Vec<&str> = targets.iter().map(|t| t.name.as_str()).collect();
+				// Security guards are not yet supported in XDS proto; once they are, they get
+				// the same normalization as local config
+				let security_guards = crate::mcp::security::normalize_backend_guards(
+					&[],
+					&Default::default(),
+					&Default::default(),
+					&server_names,
+				)
+				.map_err(|e| ProtoError::Generic(e.to_string()))?;
+				Backend::MCP(
+					name.into(),
+					McpBackend {
+						targets,
+						stateful: match m.stateful_mode() {
+							proto::agent::mcp_backend::StatefulMode::Stateful => true,
+							proto::agent::mcp_backend::StatefulMode::Stateless => false,
+						},
+						always_use_prefix: match m.prefix_mode() {
+							proto::agent::mcp_backend::PrefixMode::Always => true,
+							proto::agent::mcp_backend::PrefixMode::Conditional => false,
+						},
+						security_guards,
+						on_server_deny: Default::default(),
+						tool_dedup: None,
+						explain_denials: false,
+						guard_state: Default::default(),
+						notification_guards: Default::default(),
+						event_stream_guards: Default::default(),
+						tool_name_delimiter: crate::types::agent::default_tool_name_delimiter(),
 					},
-					// Security guards are not yet supported in XDS proto
-					security_guards: Vec::new(),
-				},
-			),
+				)
+			},
 			None => {
 				return Err(ProtoError::Generic("unknown backend".to_string()));
 			},
@@ -16,7 +16,10 @@ use crate::http::transformation_cel::LocalTransformationConfig;
 use crate::http::{filters, retry, timeout};
 use crate::llm::{AIBackend, AIProvider, NamedAIProvider};
 use crate::mcp::McpAuthorization;
-use crate::mcp::security::McpSecurityGuard;
+use crate::mcp::security::{
+	EventStreamGuarding, GuardStateScope, McpSecurityGuard, NotificationGuarding, OnServerDeny,
+	ToolDedupConfig,
+};
 use crate::store::LocalWorkload;
 use crate::types::agent::{
 	A2aPolicy, Authorization, Backend, BackendKey, BackendPolicy, BackendReference,
@@ -342,6 +345,13 @@ impl LocalBackend {
 			LocalBackend::Opaque(tgt) => vec![Backend::Opaque(name, tgt.clone()).into()],
 			LocalBackend::Dynamic { .. } => vec![Backend::Dynamic(name, ()).into()],
 			LocalBackend::MCP(tgt) => {
+				let server_names: Vec<&str> = tgt.targets.iter().map(|t| t.name.as_str()).collect();
+				crate::mcp::security::check_server_count(&server_names, tgt.max_servers)?;
+				let prefixed =
+					server_names.len() != 1 || matches!(tgt.prefix_mode, Some(McpPrefixMode::Always));
+				if prefixed {
+					crate::mcp::check_tool_name_delimiter(&tgt.tool_name_delimiter, &server_names)?;
+				}
 				let mut targets = vec![];
 				let mut backends = vec![];
 				for (idx, t) in tgt.targets.iter().enumerate() {
@@ -392,6 +402,7 @@ impl LocalBackend {
 					McpStatefulMode::Stateless => false,
 					McpStatefulMode::Stateful => true,
 				};
+				crate::mcp::security::check_guard_state(tgt.guard_state, stateful)?;
 				let m = McpBackend {
 					targets,
 					stateful,
@@ -399,7 +410,19 @@ impl LocalBackend {
 						McpPrefixMode::Always => true,
 						McpPrefixMode::Conditional => false,
 					}),
-					security_guards: tgt.security_guards.clone(),
+					security_guards: crate::mcp::security::normalize_backend_guards(
+						&tgt.security_guards,
+						&tgt.per_server,
+						&tgt.pattern_lists,
+						&server_names,
+					)?,
+					on_server_deny: tgt.on_server_deny,
+					tool_dedup: tgt.tool_dedup.clone(),
+					explain_denials: tgt.explain_denials,
+					guard_state: tgt.guard_state,
+					notification_guards: tgt.notification_guards,
+					event_stream_guards: tgt.event_stream_guards,
+					tool_name_delimiter: tgt.tool_name_delimiter.clone(),
 				};
 				backends.push(Backend::MCP(name, m).into());
 				backends
@@ -457,6 +480,54 @@ pub struct LocalMcpBackend {
 	/// Security guards to apply to this MCP backend
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub security_guards: Vec<McpSecurityGuard>,
+	/// Named pattern lists that security guards can reference (e.g. `custom_patterns_ref`)
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub pattern_lists: HashMap<String, Vec<String>>,
+	/// Additional guards for individual upstream servers, keyed by target name.
+	/// A guard with the same id as a backend-wide guard replaces it for that server;
+	/// any other guard is added for that server only.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub per_server: HashMap<String, Vec<McpSecurityGuard>>,
+	/// What a multiplexed tools/list does when a guard denies one of the servers:
+	/// `fail_all` (default) fails the whole list, `skip_server` drops that server's tools,
+	/// and `quarantine` drops only the tools the deny names
+	#[serde(default)]
+	pub on_server_deny: OnServerDeny,
+	/// Collapse tools that several servers expose under the same normalized name with
+	/// compatible input schemas, keeping the one from the server first in `precedence`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub tool_dedup: Option<ToolDedupConfig>,
+	/// Add which guard denied a request, its category and the guards that passed before
+	/// it to the `data` of guard denial errors. Off by default, since it reveals the
+	/// configured policy to clients; enable it for trusted clients and debugging.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub explain_denials: bool,
+	/// Whether guard state such as rug pull baselines is `shared` (default) by all
+	/// sessions of this backend, or kept `per_session` so that one client's session
+	/// can't establish or shift the baseline another client's traffic is judged against
+	#[serde(default)]
+	pub guard_state: GuardStateScope,
+	/// Whether guards `scan` (default) the notifications servers send to clients, dropping
+	/// denied ones, or `bypass` them. Client notifications are never guarded.
+	#[serde(default)]
+	pub notification_guards: NotificationGuarding,
+	/// Whether guards `scan` (default) the requests and other messages servers push on the
+	/// standalone event stream, dropping denied ones, or `bypass` them
+	#[serde(default)]
+	pub event_stream_guards: EventStreamGuarding,
+	/// Most upstream servers (targets) this backend may multiplex (default: 32). A
+	/// config with more targets is rejected.
+	#[serde(default = "default_max_servers")]
+	pub max_servers: usize,
+	/// Separates target and tool (or prompt) name when names are prefixed with their
+	/// target (default: `_`). Target names must not contain it; choose e.g. `::` when
+	/// they contain underscores.
+	#[serde(default = "crate::types::agent::default_tool_name_delimiter")]
+	pub tool_name_delimiter: String,
+}
+
+fn default_max_servers() -> usize {
+	crate::mcp::security::DEFAULT_MAX_SERVERS
 }
 
 #[apply(schema_de!)]
@@ -237,6 +237,7 @@ export const GUARD_PHASES: Array<{ value: GuardPhase; label: string }> = [
   { value: "response", label: "Response" },
   { value: "tools_list", label: "Tools List" },
   { value: "tool_invoke", label: "Tool Invoke" },
+  { value: "initialize", label: "Initialize" },
 ];
 
 // Failure modes for UI selection
@@ -84,7 +84,13 @@ export interface GuardMeta {
   icon?: string;
 }
 
-export type GuardPhase = "connection" | "request" | "response" | "tools_list" | "tool_invoke";
+export type GuardPhase =
+  | "connection"
+  | "request"
+  | "response"
+  | "tools_list"
+  | "tool_invoke"
+  | "initialize";
 
 // =============================================================================
 // JSON Schema Types (Draft 2020-12 subset)